@@ -0,0 +1,293 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::Write;
+
+use log::LevelFilter;
+use r3bl_rs_utils::*;
+
+use crate::{common::DEFAULT_LOG_FILE_PATH, picker::AppInfo};
+
+pub const BIN_NAME: &str = "r3bl-cmdr";
+
+/// What to do, based on the args that are passed on the command line.
+#[derive(Clone, Debug)]
+pub enum CliCommand {
+  /// Print the help message.
+  Help,
+  /// Print the names of the apps.
+  List,
+  /// Show the picker (no app was named).
+  RunPicker,
+  /// Run the named app (w/out the picker), w/ the args that follow its name.
+  RunApp {
+    app: &'static AppInfo,
+    args: Vec<String>,
+  },
+}
+
+#[derive(Clone, Debug)]
+pub struct CliArgs {
+  pub command: CliCommand,
+  pub is_debug: bool,
+  pub maybe_log_file_path: Option<String>,
+}
+
+impl CliArgs {
+  /// Everything is logged in debug mode. Otherwise, only the info (& more severe) messages are
+  /// logged, & only if a log file is given.
+  pub fn get_log_level(&self) -> LevelFilter {
+    if self.is_debug {
+      LevelFilter::Trace
+    } else if self.maybe_log_file_path.is_some() {
+      LevelFilter::Info
+    } else {
+      LevelFilter::Off
+    }
+  }
+}
+
+/// Parse the args (w/out the binary's name). The options can go anywhere, unless they follow a
+/// `--`. The 1st arg that isn't an option is the name of the app, & the rest are passed to it.
+pub fn parse_cli_args(args: &[String], apps: &'static [AppInfo]) -> CommonResult<CliArgs> {
+  throws_with_return!({
+    let mut is_help = false;
+    let mut is_list = false;
+    let mut is_debug = false;
+    let mut maybe_log_file_path = None;
+    let mut maybe_app_name: Option<&str> = None;
+    let mut app_args = vec![];
+    let mut is_end_of_options = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+      let is_option = !is_end_of_options && arg.starts_with('-') && arg != "-";
+      if !is_option {
+        match maybe_app_name {
+          None => maybe_app_name = Some(arg),
+          Some(_) => app_args.push(arg.clone()),
+        }
+        continue;
+      }
+
+      match arg.as_str() {
+        "--" => is_end_of_options = true,
+        "-h" | "--help" => is_help = true,
+        "-l" | "--list" => is_list = true,
+        "-d" | "--debug" => is_debug = true,
+        "--log-file" => match iter.next() {
+          Some(file_path) => maybe_log_file_path = Some(file_path.clone()),
+          None => return Err("--log-file needs a file path".into()),
+        },
+        _ => match arg.strip_prefix("--log-file=") {
+          Some(file_path) => maybe_log_file_path = Some(file_path.to_string()),
+          None => return Err(format!("Unknown option: {}", arg).into()),
+        },
+      }
+    }
+
+    let command = if is_help {
+      CliCommand::Help
+    } else if is_list {
+      CliCommand::List
+    } else {
+      match maybe_app_name {
+        None => CliCommand::RunPicker,
+        Some(app_name) => {
+          let app = get_app(app_name, apps)?;
+          if app.args_usage.is_empty() && !app_args.is_empty() {
+            return Err(format!("{} doesn't take any args", app.name).into());
+          }
+          CliCommand::RunApp {
+            app,
+            args: app_args,
+          }
+        }
+      }
+    };
+
+    CliArgs {
+      command,
+      is_debug,
+      maybe_log_file_path,
+    }
+  });
+}
+
+fn get_app(app_name: &str, apps: &'static [AppInfo]) -> CommonResult<&'static AppInfo> {
+  match apps.iter().find(|app| app.name == app_name) {
+    Some(app) => Ok(app),
+    None => Err(
+      format!(
+        "Unknown app: {} (run `{} --list` to see the apps)",
+        app_name, BIN_NAME
+      )
+      .into(),
+    ),
+  }
+}
+
+/// The names of the apps, one per line, w/ their titles.
+pub fn get_app_list(apps: &[AppInfo]) -> String {
+  let name_width = apps.iter().map(|app| app.name.len()).max().unwrap_or(0);
+  let mut list = String::new();
+  for app in apps {
+    let _ = writeln!(list, "{:<name_width$}  {}", app.name, app.title);
+  }
+  list
+}
+
+pub fn get_help_msg(apps: &[AppInfo]) -> String {
+  let get_usage = |app: &AppInfo| {
+    format!("{} {}", app.name, app.args_usage)
+      .trim_end()
+      .to_string()
+  };
+  let usage_width = apps
+    .iter()
+    .map(|app| get_usage(app).len())
+    .max()
+    .unwrap_or(0);
+
+  let mut help_msg = String::new();
+  let _ = writeln!(help_msg, "Usage: {} [OPTIONS] [APP [ARGS]...]", BIN_NAME);
+  let _ = writeln!(help_msg);
+  let _ = writeln!(
+    help_msg,
+    "Runs the app that is named, or shows the picker (to choose one) if none is."
+  );
+  let _ = writeln!(help_msg);
+  let _ = writeln!(help_msg, "Apps:");
+  for app in apps {
+    let _ = writeln!(
+      help_msg,
+      "  {:<usage_width$}  {}",
+      get_usage(app),
+      app.title
+    );
+  }
+  let _ = writeln!(help_msg);
+  let _ = writeln!(help_msg, "Options:");
+  let _ = writeln!(help_msg, "  -l, --list         Print the names of the apps");
+  let _ = writeln!(
+    help_msg,
+    "  -d, --debug        Log everything (to {} by default)",
+    DEFAULT_LOG_FILE_PATH
+  );
+  let _ = writeln!(help_msg, "  --log-file PATH    Log to the file at PATH");
+  let _ = writeln!(help_msg, "  -h, --help         Print this help message");
+  help_msg
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  static APPS: [AppInfo; 2] = [
+    AppInfo {
+      name: "editor",
+      title: "Editor",
+      description: "",
+      args_usage: "[FILE]",
+      maybe_args_prompt: None,
+      is_full_screen: true,
+      run: |_| Box::pin(async { Ok(()) }),
+    },
+    AppInfo {
+      name: "layout",
+      title: "Layout",
+      description: "",
+      args_usage: "",
+      maybe_args_prompt: None,
+      is_full_screen: true,
+      run: |_| Box::pin(async { Ok(()) }),
+    },
+  ];
+
+  fn parse(args: &[&str]) -> CommonResult<CliArgs> {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    parse_cli_args(&args, &APPS)
+  }
+
+  fn get_run_app(cli_args: CliArgs) -> (&'static str, Vec<String>) {
+    match cli_args.command {
+      CliCommand::RunApp { app, args } => (app.name, args),
+      command => panic!("Expected an app to run, got: {:?}", command),
+    }
+  }
+
+  #[test]
+  fn test_no_args_runs_the_picker() {
+    let cli_args = parse(&[]).unwrap();
+    assert!(matches!(cli_args.command, CliCommand::RunPicker));
+    assert!(!cli_args.is_debug);
+    assert_eq!(cli_args.get_log_level(), LevelFilter::Off);
+  }
+
+  #[test]
+  fn test_the_app_gets_the_args_that_follow_its_name() {
+    let cli_args = parse(&["editor", "Cargo.toml"]).unwrap();
+    assert_eq!(
+      get_run_app(cli_args),
+      ("editor", vec!["Cargo.toml".to_string()])
+    );
+  }
+
+  #[test]
+  fn test_options_can_go_anywhere_until_double_dash() {
+    let cli_args = parse(&["editor", "-d", "--", "--debug"]).unwrap();
+    assert!(cli_args.is_debug);
+    assert_eq!(cli_args.get_log_level(), LevelFilter::Trace);
+    assert_eq!(
+      get_run_app(cli_args),
+      ("editor", vec!["--debug".to_string()])
+    );
+
+    // A lone `-` (eg: stdin) isn't an option.
+    let cli_args = parse(&["editor", "-"]).unwrap();
+    assert_eq!(get_run_app(cli_args), ("editor", vec!["-".to_string()]));
+  }
+
+  #[test]
+  fn test_the_log_file_can_be_given_in_either_form() {
+    let cli_args = parse(&["--log-file", "a.log"]).unwrap();
+    assert_eq!(cli_args.maybe_log_file_path.as_deref(), Some("a.log"));
+    assert_eq!(cli_args.get_log_level(), LevelFilter::Info);
+
+    let cli_args = parse(&["--log-file=b.log", "layout"]).unwrap();
+    assert_eq!(cli_args.maybe_log_file_path.as_deref(), Some("b.log"));
+    assert_eq!(get_run_app(cli_args), ("layout", vec![]));
+
+    assert!(parse(&["--log-file"]).is_err());
+  }
+
+  #[test]
+  fn test_help_or_list_is_printed_instead_of_running_an_app() {
+    let cli_args = parse(&["editor", "--help", "--list"]).unwrap();
+    assert!(matches!(cli_args.command, CliCommand::Help));
+
+    let cli_args = parse(&["-l", "editor"]).unwrap();
+    assert!(matches!(cli_args.command, CliCommand::List));
+  }
+
+  #[test]
+  fn test_bad_args_are_rejected() {
+    assert!(parse(&["--nope"]).is_err());
+    assert!(parse(&["nope"]).is_err());
+    assert!(parse(&["layout", "Cargo.toml"]).is_err());
+  }
+}
@@ -0,0 +1,26 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! The command line interface. An app can be run directly (w/out the picker) by passing its name
+//! (& its args) on the command line, eg: `r3bl-cmdr editor README.md`. The help message is made
+//! from the same registry of apps (see [crate::picker::AppInfo]) that the picker shows.
+
+// Attach sources.
+pub mod cli_args;
+
+// Re-export.
+pub use cli_args::*;
@@ -0,0 +1,98 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::io::Write;
+
+use r3bl_rs_utils::*;
+
+/// Set this env var (to anything) to also copy to the terminal's clipboard.
+pub const OSC52_ENV_VAR: &str = "R3BL_CMDR_OSC52";
+
+/// In-process clipboard, which is shared by all the editors in an app. When `is_osc52_enabled` is
+/// true, copied text is also sent to the terminal (see [write_to_terminal_clipboard]), so it can be
+/// pasted into other apps (even over ssh). This is off by default, since not all terminals support
+/// it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Clipboard {
+  pub maybe_text: Option<String>,
+  pub is_osc52_enabled: bool,
+}
+
+impl Clipboard {
+  pub fn copy(&mut self, text: &str) { self.maybe_text = Some(text.to_string()); }
+
+  pub fn get_text(&self) -> Option<&str> { self.maybe_text.as_deref() }
+}
+
+/// Copy the `text` to the terminal's clipboard, using the OSC 52 escape sequence.
+pub fn write_to_terminal_clipboard(text: &str) -> CommonResult<()> {
+  throws!({
+    let mut stdout = std::io::stdout();
+    stdout.write_all(get_osc52_sequence(text).as_bytes())?;
+    stdout.flush()?;
+  });
+}
+
+/// `ESC ] 52 ; c ; <base64 of the text> BEL`.
+pub fn get_osc52_sequence(text: &str) -> String {
+  format!("\x1b]52;c;{}\x07", encode_base64(text.as_bytes()))
+}
+
+/// Standard base64 (w/ padding), which is all that OSC 52 needs.
+fn encode_base64(bytes: &[u8]) -> String {
+  const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+  let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+  for chunk in bytes.chunks(3) {
+    let triple = (chunk[0] as u32) << 16
+      | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+      | *chunk.get(2).unwrap_or(&0) as u32;
+    for index in 0..4 {
+      if index <= chunk.len() {
+        encoded.push(ALPHABET[(triple >> (18 - 6 * index) & 0x3f) as usize] as char);
+      } else {
+        encoded.push('=');
+      }
+    }
+  }
+  encoded
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_base64_is_padded_to_4_chars() {
+    assert_eq!(encode_base64(b""), "");
+    assert_eq!(encode_base64(b"f"), "Zg==");
+    assert_eq!(encode_base64(b"fo"), "Zm8=");
+    assert_eq!(encode_base64(b"foo"), "Zm9v");
+    assert_eq!(encode_base64(b"foobar"), "Zm9vYmFy");
+  }
+
+  #[test]
+  fn test_base64_uses_the_whole_alphabet() {
+    assert_eq!(encode_base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+    assert_eq!(encode_base64("é\n".as_bytes()), "w6kK");
+  }
+
+  #[test]
+  fn test_osc52_sequence_wraps_the_base64_text() {
+    assert_eq!(get_osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+  }
+}
@@ -0,0 +1,53 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::path::PathBuf;
+
+use r3bl_rs_utils::*;
+
+/// The name of the dir (inside of the user's data dir) that this app keeps its data in.
+pub const APP_DATA_DIR_NAME: &str = "r3bl-cmdr";
+
+/// The dir that this app keeps its data in (which is created if it doesn't exist yet). It is inside
+/// of the user's data dir, which is `$XDG_DATA_HOME` (or `~/.local/share`) on Linux,
+/// `~/Library/Application Support` on macOS, & `%APPDATA%` on Windows.
+pub async fn get_app_data_dir() -> CommonResult<PathBuf> {
+  throws_with_return!({
+    let app_data_dir = get_user_data_dir()
+      .ok_or("Could not find the user's data dir")?
+      .join(APP_DATA_DIR_NAME);
+    tokio::fs::create_dir_all(&app_data_dir).await?;
+    app_data_dir
+  });
+}
+
+fn get_user_data_dir() -> Option<PathBuf> {
+  let get_dir_from_env = |name: &str| {
+    std::env::var_os(name)
+      .filter(|value| !value.is_empty())
+      .map(PathBuf::from)
+  };
+
+  if cfg!(windows) {
+    get_dir_from_env("APPDATA")
+  } else if cfg!(target_os = "macos") {
+    get_dir_from_env("HOME").map(|home_dir| home_dir.join("Library/Application Support"))
+  } else {
+    get_dir_from_env("XDG_DATA_HOME")
+      .or_else(|| get_dir_from_env("HOME").map(|home_dir| home_dir.join(".local/share")))
+  }
+}
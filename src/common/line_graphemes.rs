@@ -0,0 +1,107 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Helpers to work w/ a line of text as a sequence of grapheme clusters (what the user sees as a
+//! single "character", eg: `👨‍👩‍👧` or `é` made from `e` + a combining accent), each of which takes
+//! up 1 or 2 cols on the screen (eg: `😀` and `中` are 2 cols wide).
+//!
+//! Positions in a line are:
+//! - "grapheme index": index of a grapheme cluster in the line (where the caret can be).
+//! - "display col": col on the screen, relative to the start of the line.
+
+use r3bl_rs_utils::*;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+pub fn grapheme_count(line: &str) -> usize { line.graphemes(true).count() }
+
+/// Display width of a single grapheme cluster (0, 1 or 2 cols).
+pub fn grapheme_display_width(grapheme: &str) -> UnitType {
+  UnicodeWidthStr::width(grapheme) as UnitType
+}
+
+/// Display width of all the graphemes in the `text`.
+pub fn display_width(text: &str) -> UnitType {
+  text.graphemes(true).map(grapheme_display_width).sum()
+}
+
+/// Byte index of the grapheme at `grapheme_index`, or the length of the line if it is past the end.
+pub fn grapheme_byte_index(line: &str, grapheme_index: usize) -> usize {
+  line
+    .grapheme_indices(true)
+    .nth(grapheme_index)
+    .map(|(byte_index, _)| byte_index)
+    .unwrap_or(line.len())
+}
+
+/// The grapheme at `grapheme_index` (if any).
+pub fn grapheme_at(line: &str, grapheme_index: usize) -> Option<&str> {
+  line.graphemes(true).nth(grapheme_index)
+}
+
+/// Display col where the grapheme at `grapheme_index` starts.
+pub fn display_col_of_grapheme(line: &str, grapheme_index: usize) -> UnitType {
+  line
+    .graphemes(true)
+    .take(grapheme_index)
+    .map(grapheme_display_width)
+    .sum()
+}
+
+/// Index of the grapheme that is painted at `display_col` (or the grapheme count if the line is
+/// shorter). If `display_col` falls on the 2nd col of a wide grapheme, that grapheme's index is
+/// returned.
+pub fn grapheme_index_at_display_col(line: &str, display_col: UnitType) -> usize {
+  let mut col: UnitType = 0;
+  for (grapheme_index, grapheme) in line.graphemes(true).enumerate() {
+    let width = grapheme_display_width(grapheme);
+    if display_col < col + width {
+      return grapheme_index;
+    }
+    col += width;
+  }
+  grapheme_count(line)
+}
+
+/// The part of the line that is painted from `start_col` up to (at most) `width` cols. Wide
+/// graphemes that are cut by either edge are replaced w/ spaces, so that the other graphemes stay
+/// in their cols.
+pub fn clip_to_display_cols(line: &str, start_col: UnitType, width: UnitType) -> String {
+  let end_col = start_col + width;
+  let mut clipped = String::new();
+  let mut col: UnitType = 0;
+
+  for grapheme in line.graphemes(true) {
+    let grapheme_width = grapheme_display_width(grapheme);
+    let grapheme_end_col = col + grapheme_width;
+
+    if col >= end_col {
+      break;
+    }
+
+    if col >= start_col && grapheme_end_col <= end_col {
+      clipped.push_str(grapheme);
+    } else if grapheme_end_col > start_col {
+      let visible_cols = grapheme_end_col.min(end_col) - col.max(start_col);
+      clipped.push_str(&" ".repeat(visible_cols as usize));
+    }
+
+    col = grapheme_end_col;
+  }
+
+  clipped
+}
@@ -0,0 +1,91 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{fs::File,
+          io::Write,
+          sync::{atomic::{AtomicBool, Ordering},
+                 Mutex}};
+
+use log::{LevelFilter, Log, Metadata, Record};
+use r3bl_rs_utils::*;
+
+/// The file that is logged to, unless another one is passed on the command line.
+pub const DEFAULT_LOG_FILE_PATH: &str = "log.txt";
+
+/// Only the log records from this crate (& the r3bl crates) are written, not the ones from its
+/// dependencies.
+const LOG_TARGET_PREFIX: &str = "r3bl";
+
+static IS_LOGGING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// True if [init_logging] turned logging on. The log messages are only put together (eg: w/ a dump
+/// of the state) if it is, so they don't slow the apps down when nothing is logged.
+pub fn is_logging_enabled() -> bool { IS_LOGGING_ENABLED.load(Ordering::Relaxed) }
+
+/// Send the log records (including the ones from [log_no_err!]) to the file at the given path, at
+/// the given level. Nothing is logged (& the file isn't created) if the level is
+/// [LevelFilter::Off].
+///
+/// This has to be called once, before any app is run.
+pub fn init_logging(level: LevelFilter, file_path: &str) -> CommonResult<()> {
+  throws!({
+    // Turn off r3bl's own logger (which always logs to `log.txt`), so it doesn't try to take over
+    // the global logger.
+    try_to_set_log_level(LevelFilter::Off)?;
+
+    if level != LevelFilter::Off {
+      let file = File::create(file_path)?;
+      let logger = Box::leak(Box::new(FileLogger {
+        file: Mutex::new(file),
+      }));
+      log::set_logger(logger).map_err(|error| error.to_string())?;
+      log::set_max_level(level);
+      IS_LOGGING_ENABLED.store(true, Ordering::Relaxed);
+    }
+  });
+}
+
+struct FileLogger {
+  file: Mutex<File>,
+}
+
+impl Log for FileLogger {
+  fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+    metadata.target().starts_with(LOG_TARGET_PREFIX)
+  }
+
+  fn log(&self, record: &Record<'_>) {
+    if !self.enabled(record.metadata()) {
+      return;
+    }
+    if let Ok(mut file) = self.file.lock() {
+      let _ = writeln!(
+        file,
+        "{:<5} [{}] {}",
+        record.level(),
+        record.target(),
+        record.args()
+      );
+    }
+  }
+
+  fn flush(&self) {
+    if let Ok(mut file) = self.file.lock() {
+      let _ = file.flush();
+    }
+  }
+}
@@ -0,0 +1,36 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+// Attach sources.
+pub mod clipboard;
+pub mod data_dir;
+pub mod line_graphemes;
+pub mod logging;
+pub mod random_id;
+pub mod soft_wrap;
+pub mod undo_history;
+pub mod viewport;
+
+// Re-export.
+pub use clipboard::*;
+pub use data_dir::*;
+pub use line_graphemes::*;
+pub use logging::*;
+pub use random_id::*;
+pub use soft_wrap::*;
+pub use undo_history::*;
+pub use viewport::*;
@@ -0,0 +1,23 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{collections::hash_map::RandomState,
+          hash::{BuildHasher, Hasher}};
+
+/// A random id, which is different in each process (eg: to tell the sites in a collab session, or
+/// the peers on the local network, apart).
+pub fn generate_random_id() -> u64 { RandomState::new().build_hasher().finish() }
@@ -0,0 +1,151 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Soft wrap lays out a line that is wider than the box across multiple visual rows. The line
+//! itself isn't changed, each visual row is just a range of grapheme indices in it.
+
+use std::ops::Range;
+
+use r3bl_rs_utils::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::*;
+
+/// Split the `line` into visual rows that are at most `width` display cols wide. Rows are broken
+/// after whitespace when possible (a word that is wider than `width` is broken anyway). There is
+/// always at least one row, and the ranges are contiguous.
+///
+/// If the last row is completely full, then an empty row is added after it, so that a caret at the
+/// end of the line has a col to be painted in.
+pub fn wrap_line(line: &str, width: UnitType) -> Vec<Range<usize>> {
+  let graphemes: Vec<&str> = line.graphemes(true).collect();
+  // Nothing has been rendered yet, so don't wrap.
+  let width = if width == 0 { UnitType::MAX } else { width };
+
+  let mut rows = vec![];
+  let mut row_start = 0;
+  let mut row_width: UnitType = 0;
+  let mut maybe_word_start: Option<usize> = None;
+
+  let mut index = 0;
+  while index < graphemes.len() {
+    let grapheme_width = grapheme_display_width(graphemes[index]);
+
+    // Doesn't fit, so move the word that this grapheme is in (or just this grapheme) to a new row.
+    if row_width + grapheme_width > width && index > row_start {
+      let row_end = match maybe_word_start {
+        Some(word_start) if word_start > row_start && !is_whitespace(graphemes[index]) => {
+          word_start
+        }
+        _ => index,
+      };
+      rows.push(row_start..row_end);
+      row_start = row_end;
+      row_width = graphemes[row_start..index]
+        .iter()
+        .map(|grapheme| grapheme_display_width(grapheme))
+        .sum();
+      maybe_word_start = None;
+      continue;
+    }
+
+    row_width += grapheme_width;
+    if is_whitespace(graphemes[index]) {
+      maybe_word_start = Some(index + 1);
+    }
+    index += 1;
+  }
+
+  rows.push(row_start..graphemes.len());
+  if row_width >= width && !graphemes.is_empty() {
+    rows.push(graphemes.len()..graphemes.len());
+  }
+  rows
+}
+
+/// All the visual rows for `lines`, each w/ the index of the line that it is in.
+pub fn wrap_lines(lines: &[String], width: UnitType) -> Vec<(usize, Range<usize>)> {
+  lines
+    .iter()
+    .enumerate()
+    .flat_map(|(line_index, line)| {
+      wrap_line(line, width)
+        .into_iter()
+        .map(move |row| (line_index, row))
+    })
+    .collect()
+}
+
+/// Index of the row (returned by [wrap_line]) that a caret at `grapheme_index` is painted in. A
+/// caret at the end of a row (that isn't the last one) is painted at the start of the next row.
+pub fn wrapped_row_index_of(rows: &[Range<usize>], grapheme_index: usize) -> usize {
+  rows
+    .iter()
+    .position(|row| grapheme_index < row.end)
+    .unwrap_or(rows.len() - 1)
+}
+
+/// The text of a visual `row` in the `line`.
+pub fn wrapped_row_text<'a>(line: &'a str, row: &Range<usize>) -> &'a str {
+  &line[grapheme_byte_index(line, row.start)..grapheme_byte_index(line, row.end)]
+}
+
+fn is_whitespace(grapheme: &str) -> bool { grapheme.chars().all(char::is_whitespace) }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_rows_are_broken_after_whitespace() {
+    assert_eq!(wrap_line("hello world", 6), vec![0..6, 6..11]);
+    assert_eq!(wrap_line("ab cd ef", 4), vec![0..3, 3..6, 6..8]);
+  }
+
+  #[test]
+  fn test_a_word_wider_than_the_row_is_broken() {
+    assert_eq!(wrap_line("abcdefgh", 3), vec![0..3, 3..6, 6..8]);
+  }
+
+  #[test]
+  fn test_a_full_last_row_is_followed_by_an_empty_one() {
+    assert_eq!(wrap_line("abc", 3), vec![0..3, 3..3]);
+    assert_eq!(wrap_line("hello world", 5), vec![0..5, 5..6, 6..11, 11..11]);
+  }
+
+  #[test]
+  fn test_wide_graphemes_take_2_cols() {
+    assert_eq!(wrap_line("日本語", 4), vec![0..2, 2..3]);
+    assert_eq!(wrap_line("a日本", 2), vec![0..1, 1..2, 2..3, 3..3]);
+  }
+
+  #[test]
+  fn test_empty_lines_and_zero_width_are_1_row() {
+    assert_eq!(wrap_line("", 10), vec![0..0]);
+    // Nothing has been rendered yet.
+    assert_eq!(wrap_line("hello world", 0), vec![0..11]);
+  }
+
+  #[test]
+  fn test_a_caret_at_the_end_of_a_row_is_painted_in_the_next_one() {
+    let rows = wrap_line("hello world", 6);
+    assert_eq!(wrapped_row_index_of(&rows, 5), 0);
+    assert_eq!(wrapped_row_index_of(&rows, 6), 1);
+    assert_eq!(wrapped_row_index_of(&rows, 11), 1);
+    assert_eq!(wrapped_row_text("hello world", &rows[1]), "world");
+  }
+}
@@ -0,0 +1,168 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{collections::VecDeque, sync::Arc};
+
+pub const DEFAULT_UNDO_HISTORY_DEPTH: usize = 100;
+
+/// Undo & redo stacks of snapshots of some state `S`, which can be kept in any Redux store state
+/// (and updated by its reducer):
+/// 1. Before a change is made, the reducer calls [UndoHistory::push] w/ the state as it was.
+/// 2. [UndoHistory::undo] & [UndoHistory::redo] take the current state & return the one to replace
+///    it with.
+///
+/// The snapshots are shared ([Arc]), so cloning the store state doesn't copy the whole history. At
+/// most `max_depth` snapshots are kept, the oldest ones are dropped first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UndoHistory<S>
+where
+  S: Clone,
+{
+  past: VecDeque<Arc<S>>,
+  future: Vec<Arc<S>>,
+  max_depth: usize,
+  /// True if the last change that was pushed can be merged w/ the next one.
+  is_coalescing: bool,
+}
+
+impl<S> Default for UndoHistory<S>
+where
+  S: Clone,
+{
+  fn default() -> Self { Self::new(DEFAULT_UNDO_HISTORY_DEPTH) }
+}
+
+impl<S> UndoHistory<S>
+where
+  S: Clone,
+{
+  pub fn new(max_depth: usize) -> Self {
+    Self {
+      past: VecDeque::new(),
+      future: Vec::new(),
+      max_depth,
+      is_coalescing: false,
+    }
+  }
+
+  /// Save `prior_state` (the state before a change is made) so that the change can be undone. A
+  /// run of changes that are all `coalesce` (eg: typing chars) is undone in a single step, since
+  /// only the state before the first change in the run is saved.
+  pub fn push(&mut self, prior_state: &S, coalesce: bool) {
+    self.future.clear();
+
+    if !(coalesce && self.is_coalescing) {
+      self.past.push_back(Arc::new(prior_state.clone()));
+      if self.past.len() > self.max_depth {
+        self.past.pop_front();
+      }
+    }
+
+    self.is_coalescing = coalesce;
+  }
+
+  /// Stop merging changes, so the next change that is pushed starts a new undo step (eg: when the
+  /// caret is moved between typing runs).
+  pub fn break_coalescing(&mut self) { self.is_coalescing = false; }
+
+  /// Returns the state to restore (if any), and saves `current_state` so it can be redone.
+  pub fn undo(&mut self, current_state: &S) -> Option<S> {
+    let prior_state = self.past.pop_back()?;
+    self.future.push(Arc::new(current_state.clone()));
+    self.is_coalescing = false;
+    Some(prior_state.as_ref().clone())
+  }
+
+  /// Returns the state to restore (if any), and saves `current_state` so it can be undone again.
+  pub fn redo(&mut self, current_state: &S) -> Option<S> {
+    let next_state = self.future.pop()?;
+    self.past.push_back(Arc::new(current_state.clone()));
+    self.is_coalescing = false;
+    Some(next_state.as_ref().clone())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_undo_then_redo_restores_each_state() {
+    let mut history = UndoHistory::default();
+    history.push(&"a", false);
+    history.push(&"ab", false);
+
+    assert_eq!(history.undo(&"abc"), Some("ab"));
+    assert_eq!(history.undo(&"ab"), Some("a"));
+    assert_eq!(history.undo(&"a"), None);
+    assert_eq!(history.redo(&"a"), Some("ab"));
+    assert_eq!(history.redo(&"ab"), Some("abc"));
+    assert_eq!(history.redo(&"abc"), None);
+  }
+
+  #[test]
+  fn test_push_clears_the_redo_stack() {
+    let mut history = UndoHistory::default();
+    history.push(&"a", false);
+    assert_eq!(history.undo(&"ab"), Some("a"));
+
+    history.push(&"a", false);
+    assert_eq!(history.redo(&"ax"), None);
+  }
+
+  #[test]
+  fn test_a_run_of_coalesced_changes_is_undone_in_one_step() {
+    let mut history = UndoHistory::default();
+    history.push(&"", true);
+    history.push(&"a", true);
+    history.push(&"ab", true);
+
+    assert_eq!(history.undo(&"abc"), Some(""));
+    assert_eq!(history.undo(&""), None);
+  }
+
+  #[test]
+  fn test_breaking_coalescing_starts_a_new_step() {
+    let mut history = UndoHistory::default();
+    history.push(&"", true);
+    history.push(&"a", true);
+    history.break_coalescing();
+    history.push(&"ab", true);
+    history.push(&"abc", true);
+    // A change that isn't coalesced ends the run too.
+    history.push(&"abcd", false);
+    history.push(&"abc", true);
+
+    assert_eq!(history.undo(&"abcx"), Some("abc"));
+    assert_eq!(history.undo(&"abc"), Some("abcd"));
+    assert_eq!(history.undo(&"abcd"), Some("ab"));
+    assert_eq!(history.undo(&"ab"), Some(""));
+    assert_eq!(history.undo(&""), None);
+  }
+
+  #[test]
+  fn test_the_oldest_states_are_dropped_past_the_max_depth() {
+    let mut history = UndoHistory::new(2);
+    history.push(&1, false);
+    history.push(&2, false);
+    history.push(&3, false);
+
+    assert_eq!(history.undo(&4), Some(3));
+    assert_eq!(history.undo(&3), Some(2));
+    assert_eq!(history.undo(&2), None);
+  }
+}
@@ -0,0 +1,152 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::ops::Range;
+
+use r3bl_rs_utils::*;
+
+use super::*;
+
+/// The part of a (possibly much larger) list of lines that is painted inside a box. `offset` is the
+/// line index (`row`) & display col (`col`) that is painted at the top left corner of the box.
+///
+/// The size of the box is passed in (instead of being saved here), since it is only known at render
+/// time & can change between renders (eg: when the terminal is resized).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Viewport {
+  pub offset: Position,
+}
+
+// Scroll.
+impl Viewport {
+  /// Scroll (if needed) so that the cols from `start_col` up to (not including) `end_col` in `row`
+  /// are visible. Eg: a caret on a wide grapheme needs all of its cols to be visible.
+  pub fn scroll_into_view(
+    &mut self, row: UnitType, start_col: UnitType, end_col: UnitType, viewport_size: Size,
+  ) {
+    if viewport_size.rows == 0 || viewport_size.cols == 0 {
+      return;
+    }
+
+    if row < self.offset.row {
+      self.offset.row = row;
+    } else if row >= self.offset.row + viewport_size.rows {
+      self.offset.row = row - viewport_size.rows + 1;
+    }
+
+    if start_col < self.offset.col {
+      self.offset.col = start_col;
+    } else if end_col > self.offset.col + viewport_size.cols {
+      self.offset.col = end_col - viewport_size.cols;
+    }
+  }
+
+  /// Scroll up by a page (the number of rows in the viewport).
+  pub fn page_up(&mut self, viewport_size: Size) {
+    self.offset.row = self.offset.row.saturating_sub(viewport_size.rows);
+  }
+
+  /// Scroll down by a page (the number of rows in the viewport), but not past the last page of
+  /// `line_count` lines.
+  pub fn page_down(&mut self, line_count: usize, viewport_size: Size) {
+    let last_page_row = (line_count as UnitType).saturating_sub(viewport_size.rows);
+    if self.offset.row < last_page_row {
+      self.offset.row = (self.offset.row + viewport_size.rows).min(last_page_row);
+    }
+  }
+}
+
+// Map buffer positions (line index & display col) to the box.
+impl Viewport {
+  pub fn contains(&self, pos: Position, viewport_size: Size) -> bool {
+    pos.row >= self.offset.row
+      && pos.col >= self.offset.col
+      && pos.row - self.offset.row < viewport_size.rows
+      && pos.col - self.offset.col < viewport_size.cols
+  }
+
+  /// Position relative to the box origin, of a `pos` that the viewport [Viewport::contains].
+  pub fn box_position_of(&self, pos: Position) -> Position {
+    position!(col: pos.col - self.offset.col, row: pos.row - self.offset.row)
+  }
+
+  /// The parts of `lines` that fit inside the viewport, one per row of the box. If `soft_wrap` is
+  /// true, then the lines are laid out across visual rows that fit in the width of the viewport
+  /// (see [wrap_lines]), and `offset.row` is the index of a visual row (`offset.col` is not used,
+  /// since nothing is wider than the viewport).
+  pub fn visible_line_slices(
+    &self, lines: &[String], viewport_size: Size, soft_wrap: bool,
+  ) -> Vec<VisibleLineSlice> {
+    let to_visible_line_slice =
+      |row_index: usize, line_index: usize, byte_range: Range<usize>| VisibleLineSlice {
+        row_index: row_index as UnitType,
+        line_index,
+        byte_range,
+        start_col: if soft_wrap { 0 } else { self.offset.col },
+      };
+
+    if soft_wrap {
+      wrap_lines(lines, viewport_size.cols)
+        .into_iter()
+        .skip(self.offset.row as usize)
+        .take(viewport_size.rows as usize)
+        .enumerate()
+        .map(|(row_index, (line_index, wrapped_row))| {
+          let line = &lines[line_index];
+          let byte_range = grapheme_byte_index(line, wrapped_row.start)
+            ..grapheme_byte_index(line, wrapped_row.end);
+          to_visible_line_slice(row_index, line_index, byte_range)
+        })
+        .collect()
+    } else {
+      (self.offset.row as usize..lines.len())
+        .take(viewport_size.rows as usize)
+        .enumerate()
+        .map(|(row_index, line_index)| {
+          to_visible_line_slice(row_index, line_index, 0..lines[line_index].len())
+        })
+        .collect()
+    }
+  }
+
+  /// The text of each [Viewport::visible_line_slices], clipped to the visible display cols. Each
+  /// item is the row (relative to the box origin) to paint it at & the visible text.
+  pub fn visible_lines(
+    &self, lines: &[String], viewport_size: Size, soft_wrap: bool,
+  ) -> Vec<(UnitType, String)> {
+    self
+      .visible_line_slices(lines, viewport_size, soft_wrap)
+      .into_iter()
+      .map(|slice| {
+        let text = &lines[slice.line_index][slice.byte_range];
+        let visible_text = clip_to_display_cols(text, slice.start_col, viewport_size.cols);
+        (slice.row_index, visible_text)
+      })
+      .collect()
+  }
+}
+
+/// The part of a line that is painted in a row of the viewport: the bytes in `byte_range` of the
+/// line at `line_index`, painted from display col `start_col` (relative to the start of
+/// `byte_range`) onwards, at `row_index` (relative to the box origin).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VisibleLineSlice {
+  pub row_index: UnitType,
+  pub line_index: usize,
+  pub byte_range: Range<usize>,
+  pub start_col: UnitType,
+}
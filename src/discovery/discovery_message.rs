@@ -0,0 +1,107 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{fmt::{Display, Formatter},
+          net::{IpAddr, SocketAddr}};
+
+use r3bl_rs_utils::*;
+use serde::{Deserialize, Serialize};
+
+use crate::common::*;
+
+/// A running `r3bl-cmdr` instance, as it announces itself on the local network.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PeerInfo {
+  /// Random id, which tells instances w/ the same user & host apart.
+  pub instance_id: u64,
+  pub host_name: String,
+  pub user_name: String,
+  pub shared_apps: Vec<SharedApp>,
+}
+
+/// An app that a peer shares (eg: an editor that is hosted in a collab session), & the address
+/// that other peers connect to in order to use it. An app that listens on every interface (eg:
+/// `0.0.0.0:8421`) doesn't know which of its IPs the other peers can reach, so they use the IP that
+/// its announcement came from (see [PeerInfo::fill_in_sender_ip]).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SharedApp {
+  pub name: String,
+  pub addr: String,
+}
+
+impl PeerInfo {
+  /// This instance, w/ a new random id. The user name comes from the `USER` env var.
+  pub fn new_local(shared_apps: Vec<SharedApp>) -> Self {
+    Self {
+      instance_id: generate_random_id(),
+      host_name: gethostname::gethostname().to_string_lossy().into(),
+      user_name: std::env::var("USER").unwrap_or_else(|_| "anonymous".into()),
+      shared_apps,
+    }
+  }
+
+  /// Replace the unspecified IP (eg: `0.0.0.0`) in the addresses of the shared apps w/ the IP of
+  /// the peer that sent the announcement.
+  pub fn fill_in_sender_ip(&mut self, sender_ip: IpAddr) {
+    for shared_app in self.shared_apps.iter_mut() {
+      if let Ok(addr) = shared_app.addr.parse::<SocketAddr>() {
+        if addr.ip().is_unspecified() {
+          shared_app.addr = SocketAddr::new(sender_ip, addr.port()).to_string();
+        }
+      }
+    }
+  }
+
+  pub fn get_shared_app(&self, name: &str) -> Option<&SharedApp> {
+    self
+      .shared_apps
+      .iter()
+      .find(|shared_app| shared_app.name == name)
+  }
+}
+
+impl Display for PeerInfo {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}@{}", self.user_name, self.host_name)
+  }
+}
+
+/// What the peers send to each other. Each message is one packet of JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiscoveryMessage {
+  /// Sent periodically, while the peer is running.
+  Announce(PeerInfo),
+  /// Sent when the peer stops, so that it is removed right away (instead of when it expires).
+  Goodbye { instance_id: u64 },
+}
+
+impl DiscoveryMessage {
+  pub fn to_packet(&self) -> CommonResult<Vec<u8>> {
+    throws_with_return!(serde_json::to_vec(self)?);
+  }
+
+  pub fn from_packet(packet: &[u8]) -> CommonResult<Self> {
+    throws_with_return!(serde_json::from_slice(packet)?);
+  }
+
+  pub fn get_instance_id(&self) -> u64 {
+    match self {
+      DiscoveryMessage::Announce(peer_info) => peer_info.instance_id,
+      DiscoveryMessage::Goodbye { instance_id } => *instance_id,
+    }
+  }
+}
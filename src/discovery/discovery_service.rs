@@ -0,0 +1,259 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{sync::Arc,
+          time::{Duration, Instant}};
+
+use r3bl_rs_utils::*;
+use tokio::{sync::watch, task::JoinHandle};
+
+use super::*;
+use crate::common::*;
+
+/// How often a running instance announces itself.
+pub const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(2);
+/// Peers that haven't announced themselves for this long (ie: that missed a few announcements in
+/// a row) are removed.
+pub const PEER_TTL: Duration = Duration::from_secs(7);
+
+pub type SharedDiscoveryTransport = Arc<dyn DiscoveryTransport>;
+
+/// Announces the local peer every `interval`, until it is stopped.
+pub struct Announcer {
+  transport: SharedDiscoveryTransport,
+  instance_id: u64,
+  task: JoinHandle<()>,
+}
+
+impl Announcer {
+  pub fn start(
+    transport: SharedDiscoveryTransport, local_peer: PeerInfo, interval: Duration,
+  ) -> Self {
+    let instance_id = local_peer.instance_id;
+    let task = tokio::spawn({
+      let transport = transport.clone();
+      let message = DiscoveryMessage::Announce(local_peer);
+      async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+          interval.tick().await;
+          if let Err(error) = send_message(transport.as_ref(), &message).await {
+            call_if_true!(
+              is_logging_enabled(),
+              log_no_err!(ERROR, "📡 Could not announce this peer: {}", error)
+            );
+          }
+        }
+      }
+    });
+    Self {
+      transport,
+      instance_id,
+      task,
+    }
+  }
+
+  /// Stop announcing, & tell the other peers that this one is gone.
+  pub async fn stop(self) -> CommonResult<()> {
+    self.task.abort();
+    let message = DiscoveryMessage::Goodbye {
+      instance_id: self.instance_id,
+    };
+    send_message(self.transport.as_ref(), &message).await
+  }
+}
+
+impl Drop for Announcer {
+  fn drop(&mut self) { self.task.abort(); }
+}
+
+/// Keeps a [PeerDirectory] up to date w/ the announcements that are received (ignoring the ones
+/// from the local peer), & publishes the list of peers whenever it changes. Unspecified IPs in the
+/// announced addresses are replaced w/ the IP of the sender (see [PeerInfo::fill_in_sender_ip]).
+pub struct PeerBrowser {
+  receiver: watch::Receiver<Vec<PeerInfo>>,
+  task: JoinHandle<()>,
+}
+
+impl PeerBrowser {
+  pub fn start(transport: SharedDiscoveryTransport, local_instance_id: u64, ttl: Duration) -> Self {
+    let (sender, receiver) = watch::channel(vec![]);
+    let task = tokio::spawn(async move {
+      let mut directory = PeerDirectory::default();
+      let mut expiry_interval = tokio::time::interval(ttl / 2);
+      loop {
+        let is_changed = tokio::select! {
+          result = transport.recv_packet() => match result {
+            Ok((packet, sender_ip)) => match DiscoveryMessage::from_packet(&packet) {
+              Ok(mut message) if message.get_instance_id() != local_instance_id => {
+                if let DiscoveryMessage::Announce(peer_info) = &mut message {
+                  peer_info.fill_in_sender_ip(sender_ip);
+                }
+                directory.apply_message(&message, Instant::now())
+              }
+              // Packets from the local peer, or from something else on the network.
+              _ => false,
+            },
+            Err(error) => {
+              call_if_true!(
+                is_logging_enabled(),
+                log_no_err!(ERROR, "📡 Stopped looking for peers: {}", error)
+              );
+              break;
+            }
+          },
+          _ = expiry_interval.tick() => directory.remove_expired(Instant::now(), ttl),
+        };
+        if is_changed && sender.send(directory.get_peers()).is_err() {
+          break;
+        }
+      }
+    });
+    Self { receiver, task }
+  }
+
+  pub fn get_peers(&self) -> Vec<PeerInfo> { self.receiver.borrow().clone() }
+
+  /// Wait for the list of peers to change. Returns [None] if the browser has stopped.
+  pub async fn get_changed_peers(&mut self) -> Option<Vec<PeerInfo>> {
+    self.receiver.changed().await.ok()?;
+    Some(self.get_peers())
+  }
+}
+
+impl Drop for PeerBrowser {
+  fn drop(&mut self) { self.task.abort(); }
+}
+
+pub async fn send_message(
+  transport: &dyn DiscoveryTransport, message: &DiscoveryMessage,
+) -> CommonResult<()> {
+  throws!({
+    transport.send_packet(&message.to_packet()?).await?;
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const INTERVAL: Duration = Duration::from_millis(20);
+
+  async fn wait_for_peers(browser: &mut PeerBrowser, count: usize) -> Vec<PeerInfo> {
+    tokio::time::timeout(Duration::from_secs(5), async {
+      loop {
+        let peers = browser.get_peers();
+        if peers.len() == count {
+          return peers;
+        }
+        browser.get_changed_peers().await.unwrap();
+      }
+    })
+    .await
+    .unwrap()
+  }
+
+  fn create_peer(user_name: &str, shared_apps: Vec<SharedApp>) -> PeerInfo {
+    PeerInfo {
+      user_name: user_name.into(),
+      ..PeerInfo::new_local(shared_apps)
+    }
+  }
+
+  #[tokio::test]
+  async fn test_peers_find_each_other_but_not_themselves() {
+    let network = InMemoryNetwork::default();
+    let alice = create_peer("alice", vec![]);
+    let bob = create_peer(
+      "bob",
+      vec![SharedApp {
+        name: "editor".into(),
+        addr: "192.168.1.5:8421".into(),
+      }],
+    );
+
+    let mut alice_browser = PeerBrowser::start(
+      Arc::new(network.create_transport()),
+      alice.instance_id,
+      PEER_TTL,
+    );
+    let mut bob_browser = PeerBrowser::start(
+      Arc::new(network.create_transport()),
+      bob.instance_id,
+      PEER_TTL,
+    );
+    let _alice_announcer = Announcer::start(
+      Arc::new(network.create_transport()),
+      alice.clone(),
+      INTERVAL,
+    );
+    let _bob_announcer =
+      Announcer::start(Arc::new(network.create_transport()), bob.clone(), INTERVAL);
+
+    assert_eq!(wait_for_peers(&mut alice_browser, 1).await, vec![bob]);
+    assert_eq!(wait_for_peers(&mut bob_browser, 1).await, vec![alice]);
+  }
+
+  #[tokio::test]
+  async fn test_unspecified_ip_is_replaced_w_the_senders() {
+    let network = InMemoryNetwork::default();
+    let shared_app = |addr: &str| SharedApp {
+      name: "editor".into(),
+      addr: addr.into(),
+    };
+    let alice = create_peer("alice", vec![shared_app("0.0.0.0:8421")]);
+    let mut bob_browser = PeerBrowser::start(Arc::new(network.create_transport()), 0, PEER_TTL);
+
+    let _alice_announcer = Announcer::start(
+      Arc::new(network.create_transport()),
+      alice.clone(),
+      INTERVAL,
+    );
+    let peers = wait_for_peers(&mut bob_browser, 1).await;
+    assert_eq!(peers[0].shared_apps, vec![shared_app("127.0.0.1:8421")]);
+  }
+
+  #[tokio::test]
+  async fn test_peer_that_says_goodbye_is_removed() {
+    let network = InMemoryNetwork::default();
+    let alice = create_peer("alice", vec![]);
+    let mut bob_browser = PeerBrowser::start(Arc::new(network.create_transport()), 0, PEER_TTL);
+
+    let alice_announcer = Announcer::start(
+      Arc::new(network.create_transport()),
+      alice.clone(),
+      INTERVAL,
+    );
+    assert_eq!(wait_for_peers(&mut bob_browser, 1).await, vec![alice]);
+
+    alice_announcer.stop().await.unwrap();
+    assert_eq!(wait_for_peers(&mut bob_browser, 0).await, vec![]);
+  }
+
+  #[test]
+  fn test_peer_that_stops_announcing_expires() {
+    let alice = create_peer("alice", vec![]);
+    let mut directory = PeerDirectory::default();
+    let start = Instant::now();
+
+    assert!(directory.apply_message(&DiscoveryMessage::Announce(alice.clone()), start));
+    assert!(!directory.apply_message(&DiscoveryMessage::Announce(alice), start));
+    assert!(!directory.remove_expired(start + PEER_TTL, PEER_TTL));
+    assert!(directory.remove_expired(start + PEER_TTL * 2, PEER_TTL));
+    assert!(directory.get_peers().is_empty());
+  }
+}
@@ -0,0 +1,34 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Local peer discovery (in the spirit of mDNS). Each running `r3bl-cmdr` instance periodically
+//! announces itself (its host name, user & the apps that it shares) over a [DiscoveryTransport],
+//! and keeps a [PeerDirectory] of the other instances that it hears from. Peers that stop
+//! announcing themselves expire. The transport is pluggable: [UdpMulticastTransport] is used on a
+//! real network, and `InMemoryNetwork` is used to run many peers in one process (in tests).
+
+// Attach sources.
+pub mod discovery_message;
+pub mod discovery_service;
+pub mod peer_directory;
+pub mod transport;
+
+// Re-export.
+pub use discovery_message::*;
+pub use discovery_service::*;
+pub use peer_directory::*;
+pub use transport::*;
@@ -0,0 +1,66 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{collections::HashMap,
+          time::{Duration, Instant}};
+
+use super::*;
+
+/// The peers that have been heard from, & when each one last announced itself.
+#[derive(Clone, Debug, Default)]
+pub struct PeerDirectory {
+  peers: HashMap<u64, (PeerInfo, Instant)>,
+}
+
+impl PeerDirectory {
+  /// Returns true if the list of peers (or what one of them shares) changed.
+  pub fn apply_message(&mut self, message: &DiscoveryMessage, now: Instant) -> bool {
+    match message {
+      DiscoveryMessage::Announce(peer_info) => {
+        let maybe_old_peer_info = self
+          .peers
+          .insert(peer_info.instance_id, (peer_info.clone(), now))
+          .map(|(old_peer_info, _)| old_peer_info);
+        maybe_old_peer_info.as_ref() != Some(peer_info)
+      }
+      DiscoveryMessage::Goodbye { instance_id } => self.peers.remove(instance_id).is_some(),
+    }
+  }
+
+  /// Remove the peers that haven't announced themselves for longer than `ttl`. Returns true if
+  /// any were removed.
+  pub fn remove_expired(&mut self, now: Instant, ttl: Duration) -> bool {
+    let count = self.peers.len();
+    self
+      .peers
+      .retain(|_, (_, last_seen)| now.duration_since(*last_seen) <= ttl);
+    self.peers.len() != count
+  }
+
+  /// Sorted by user, then host (so the list doesn't jump around as announcements come in).
+  pub fn get_peers(&self) -> Vec<PeerInfo> {
+    let mut peers: Vec<PeerInfo> = self
+      .peers
+      .values()
+      .map(|(peer_info, _)| peer_info.clone())
+      .collect();
+    peers.sort_by(|a, b| {
+      (&a.user_name, &a.host_name, a.instance_id).cmp(&(&b.user_name, &b.host_name, b.instance_id))
+    });
+    peers
+  }
+}
@@ -0,0 +1,147 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddrV4};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+
+/// Multicast group that the instances on the local network announce themselves to.
+pub const DISCOVERY_MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(239, 255, 84, 21);
+pub const DISCOVERY_PORT: u16 = 8422;
+/// Packets that are bigger than this are truncated (& then fail to parse).
+pub const MAX_PACKET_SIZE: usize = 8 * 1024;
+
+/// Sends packets to every peer (that uses the same transport), & receives the packets that they
+/// send. Packets may be lost, & a transport may also receive the packets that it sent itself.
+#[async_trait]
+pub trait DiscoveryTransport: Send + Sync {
+  async fn send_packet(&self, packet: &[u8]) -> CommonResult<()>;
+
+  /// Wait for the next packet, & return it w/ the IP address of the peer that sent it.
+  async fn recv_packet(&self) -> CommonResult<(Vec<u8>, IpAddr)>;
+}
+
+/// UDP multicast on the local network. Many instances (even on the same host) can bind to the
+/// [DISCOVERY_PORT] at the same time.
+#[derive(Debug)]
+pub struct UdpMulticastTransport {
+  socket: UdpSocket,
+  group_addr: SocketAddrV4,
+}
+
+impl UdpMulticastTransport {
+  pub fn bind() -> CommonResult<Self> {
+    throws_with_return!({
+      let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+      socket.set_reuse_address(true)?;
+      socket.bind(&SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT).into())?;
+      socket.join_multicast_v4(&DISCOVERY_MULTICAST_ADDR, &Ipv4Addr::UNSPECIFIED)?;
+      socket.set_multicast_loop_v4(true)?;
+      socket.set_nonblocking(true)?;
+      Self {
+        socket: UdpSocket::from_std(socket.into())?,
+        group_addr: SocketAddrV4::new(DISCOVERY_MULTICAST_ADDR, DISCOVERY_PORT),
+      }
+    });
+  }
+}
+
+#[async_trait]
+impl DiscoveryTransport for UdpMulticastTransport {
+  async fn send_packet(&self, packet: &[u8]) -> CommonResult<()> {
+    throws!({
+      self.socket.send_to(packet, self.group_addr).await?;
+    });
+  }
+
+  async fn recv_packet(&self) -> CommonResult<(Vec<u8>, IpAddr)> {
+    throws_with_return!({
+      let mut packet = vec![0; MAX_PACKET_SIZE];
+      let (len, sender_addr) = self.socket.recv_from(&mut packet).await?;
+      packet.truncate(len);
+      (packet, sender_addr.ip())
+    });
+  }
+}
+
+#[cfg(test)]
+pub use in_memory::*;
+
+/// Only the tests run many peers in one process.
+#[cfg(test)]
+mod in_memory {
+  use async_trait::async_trait;
+  use r3bl_rs_utils::*;
+  use tokio::sync::{broadcast::{self, error::RecvError},
+                    Mutex};
+
+  use super::*;
+
+  /// A network that only exists in this process. Every transport that is created from it receives
+  /// the packets that are sent by all of them (including itself), like multicast w/ loopback.
+  #[derive(Clone, Debug)]
+  pub struct InMemoryNetwork {
+    sender: broadcast::Sender<Vec<u8>>,
+  }
+
+  impl Default for InMemoryNetwork {
+    fn default() -> Self {
+      let (sender, _) = broadcast::channel(64);
+      Self { sender }
+    }
+  }
+
+  impl InMemoryNetwork {
+    pub fn create_transport(&self) -> InMemoryTransport {
+      InMemoryTransport {
+        sender: self.sender.clone(),
+        receiver: Mutex::new(self.sender.subscribe()),
+      }
+    }
+  }
+
+  #[derive(Debug)]
+  pub struct InMemoryTransport {
+    sender: broadcast::Sender<Vec<u8>>,
+    receiver: Mutex<broadcast::Receiver<Vec<u8>>>,
+  }
+
+  #[async_trait]
+  impl DiscoveryTransport for InMemoryTransport {
+    async fn send_packet(&self, packet: &[u8]) -> CommonResult<()> {
+      throws!({
+        self.sender.send(packet.to_vec())?;
+      });
+    }
+
+    /// Packets that this transport fell too far behind on are dropped (like a real network would).
+    /// Every packet is sent from localhost.
+    async fn recv_packet(&self) -> CommonResult<(Vec<u8>, IpAddr)> {
+      let mut receiver = self.receiver.lock().await;
+      loop {
+        match receiver.recv().await {
+          Ok(packet) => return Ok((packet, Ipv4Addr::LOCALHOST.into())),
+          Err(RecvError::Lagged(_)) => continue,
+          Err(RecvError::Closed) => return Err("The in-memory network is closed".into()),
+        }
+      }
+    }
+  }
+}
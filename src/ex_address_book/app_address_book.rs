@@ -0,0 +1,293 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{path::PathBuf, sync::Arc};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::sync::RwLock;
+
+use super::*;
+use crate::common::*;
+
+// Constants for the ids.
+const CONTAINER_ID: &str = "container";
+pub const CONTACT_LIST_ID: &str = "contact_list";
+pub const CONTACT_DETAIL_ID: &str = "contact_detail";
+
+/// Async trait object that implements the [TWApp] trait. The list of contacts is on the left, &
+/// the selected contact (or the form that a contact is edited in) is on the right. The route in the
+/// state decides which of them has focus.
+#[derive(Default)]
+pub struct AppAddressBook {
+  pub component_registry: ComponentRegistry<AddressBookState, AddressBookAction>,
+  pub has_focus: HasFocus,
+  /// The contacts are saved here (once the store is shared, see [ContactsFileSaver]).
+  pub maybe_contacts_file_path: Option<PathBuf>,
+}
+
+impl AppAddressBook {
+  pub fn new(contacts_file_path: PathBuf) -> Self {
+    Self {
+      maybe_contacts_file_path: Some(contacts_file_path),
+      ..Default::default()
+    }
+  }
+}
+
+#[async_trait]
+impl TWApp<AddressBookState, AddressBookAction> for AppAddressBook {
+  async fn app_handle_event(
+    &mut self, input_event: &TWInputEvent, state: &AddressBookState,
+    shared_store: &SharedStore<AddressBookState, AddressBookAction>, _terminal_size: Size,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      self.set_focus_from_route(&state.route);
+
+      // If component has focus, then route input_event to it. Return its propagation enum.
+      if let Some(shared_component_has_focus) =
+        self.component_registry.get_has_focus(&self.has_focus)
+      {
+        let result_event_propagation = shared_component_has_focus
+          .write()
+          .await
+          .handle_event(input_event, state, shared_store)
+          .await?;
+        return Ok(result_event_propagation);
+      };
+
+      // input_event not handled, propagate it.
+      EventPropagation::Propagate
+    });
+  }
+
+  async fn app_render(
+    &mut self, state: &AddressBookState,
+    shared_store: &SharedStore<AddressBookState, AddressBookAction>, window_size: Size,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      self.start_saving_contacts_once(state, shared_store);
+      self.create_components_populate_registry();
+      self.set_focus_from_route(&state.route);
+
+      let mut surface = surface_start! {
+        stylesheet: style_helpers::create_stylesheet()?,
+        pos: (0, 0).into(),
+        size: (window_size.cols, window_size.rows.saturating_sub(1)).into(), // Leave row at bottom for message.
+      };
+
+      self
+        .create_main_container(&mut surface, state, shared_store)
+        .await?;
+
+      surface.surface_end()?;
+
+      status_bar_helpers::render(&mut surface.render_buffer, window_size, state);
+
+      call_if_true!(is_logging_enabled(), {
+        log_no_err!(
+          INFO,
+          "📇 AppAddressBook::render -> size, state: {} {}",
+          window_size,
+          state
+        );
+      });
+      surface.render_buffer
+    });
+  }
+}
+
+// Handle component registry and rendering.
+mod component_construction_and_rendering {
+  use super::*;
+
+  impl AppAddressBook {
+    /// The contacts in the `state` (when it is 1st rendered) are the ones that were loaded from
+    /// the file.
+    pub fn start_saving_contacts_once(
+      &mut self, state: &AddressBookState,
+      shared_store: &SharedStore<AddressBookState, AddressBookAction>,
+    ) {
+      if let Some(file_path) = self.maybe_contacts_file_path.take() {
+        let contacts_file_saver =
+          ContactsFileSaver::new(file_path, state.contacts.clone(), shared_store.clone());
+        let shared_store = shared_store.clone();
+        tokio::spawn(async move {
+          shared_store
+            .write()
+            .await
+            .add_subscriber(Arc::new(RwLock::new(contacts_file_saver)))
+            .await;
+        });
+      }
+    }
+
+    pub fn create_components_populate_registry(&mut self) {
+      if self.component_registry.id_does_not_exist(CONTACT_LIST_ID) {
+        let shared_component = Arc::new(RwLock::new(ContactListComponent));
+        self
+          .component_registry
+          .put(CONTACT_LIST_ID, shared_component);
+      }
+
+      if self.component_registry.id_does_not_exist(CONTACT_DETAIL_ID) {
+        let shared_component = Arc::new(RwLock::new(ContactDetailComponent));
+        self
+          .component_registry
+          .put(CONTACT_DETAIL_ID, shared_component);
+      }
+    }
+
+    /// The list has focus on the list route, & the detail has it on the others.
+    pub fn set_focus_from_route(&mut self, route: &AddressBookRoute) {
+      match route {
+        AddressBookRoute::List => self.has_focus.set_id(CONTACT_LIST_ID),
+        AddressBookRoute::Detail | AddressBookRoute::Edit(_) => {
+          self.has_focus.set_id(CONTACT_DETAIL_ID)
+        }
+      }
+    }
+
+    /// Main container CONTAINER_ID.
+    pub async fn create_main_container<'a>(
+      &mut self, surface: &mut Surface, state: &'a AddressBookState,
+      shared_store: &'a SharedStore<AddressBookState, AddressBookAction>,
+    ) -> CommonResult<()> {
+      throws!({
+        surface.box_start(box_props! {
+          id:   CONTAINER_ID,
+          dir:  Direction::Horizontal,
+          size: (100, 100).try_into()?,
+        })?;
+        self.create_left_col(surface, state, shared_store).await?;
+        self.create_right_col(surface, state, shared_store).await?;
+        surface.box_end()?;
+      });
+    }
+
+    /// Left column CONTACT_LIST_ID.
+    async fn create_left_col<'a>(
+      &mut self, surface: &mut Surface, state: &'a AddressBookState,
+      shared_store: &'a SharedStore<AddressBookState, AddressBookAction>,
+    ) -> CommonResult<()> {
+      throws!({
+        make_box! {
+          in:     surface,
+          id:     CONTACT_LIST_ID,
+          dir:    Direction::Vertical,
+          size:   (40, 100).try_into()?,
+          styles: ["style_contact_list"],
+          render: {
+            from:         self.component_registry,
+            has_focus:    self.has_focus,
+            state:        state,
+            shared_store: shared_store
+          }
+        }
+      });
+    }
+
+    /// Right column CONTACT_DETAIL_ID.
+    async fn create_right_col<'a>(
+      &mut self, surface: &mut Surface, state: &'a AddressBookState,
+      shared_store: &'a SharedStore<AddressBookState, AddressBookAction>,
+    ) -> CommonResult<()> {
+      throws!({
+        make_box! {
+          in:     surface,
+          id:     CONTACT_DETAIL_ID,
+          dir:    Direction::Vertical,
+          size:   (60, 100).try_into()?,
+          styles: ["style_contact_detail"],
+          render: {
+            from:         self.component_registry,
+            has_focus:    self.has_focus,
+            state:        state,
+            shared_store: shared_store
+          }
+        }
+      });
+    }
+  }
+}
+
+mod style_helpers {
+  use super::*;
+
+  pub fn create_stylesheet() -> CommonResult<Stylesheet> {
+    throws_with_return!({
+      stylesheet! {
+        style! {
+          id: style_contact_list
+          margin: 1
+        },
+        style! {
+          id: style_contact_detail
+          margin: 1
+        }
+      }
+    })
+  }
+}
+
+mod status_bar_helpers {
+  use super::*;
+
+  /// Shows the status message (if there is one), or the keys that work on the current route, at
+  /// the bottom row of the screen.
+  pub fn render(queue: &mut TWCommandQueue, size: Size, state: &AddressBookState) {
+    let st_vec = match (&state.status_message, &state.route) {
+      (Some(message), _) => styled_texts! {
+        styled_text! { message, style!(attrib: [bold]) }
+      },
+      (None, AddressBookRoute::List) => styled_texts! {
+        styled_text! { "Hints:",                  style!(attrib: [dim])       },
+        styled_text! { " Ctrl+q : Exit ⛔ ",      style!(attrib: [bold])      },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " ↑ / ↓ : select ",        style!(attrib: [underline]) },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " Enter : show ",          style!(attrib: [underline]) },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " a / e / d : add / edit / delete ", style!(attrib: [underline]) }
+      },
+      (None, AddressBookRoute::Detail) => styled_texts! {
+        styled_text! { "Hints:",                  style!(attrib: [dim])       },
+        styled_text! { " Ctrl+q : Exit ⛔ ",      style!(attrib: [bold])      },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " Esc : back ",            style!(attrib: [underline]) },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " e / d : edit / delete ", style!(attrib: [underline]) }
+      },
+      (None, AddressBookRoute::Edit(_)) => styled_texts! {
+        styled_text! { "Hints:",                  style!(attrib: [dim])       },
+        styled_text! { " Enter : save ",          style!(attrib: [bold])      },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " Esc : cancel ",          style!(attrib: [underline]) },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " Tab / Shift+Tab : next / prev field ", style!(attrib: [underline]) }
+      },
+    };
+
+    let display_width = st_vec.unicode_string().display_width;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    let center: Position = (col_center, row_bottom).into();
+
+    *queue += TWCommand::MoveCursorPositionAbs(center);
+    *queue += st_vec.render();
+  }
+}
@@ -0,0 +1,149 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use serde::{Deserialize, Serialize};
+
+pub type ContactId = u64;
+
+/// A contact in the address book.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Contact {
+  pub id: ContactId,
+  pub name: String,
+  pub email: String,
+  pub phone: String,
+}
+
+impl Contact {
+  pub fn get_field(&self, field: ContactField) -> &str {
+    match field {
+      ContactField::Name => &self.name,
+      ContactField::Email => &self.email,
+      ContactField::Phone => &self.phone,
+    }
+  }
+}
+
+/// The fields of a [Contact] that can be edited (in this order).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ContactField {
+  #[default]
+  Name,
+  Email,
+  Phone,
+}
+
+impl ContactField {
+  pub const ALL: [ContactField; 3] = [ContactField::Name, ContactField::Email, ContactField::Phone];
+
+  pub fn get_label(&self) -> &'static str {
+    match self {
+      ContactField::Name => "Name",
+      ContactField::Email => "Email",
+      ContactField::Phone => "Phone",
+    }
+  }
+
+  /// The field after this one, wrapping around to the 1st one.
+  pub fn get_next(&self) -> ContactField {
+    let index = ContactField::ALL
+      .iter()
+      .position(|it| it == self)
+      .unwrap_or_default();
+    ContactField::ALL[(index + 1) % ContactField::ALL.len()]
+  }
+
+  /// The field before this one, wrapping around to the last one.
+  pub fn get_prev(&self) -> ContactField {
+    let index = ContactField::ALL
+      .iter()
+      .position(|it| it == self)
+      .unwrap_or_default();
+    ContactField::ALL[(index + ContactField::ALL.len() - 1) % ContactField::ALL.len()]
+  }
+}
+
+/// The form that a new contact (if `maybe_contact_id` is [None]) or an existing one is edited in.
+/// Text is typed into the end of the `focused_field`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct ContactForm {
+  pub maybe_contact_id: Option<ContactId>,
+  pub name: String,
+  pub email: String,
+  pub phone: String,
+  pub focused_field: ContactField,
+}
+
+/// Changes to the [ContactForm] as it is typed into.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContactFormEvent {
+  InsertChar(char),
+  DeleteChar,
+  FocusNextField,
+  FocusPrevField,
+}
+
+impl ContactForm {
+  pub fn new_from_contact(contact: &Contact) -> Self {
+    Self {
+      maybe_contact_id: Some(contact.id),
+      name: contact.name.clone(),
+      email: contact.email.clone(),
+      phone: contact.phone.clone(),
+      focused_field: ContactField::default(),
+    }
+  }
+
+  pub fn get_field(&self, field: ContactField) -> &str {
+    match field {
+      ContactField::Name => &self.name,
+      ContactField::Email => &self.email,
+      ContactField::Phone => &self.phone,
+    }
+  }
+
+  fn get_field_mut(&mut self, field: ContactField) -> &mut String {
+    match field {
+      ContactField::Name => &mut self.name,
+      ContactField::Email => &mut self.email,
+      ContactField::Phone => &mut self.phone,
+    }
+  }
+
+  pub fn apply_event(&mut self, event: &ContactFormEvent) {
+    match event {
+      ContactFormEvent::InsertChar(typed_char) => {
+        self.get_field_mut(self.focused_field).push(*typed_char)
+      }
+      ContactFormEvent::DeleteChar => {
+        self.get_field_mut(self.focused_field).pop();
+      }
+      ContactFormEvent::FocusNextField => self.focused_field = self.focused_field.get_next(),
+      ContactFormEvent::FocusPrevField => self.focused_field = self.focused_field.get_prev(),
+    }
+  }
+
+  /// The contact w/ the values in the form (trimmed). A new contact gets the `new_contact_id`.
+  pub fn to_contact(&self, new_contact_id: ContactId) -> Contact {
+    Contact {
+      id: self.maybe_contact_id.unwrap_or(new_contact_id),
+      name: self.name.trim().to_string(),
+      email: self.email.trim().to_string(),
+      phone: self.phone.trim().to_string(),
+    }
+  }
+}
@@ -0,0 +1,203 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::{common::*, host_components::*};
+
+/// Shows the selected contact, or the form that a contact is edited in (on the edit route).
+#[derive(Debug, Clone, Default)]
+pub struct ContactDetailComponent;
+
+#[async_trait]
+impl Component<AddressBookState, AddressBookAction> for ContactDetailComponent {
+  /// Handle following input events (and consume them) on the detail route:
+  /// - Esc, Left     : go back to the list
+  /// - `e`           : edit the contact
+  /// - `d`, Delete   : delete the contact
+  ///
+  /// And on the edit route:
+  /// - typed chars, Backspace : edit the focused field
+  /// - Tab, Down / BackTab, Up : focus the next / previous field
+  /// - Enter : save the contact
+  /// - Esc   : go back w/out saving
+  async fn handle_event(
+    &mut self, input_event: &TWInputEvent, state: &AddressBookState,
+    shared_store: &SharedStore<AddressBookState, AddressBookAction>,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      let maybe_action = match &state.route {
+        AddressBookRoute::Edit(form) => get_edit_action(input_event, form),
+        _ => get_detail_action(input_event, state.get_selected_contact()),
+      };
+
+      let mut event_consumed = false;
+      if let Some(action) = maybe_action {
+        spawn_and_consume_event!(event_consumed, shared_store, action);
+      }
+
+      if event_consumed {
+        EventPropagation::Consumed
+      } else {
+        EventPropagation::Propagate
+      }
+    });
+  }
+
+  async fn render(
+    &mut self, has_focus: &HasFocus, current_box: &TWBox, state: &AddressBookState,
+    _shared_store: &SharedStore<AddressBookState, AddressBookAction>,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      let is_focused = has_focus.does_current_box_have_focus(current_box);
+      let mut queue = tw_command_queue!();
+
+      match (&state.route, state.get_selected_contact()) {
+        (AddressBookRoute::Edit(form), _) => {
+          render_helpers::render_form(&mut queue, current_box, form)
+        }
+        (_, Some(contact)) => {
+          render_helpers::render_contact(&mut queue, current_box, contact, is_focused)
+        }
+        (_, None) => render_row(
+          &mut queue,
+          current_box,
+          0,
+          "No contact is selected",
+          style!(attrib: [dim]),
+        ),
+      }
+
+      queue
+    });
+  }
+}
+
+fn get_detail_action(
+  input_event: &TWInputEvent, maybe_contact: Option<&Contact>,
+) -> Option<AddressBookAction> {
+  let contact = maybe_contact?;
+  match input_event {
+    TWInputEvent::DisplayableKeypress('e') => Some(AddressBookAction::Navigate(
+      AddressBookRoute::Edit(ContactForm::new_from_contact(contact)),
+    )),
+    TWInputEvent::DisplayableKeypress('d') => Some(AddressBookAction::DeleteContact(contact.id)),
+    TWInputEvent::NonDisplayableKeypress(Keypress {
+      maybe_modifier_keys: None,
+      non_modifier_key: NonModifierKey::Special(special_key),
+    }) => match special_key {
+      SpecialKey::Esc | SpecialKey::Left => {
+        Some(AddressBookAction::Navigate(AddressBookRoute::List))
+      }
+      SpecialKey::Delete => Some(AddressBookAction::DeleteContact(contact.id)),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn get_edit_action(input_event: &TWInputEvent, form: &ContactForm) -> Option<AddressBookAction> {
+  match input_event {
+    TWInputEvent::DisplayableKeypress(typed_char) => Some(AddressBookAction::EditForm(
+      ContactFormEvent::InsertChar(*typed_char),
+    )),
+    // Shift+Tab can come w/ the shift modifier, or w/out it.
+    TWInputEvent::NonDisplayableKeypress(Keypress {
+      non_modifier_key: NonModifierKey::Special(special_key),
+      ..
+    }) => match special_key {
+      SpecialKey::Backspace => Some(AddressBookAction::EditForm(ContactFormEvent::DeleteChar)),
+      SpecialKey::Tab | SpecialKey::Down => Some(AddressBookAction::EditForm(
+        ContactFormEvent::FocusNextField,
+      )),
+      SpecialKey::BackTab | SpecialKey::Up => Some(AddressBookAction::EditForm(
+        ContactFormEvent::FocusPrevField,
+      )),
+      SpecialKey::Enter => {
+        let contact = form.to_contact(generate_random_id());
+        Some(match form.maybe_contact_id {
+          Some(_) => AddressBookAction::UpdateContact(contact),
+          None => AddressBookAction::AddContact(contact),
+        })
+      }
+      SpecialKey::Esc => Some(AddressBookAction::Navigate(match form.maybe_contact_id {
+        Some(_) => AddressBookRoute::Detail,
+        None => AddressBookRoute::List,
+      })),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+mod render_helpers {
+  use super::*;
+
+  /// Width of the labels (& the space after them).
+  const LABEL_WIDTH: usize = 7;
+
+  pub fn render_contact(
+    queue: &mut TWCommandQueue, current_box: &TWBox, contact: &Contact, is_focused: bool,
+  ) {
+    let title_style = if is_focused {
+      style!(attrib: [bold, reverse])
+    } else {
+      style!(attrib: [bold])
+    };
+    render_row(queue, current_box, 0, &contact.name, title_style);
+
+    for (index, field) in ContactField::ALL.into_iter().enumerate() {
+      let text = format!(
+        "{:width$}{}",
+        field.get_label(),
+        contact.get_field(field),
+        width = LABEL_WIDTH
+      );
+      let row = 2 + index as UnitType;
+      render_row(queue, current_box, row, &text, Style::default());
+    }
+  }
+
+  /// The focused field is highlighted, & has a caret at the end of its text.
+  pub fn render_form(queue: &mut TWCommandQueue, current_box: &TWBox, form: &ContactForm) {
+    let title = match form.maybe_contact_id {
+      Some(_) => "Edit contact",
+      None => "New contact",
+    };
+    render_row(queue, current_box, 0, title, style!(attrib: [bold]));
+
+    for (index, field) in ContactField::ALL.into_iter().enumerate() {
+      let is_focused = field == form.focused_field;
+      let text = format!(
+        "{:width$}{}{}",
+        field.get_label(),
+        form.get_field(field),
+        if is_focused { "▏" } else { "" },
+        width = LABEL_WIDTH
+      );
+      let style = if is_focused {
+        style!(attrib: [reverse])
+      } else {
+        Style::default()
+      };
+      let row = 2 + index as UnitType;
+      render_row(queue, current_box, row, &text, style);
+    }
+  }
+}
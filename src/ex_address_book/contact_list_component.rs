@@ -0,0 +1,154 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::{common::*, host_components::*};
+
+/// Lists the contacts (scrolled so that the selected one is visible).
+#[derive(Debug, Clone, Default)]
+pub struct ContactListComponent;
+
+#[async_trait]
+impl Component<AddressBookState, AddressBookAction> for ContactListComponent {
+  /// Handle following input events (and consume them):
+  /// - Up, Down      : select the previous / next contact
+  /// - Enter, Right  : show the selected contact
+  /// - `a`           : add a new contact
+  /// - `e`           : edit the selected contact
+  /// - `d`, Delete   : delete the selected contact
+  async fn handle_event(
+    &mut self, input_event: &TWInputEvent, state: &AddressBookState,
+    shared_store: &SharedStore<AddressBookState, AddressBookAction>,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      let mut event_consumed = false;
+      let maybe_selected_contact = state.get_selected_contact();
+
+      if let TWInputEvent::DisplayableKeypress(typed_char) = input_event {
+        match (typed_char, maybe_selected_contact) {
+          ('a', _) => spawn_and_consume_event!(
+            event_consumed,
+            shared_store,
+            AddressBookAction::Navigate(AddressBookRoute::Edit(ContactForm::default()))
+          ),
+          ('e', Some(contact)) => spawn_and_consume_event!(
+            event_consumed,
+            shared_store,
+            AddressBookAction::Navigate(AddressBookRoute::Edit(ContactForm::new_from_contact(
+              contact
+            )))
+          ),
+          ('d', Some(contact)) => spawn_and_consume_event!(
+            event_consumed,
+            shared_store,
+            AddressBookAction::DeleteContact(contact.id)
+          ),
+          _ => {}
+        }
+      }
+
+      if let TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: None,
+        non_modifier_key: NonModifierKey::Special(special_key),
+      }) = input_event
+      {
+        match (special_key, maybe_selected_contact) {
+          (SpecialKey::Up, _) => {
+            spawn_and_consume_event!(event_consumed, shared_store, AddressBookAction::SelectPrev)
+          }
+          (SpecialKey::Down, _) => {
+            spawn_and_consume_event!(event_consumed, shared_store, AddressBookAction::SelectNext)
+          }
+          (SpecialKey::Enter | SpecialKey::Right, Some(_)) => spawn_and_consume_event!(
+            event_consumed,
+            shared_store,
+            AddressBookAction::Navigate(AddressBookRoute::Detail)
+          ),
+          (SpecialKey::Delete, Some(contact)) => spawn_and_consume_event!(
+            event_consumed,
+            shared_store,
+            AddressBookAction::DeleteContact(contact.id)
+          ),
+          _ => {}
+        }
+      }
+
+      if event_consumed {
+        EventPropagation::Consumed
+      } else {
+        EventPropagation::Propagate
+      }
+    });
+  }
+
+  async fn render(
+    &mut self, has_focus: &HasFocus, current_box: &TWBox, state: &AddressBookState,
+    _shared_store: &SharedStore<AddressBookState, AddressBookAction>,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      let box_origin_pos = current_box.origin_pos; // Adjusted for style margin (if any).
+      let box_bounding_size = current_box.bounding_size; // Adjusted for style margin (if any).
+      let is_focused = has_focus.does_current_box_have_focus(current_box);
+      let mut queue = tw_command_queue!();
+
+      let title = format!("Contacts ({})", state.contacts.len());
+      render_row(&mut queue, current_box, 0, &title, style!(attrib: [bold]));
+
+      if state.contacts.is_empty() {
+        let hint = "No contacts yet, press 'a' to add one";
+        render_row(&mut queue, current_box, 2, hint, style!(attrib: [dim]));
+        return Ok(queue);
+      }
+
+      // The rows after the title, scrolled so that the selected contact is on the last one (if it
+      // would be below it otherwise).
+      let max_row_count = (box_bounding_size.rows as usize).saturating_sub(2).max(1);
+      let first_index = (state.selected_index + 1).saturating_sub(max_row_count);
+      for (row_index, (index, contact)) in state
+        .contacts
+        .iter()
+        .enumerate()
+        .skip(first_index)
+        .take(max_row_count)
+        .enumerate()
+      {
+        let is_selected = index == state.selected_index;
+        let text = format!("{} {}", if is_selected { "❯" } else { " " }, contact.name);
+        let style = match (is_selected, is_focused) {
+          (true, true) => style!(attrib: [bold, reverse]),
+          (true, false) => style!(attrib: [bold]),
+          _ => Style::default(),
+        };
+        let row = 2 + row_index as UnitType;
+        render_row(&mut queue, current_box, row, &text, style);
+      }
+
+      call_if_true!(is_logging_enabled(), {
+        log_no_err!(
+          INFO,
+          "📇 ContactListComponent::render -> box_origin_pos: {:?}, box_bounding_size: {:?}",
+          box_origin_pos,
+          box_bounding_size
+        );
+      });
+      queue
+    });
+  }
+}
@@ -0,0 +1,170 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+
+// Create a new store and attach the reducer.
+pub async fn create_store() -> Store<AddressBookState, AddressBookAction> {
+  let mut store: Store<AddressBookState, AddressBookAction> = Store::default();
+  store.add_reducer(AddressBookReducer::new()).await;
+  store
+}
+
+/// Action.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+#[allow(dead_code)]
+pub enum AddressBookAction {
+  /// The contacts were loaded from the file.
+  SetContacts(Vec<Contact>),
+  AddContact(Contact),
+  UpdateContact(Contact),
+  DeleteContact(ContactId),
+  SelectPrev,
+  SelectNext,
+  Navigate(AddressBookRoute),
+  /// Change the form (if the edit route is shown).
+  EditForm(ContactFormEvent),
+  /// Show a message in the status bar (eg: once the contacts are saved).
+  SetStatusMessage(String),
+  #[default]
+  Noop,
+}
+
+impl Display for AddressBookAction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{:?}", self) }
+}
+
+/// Which part of the address book the input events go to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum AddressBookRoute {
+  /// Choose a contact in the list.
+  #[default]
+  List,
+  /// Look at the selected contact.
+  Detail,
+  /// Edit a new or existing contact.
+  Edit(ContactForm),
+}
+
+/// State. The contacts (sorted by name), which one of them is selected, & the route that is shown.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct AddressBookState {
+  pub contacts: Vec<Contact>,
+  pub selected_index: usize,
+  pub route: AddressBookRoute,
+  pub status_message: Option<String>,
+}
+
+impl AddressBookState {
+  pub fn get_selected_contact(&self) -> Option<&Contact> { self.contacts.get(self.selected_index) }
+}
+
+impl Display for AddressBookState {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "State {{ contacts: {}, selected_index: {}, route: {:?}, status_message: {:?} }}",
+      self.contacts.len(),
+      self.selected_index,
+      self.route,
+      self.status_message
+    )
+  }
+}
+
+/// Reducer. The status message only lasts until the next action. A contact that is added or
+/// updated is selected & shown (unless it has no name, in which case the form stays open). Saving
+/// the contacts is up to the [ContactsFileSaver], which says whether that worked.
+#[derive(Default)]
+pub struct AddressBookReducer;
+
+#[async_trait]
+impl AsyncReducer<AddressBookState, AddressBookAction> for AddressBookReducer {
+  async fn run(&self, action: &AddressBookAction, state: &AddressBookState) -> AddressBookState {
+    let mut new_state = state.clone();
+    new_state.status_message = None;
+
+    match action {
+      AddressBookAction::SetContacts(contacts) => {
+        new_state.contacts = contacts.clone();
+        sort_contacts(&mut new_state.contacts);
+        new_state.selected_index = 0;
+        new_state.route = AddressBookRoute::List;
+      }
+      AddressBookAction::AddContact(contact) | AddressBookAction::UpdateContact(contact) => {
+        if contact.name.is_empty() {
+          new_state.status_message = Some("A contact needs a name".into());
+          return new_state;
+        }
+
+        match new_state.contacts.iter_mut().find(|it| it.id == contact.id) {
+          Some(existing_contact) => *existing_contact = contact.clone(),
+          None => new_state.contacts.push(contact.clone()),
+        }
+        sort_contacts(&mut new_state.contacts);
+        new_state.selected_index = new_state
+          .contacts
+          .iter()
+          .position(|it| it.id == contact.id)
+          .unwrap_or_default();
+        new_state.route = AddressBookRoute::Detail;
+      }
+      AddressBookAction::DeleteContact(contact_id) => {
+        if let Some(index) = new_state
+          .contacts
+          .iter()
+          .position(|it| it.id == *contact_id)
+        {
+          new_state.contacts.remove(index);
+          new_state.selected_index = state
+            .selected_index
+            .min(new_state.contacts.len().saturating_sub(1));
+          new_state.route = AddressBookRoute::List;
+        }
+      }
+      AddressBookAction::SelectPrev => {
+        new_state.selected_index = state.selected_index.saturating_sub(1)
+      }
+      AddressBookAction::SelectNext => {
+        new_state.selected_index =
+          (state.selected_index + 1).min(state.contacts.len().saturating_sub(1))
+      }
+      AddressBookAction::Navigate(route) => new_state.route = route.clone(),
+      AddressBookAction::SetStatusMessage(message) => {
+        new_state.status_message = Some(message.clone())
+      }
+      AddressBookAction::EditForm(event) => {
+        if let AddressBookRoute::Edit(form) = &mut new_state.route {
+          form.apply_event(event);
+        }
+      }
+      _ => {}
+    }
+
+    new_state
+  }
+}
+
+fn sort_contacts(contacts: &mut [Contact]) {
+  contacts.sort_by_key(|contact| (contact.name.to_lowercase(), contact.id));
+}
@@ -0,0 +1,135 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{io::ErrorKind,
+          path::{Path, PathBuf}};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::{fs, sync::Mutex};
+
+use super::*;
+use crate::common::*;
+
+/// The file (in the app's data dir) that the contacts are kept in.
+pub const ADDRESS_BOOK_FILE_NAME: &str = "address_book.json";
+
+pub async fn get_address_book_file_path() -> CommonResult<PathBuf> {
+  Ok(get_app_data_dir().await?.join(ADDRESS_BOOK_FILE_NAME))
+}
+
+/// Load the contacts from the file at `file_path`. If the file does not exist, then there are no
+/// contacts yet, & the file is created when the 1st one is saved.
+pub async fn load_contacts(file_path: &Path) -> CommonResult<Vec<Contact>> {
+  throws_with_return!({
+    match fs::read_to_string(file_path).await {
+      Ok(content) => serde_json::from_str(&content)?,
+      Err(error) if error.kind() == ErrorKind::NotFound => vec![],
+      Err(error) => return Err(error.into()),
+    }
+  });
+}
+
+/// Write the `contacts` to the file at `file_path`. They are written to a temp file 1st, which
+/// then replaces the file, so that the file is never left half written.
+pub async fn save_contacts(file_path: &Path, contacts: &[Contact]) -> CommonResult<()> {
+  throws!({
+    let content = serde_json::to_string_pretty(contacts)?;
+    let temp_file_path = file_path.with_extension("json.tmp");
+    fs::write(&temp_file_path, content).await?;
+    fs::rename(&temp_file_path, file_path).await?;
+  });
+}
+
+/// Subscribes to the store, & saves the contacts to the file at `file_path` when they change. The
+/// outcome of each save is shown in the status bar (by an action that is dispatched to the store
+/// once the current one is done).
+pub struct ContactsFileSaver {
+  pub file_path: PathBuf,
+  pub last_saved_contacts: Mutex<Vec<Contact>>,
+  pub shared_store: SharedStore<AddressBookState, AddressBookAction>,
+}
+
+impl ContactsFileSaver {
+  /// The `contacts` are the ones that are in the file already.
+  pub fn new(
+    file_path: PathBuf, contacts: Vec<Contact>,
+    shared_store: SharedStore<AddressBookState, AddressBookAction>,
+  ) -> Self {
+    Self {
+      file_path,
+      last_saved_contacts: Mutex::new(contacts),
+      shared_store,
+    }
+  }
+}
+
+#[async_trait]
+impl AsyncSubscriber<AddressBookState> for ContactsFileSaver {
+  async fn run(&self, state: AddressBookState) {
+    let mut last_saved_contacts = self.last_saved_contacts.lock().await;
+    if *last_saved_contacts == state.contacts {
+      return;
+    }
+
+    // If this fails, then the contacts are saved again when they next change.
+    let message = match save_contacts(&self.file_path, &state.contacts).await {
+      Ok(_) => {
+        let message = get_saved_message(&last_saved_contacts, &state.contacts);
+        *last_saved_contacts = state.contacts;
+        message
+      }
+      Err(error) => {
+        call_if_true!(
+          is_logging_enabled(),
+          log_no_err!(
+            ERROR,
+            "📇 Could not save the contacts to {:?}: {}",
+            self.file_path,
+            error
+          )
+        );
+        format!("Could not save the contacts: {}", error)
+      }
+    };
+
+    // The store is busy w/ the action that this subscriber is run for.
+    let shared_store = self.shared_store.clone();
+    tokio::spawn(async move {
+      shared_store
+        .write()
+        .await
+        .dispatch_action(AddressBookAction::SetStatusMessage(message))
+        .await;
+    });
+  }
+}
+
+/// Describes what changed between the `last_saved_contacts` & the `contacts` that were just saved.
+fn get_saved_message(last_saved_contacts: &[Contact], contacts: &[Contact]) -> String {
+  let maybe_saved_contact = contacts
+    .iter()
+    .find(|contact| !last_saved_contacts.contains(contact));
+  let maybe_deleted_contact = last_saved_contacts
+    .iter()
+    .find(|it| !contacts.iter().any(|contact| contact.id == it.id));
+  match (maybe_saved_contact, maybe_deleted_contact) {
+    (Some(contact), _) => format!("Saved {}", contact.name),
+    (None, Some(contact)) => format!("Deleted {}", contact.name),
+    (None, None) => "Saved the contacts".into(),
+  }
+}
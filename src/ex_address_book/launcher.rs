@@ -0,0 +1,50 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::sync::Arc;
+
+use r3bl_rs_utils::*;
+use tokio::sync::RwLock;
+
+use super::*;
+
+/// The contacts are loaded from (& saved to) the address book file in the app's data dir.
+pub async fn run_app() -> CommonResult<()> {
+  throws!({
+    // Create store.
+    let mut store = create_store().await;
+
+    // Load the contacts. The app saves them whenever they change.
+    let file_path = get_address_book_file_path().await?;
+    let contacts = load_contacts(&file_path).await?;
+    store
+      .dispatch_action(AddressBookAction::SetContacts(contacts))
+      .await;
+
+    // Create an App (renders & responds to user input).
+    let shared_app = Arc::new(RwLock::new(AppAddressBook::new(file_path)));
+
+    // Exit if these keys are pressed. Typed chars (like 'x') go into the contact form.
+    let exit_keys: Vec<TWInputEvent> = vec![TWInputEvent::NonDisplayableKeypress(Keypress {
+      maybe_modifier_keys: Some(ModifierKeys::CTRL),
+      non_modifier_key: NonModifierKey::Character('q'),
+    })];
+
+    // Create a window.
+    TerminalWindow::main_event_loop(store, shared_app, exit_keys).await?
+  });
+}
@@ -0,0 +1,37 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! An address book, w/ "in-app" routing (the route is in the state, & the components that show
+//! it share the store). The contacts are kept in a JSON file in the app's data dir.
+
+// Attach sources.
+pub mod app_address_book;
+pub mod contact;
+pub mod contact_detail_component;
+pub mod contact_list_component;
+pub mod create_store;
+pub mod file_io;
+pub mod launcher;
+
+// Re-export.
+pub use app_address_book::*;
+pub use contact::*;
+pub use contact_detail_component::*;
+pub use contact_list_component::*;
+pub use create_store::*;
+pub use file_io::*;
+pub use launcher::*;
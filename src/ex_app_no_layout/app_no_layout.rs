@@ -17,7 +17,7 @@
 
 use async_trait::async_trait;
 
-use crate::*;
+use crate::{common::*, *};
 
 /// Async trait object that implements the [Render] trait.
 #[derive(Default, Debug, Clone, Copy)]
@@ -35,7 +35,7 @@ impl TWApp<AppNoLayoutState, AppNoLayoutAction> for AppNoLayout {
       let content = format!("{}", state);
 
       let content_size = content.len() as UnitType;
-      let col: UnitType = window_size.cols / 2 - content_size / 2;
+      let col: UnitType = (window_size.cols / 2).saturating_sub(content_size / 2);
       let row: UnitType = window_size.rows / 2;
 
       let colored_content = colorize_using_lolcat!(&mut self.lolcat, "{}", state);
@@ -50,7 +50,7 @@ impl TWApp<AppNoLayoutState, AppNoLayoutAction> for AppNoLayout {
 
       status_bar_helpers::create_status_bar_message(&mut queue, window_size);
 
-      call_if_true!(DEBUG, {
+      call_if_true!(is_logging_enabled(), {
         log_no_err!(
           INFO,
           "⛵ AppNoLayout::render -> size, state: {} {}",
@@ -69,7 +69,7 @@ impl TWApp<AppNoLayoutState, AppNoLayoutAction> for AppNoLayout {
   ) -> CommonResult<EventPropagation> {
     throws_with_return!({
       call_if_true!(
-        DEBUG,
+        is_logging_enabled(),
         log_no_err!(
           INFO,
           "⛵ AppNoLayout::handle_event -> input_event: {}",
@@ -84,7 +84,7 @@ impl TWApp<AppNoLayoutState, AppNoLayoutAction> for AppNoLayout {
           '+' => {
             spawn_and_consume_event!(event_consumed, shared_store, AppNoLayoutAction::AddPop(1));
             call_if_true!(
-              DEBUG,
+              is_logging_enabled(),
               log_no_err!(
                 INFO,
                 "⛵ AppNoLayout::handle_event -> + -> dispatch_spawn: {}",
@@ -95,7 +95,7 @@ impl TWApp<AppNoLayoutState, AppNoLayoutAction> for AppNoLayout {
           '-' => {
             spawn_and_consume_event!(event_consumed, shared_store, AppNoLayoutAction::SubPop(1));
             call_if_true!(
-              DEBUG,
+              is_logging_enabled(),
               log_no_err!(
                 INFO,
                 "⛵ AppNoLayout::handle_event -> - -> dispatch_spawn: {}",
@@ -115,7 +115,7 @@ impl TWApp<AppNoLayoutState, AppNoLayoutAction> for AppNoLayout {
           } => {
             spawn_and_consume_event!(event_consumed, shared_store, AppNoLayoutAction::AddPop(1));
             call_if_true!(
-              DEBUG,
+              is_logging_enabled(),
               log_no_err!(
                 INFO,
                 "⛵ AppNoLayout::handle_event -> Up -> dispatch_spawn: {}",
@@ -129,7 +129,7 @@ impl TWApp<AppNoLayoutState, AppNoLayoutAction> for AppNoLayout {
           } => {
             spawn_and_consume_event!(event_consumed, shared_store, AppNoLayoutAction::SubPop(1));
             call_if_true!(
-              DEBUG,
+              is_logging_enabled(),
               log_no_err!(
                 INFO,
                 "⛵ AppNoLayout::handle_event -> Down -> dispatch_spawn: {}",
@@ -165,8 +165,8 @@ mod status_bar_helpers {
     };
 
     let display_width = st_vec.unicode_string().display_width;
-    let col_center: UnitType = (size.cols / 2) - (display_width / 2);
-    let row_bottom: UnitType = size.rows - 1;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
     let center: Position = (col_center, row_bottom).into();
 
     *queue += TWCommand::MoveCursorPositionAbs(center);
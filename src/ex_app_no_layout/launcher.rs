@@ -19,12 +19,6 @@ use crate::*;
 
 pub async fn run_app() -> CommonResult<()> {
   throws!({
-    if DEBUG {
-      try_to_set_log_level(log::LevelFilter::Trace)?;
-    } else {
-      try_to_set_log_level(log::LevelFilter::Off)?;
-    }
-
     // Create store.
     let store = create_store().await;
 
@@ -75,7 +75,7 @@ impl TWApp<AppWithLayoutState, AppWithLayoutAction> for AppWithLayout {
       let mut surface = surface_start! {
         stylesheet: style_helpers::create_stylesheet()?,
         pos: (0, 0).into(),
-        size: (window_size.cols, window_size.rows - 1).into(), // Leave row at bottom for message.
+        size: (window_size.cols, window_size.rows.saturating_sub(1)).into(), // Leave row at bottom for message.
       };
 
       self
@@ -289,8 +289,8 @@ mod status_bar_helpers {
     };
 
     let display_width = st_vec.unicode_string().display_width;
-    let col_center: UnitType = (size.cols / 2) - (display_width / 2);
-    let row_bottom: UnitType = size.rows - 1;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
     let center: Position = (col_center, row_bottom).into();
 
     *queue += TWCommand::MoveCursorPositionAbs(center);
@@ -19,10 +19,12 @@ use async_trait::async_trait;
 use r3bl_rs_utils::*;
 
 use super::*;
+use crate::common::*;
 
 #[derive(Debug, Clone, Default)]
 pub struct ColumnRenderComponent {
   pub lolcat: Lolcat,
+  pub viewport: Viewport,
 }
 
 #[async_trait]
@@ -97,8 +99,10 @@ impl Component<AppWithLayoutState, AppWithLayoutAction> for ColumnRenderComponen
   ) -> CommonResult<TWCommandQueue> {
     throws_with_return!({
       // Fixed strings.
-      let line_1 = format!("{} - Hello", current_box.id);
-      let line_2 = format!("{} - World", current_box.id);
+      let lines = vec![
+        format!("{} - Hello", current_box.id),
+        format!("{} - World", current_box.id),
+      ];
 
       // Setup intermediate vars.
       let box_origin_pos = current_box.origin_pos; // Adjusted for style margin (if any).
@@ -106,38 +110,26 @@ impl Component<AppWithLayoutState, AppWithLayoutAction> for ColumnRenderComponen
       let mut content_cursor_pos = position!(col: 0 , row: 0 );
       let mut queue: TWCommandQueue = tw_command_queue!();
 
-      // Line 1.
-      tw_command_queue! {
-        queue push
-        TWCommand::MoveCursorPositionRelTo(box_origin_pos, content_cursor_pos),
-        TWCommand::ApplyColors(current_box.get_computed_style()),
-        TWCommand::PrintWithAttributes(
-          colorize_using_lolcat! {
-            &mut self.lolcat,
-            "{}",
-            line_1.unicode_string().truncate_to_fit_size(box_bounding_size)
-          },
-          current_box.get_computed_style(),
-        )
-      };
-
-      // Line 2.
-      tw_command_queue! {
-        queue push
-        TWCommand::MoveCursorPositionRelTo(
-          box_origin_pos,
-          content_cursor_pos.add_row_with_bounds(1, box_bounding_size)
-        ),
-        TWCommand::PrintWithAttributes(
-          colorize_using_lolcat! {
-            &mut self.lolcat,
-            "{}",
-            line_2.unicode_string().truncate_to_fit_size(box_bounding_size)
-          },
-          current_box.get_computed_style(),
-        ),
-        TWCommand::ResetColor
-      };
+      // Lines (only the part of them that fits inside the viewport).
+      for (row_index, visible_text) in self
+        .viewport
+        .visible_lines(&lines, box_bounding_size, false)
+      {
+        tw_command_queue! {
+          queue push
+          TWCommand::MoveCursorPositionRelTo(
+            box_origin_pos,
+            position!(col: 0, row: row_index)
+          ),
+          TWCommand::ApplyColors(current_box.get_computed_style()),
+          TWCommand::PrintWithAttributes(
+            colorize_using_lolcat! { &mut self.lolcat, "{}", visible_text },
+            current_box.get_computed_style(),
+          ),
+          TWCommand::ResetColor
+        };
+      }
+      content_cursor_pos.add_row_with_bounds(1, box_bounding_size);
 
       // Paint is_focused.
       if has_focus.does_current_box_have_focus(current_box) {
@@ -151,7 +143,7 @@ impl Component<AppWithLayoutState, AppWithLayoutAction> for ColumnRenderComponen
         };
       }
 
-      call_if_true!(DEBUG, {
+      call_if_true!(is_logging_enabled(), {
         log_no_err! {
           INFO,
           "\
@@ -17,18 +17,18 @@
 
 use r3bl_rs_utils::*;
 
-use crate::*;
+use crate::{common::*, *};
 
 pub fn debug_log_action(src: String, action: AppWithLayoutAction) {
   call_if_true!(
-    DEBUG,
+    is_logging_enabled(),
     log_no_err!(INFO, "🚀 {} -> dispatch action: {}", src, action,)
   );
 }
 
 pub fn debug_log_has_focus(src: String, has_focus: &HasFocus) {
   call_if_true!(
-    DEBUG,
+    is_logging_enabled(),
     log_no_err!(
       INFO,
       "👀 {} -> focus change & rerender: {:?}",
@@ -0,0 +1,246 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::{sync::RwLock, task::JoinHandle};
+
+use super::*;
+use crate::{common::*, discovery::*, host_components::*};
+
+// Constants for the ids.
+const PEER_CHOOSER_ID: &str = "peer_chooser";
+
+/// Async trait object that implements the [TWApp] trait. Lists the peers on the local network
+/// (which are found by the `maybe_peer_browser`) in a [ChooserComponent], & lets one of them be
+/// chosen.
+#[derive(Default)]
+pub struct AppDiscovery {
+  pub component_registry: ComponentRegistry<DiscoveryState, DiscoveryAction>,
+  pub has_focus: HasFocus,
+  /// Handed off to a task that dispatches the peers that it finds, the 1st time the app renders
+  /// (which is when the app is given the store).
+  pub maybe_peer_browser: Option<PeerBrowser>,
+  pub maybe_forward_peers_task: Option<JoinHandle<()>>,
+  /// Copied from the state, so that it can be taken from the app when it exits.
+  pub maybe_chosen_peer: Option<PeerInfo>,
+}
+
+#[async_trait]
+impl TWApp<DiscoveryState, DiscoveryAction> for AppDiscovery {
+  async fn app_render(
+    &mut self, state: &DiscoveryState, shared_store: &SharedStore<DiscoveryState, DiscoveryAction>,
+    window_size: Size,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      self.forward_peers_to_store_once(shared_store);
+      self.create_components_populate_registry_init_focus();
+      self.maybe_chosen_peer = state.maybe_chosen_peer.clone();
+
+      // Leave the row at the top for the title, & the one at the bottom for the status bar.
+      let mut surface = surface_start! {
+        stylesheet: Stylesheet::default(),
+        pos: (0, 1).into(),
+        size: (window_size.cols, window_size.rows.saturating_sub(2)).into(),
+      };
+
+      make_box! {
+        in:     surface,
+        id:     PEER_CHOOSER_ID,
+        dir:    Direction::Vertical,
+        size:   (100, 100).try_into()?,
+        styles: [],
+        render: {
+          from:         self.component_registry,
+          has_focus:    self.has_focus,
+          state:        state,
+          shared_store: shared_store
+        }
+      }
+
+      surface.surface_end()?;
+
+      let mut queue = tw_command_queue!(TWCommand::ClearScreen, TWCommand::ResetColor);
+      render_helpers::render_title(&mut queue, state);
+      queue += surface.render_buffer;
+      status_bar_helpers::render(&mut queue, window_size, &self.maybe_chosen_peer);
+
+      call_if_true!(is_logging_enabled(), {
+        log_no_err!(
+          INFO,
+          "📡 AppDiscovery::render -> size, state: {} {}",
+          window_size,
+          state
+        );
+      });
+      queue
+    });
+  }
+
+  async fn app_handle_event(
+    &mut self, input_event: &TWInputEvent, state: &DiscoveryState,
+    shared_store: &SharedStore<DiscoveryState, DiscoveryAction>, _terminal_size: Size,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      self.maybe_chosen_peer = state.maybe_chosen_peer.clone();
+
+      // If component has focus, then route input_event to it. Return its propagation enum.
+      if let Some(shared_component_has_focus) =
+        self.component_registry.get_has_focus(&self.has_focus)
+      {
+        let result_event_propagation = shared_component_has_focus
+          .write()
+          .await
+          .handle_event(input_event, state, shared_store)
+          .await?;
+        return Ok(result_event_propagation);
+      };
+
+      // input_event not handled, propagate it.
+      EventPropagation::Propagate
+    });
+  }
+}
+
+impl AppDiscovery {
+  fn create_components_populate_registry_init_focus(&mut self) {
+    if self.component_registry.id_does_not_exist(PEER_CHOOSER_ID) {
+      let shared_component = Arc::new(RwLock::new(ChooserComponent::new(
+        ChooserMode::SingleSelect,
+        chooser_helpers::get_peer_items,
+        chooser_helpers::choose_peer,
+      )));
+      self
+        .component_registry
+        .put(PEER_CHOOSER_ID, shared_component);
+    }
+
+    if self.has_focus.get_id().is_none() {
+      self.has_focus.set_id(PEER_CHOOSER_ID);
+    }
+  }
+
+  fn forward_peers_to_store_once(
+    &mut self, shared_store: &SharedStore<DiscoveryState, DiscoveryAction>,
+  ) {
+    if let Some(mut peer_browser) = self.maybe_peer_browser.take() {
+      let shared_store = shared_store.clone();
+      self.maybe_forward_peers_task = Some(tokio::spawn(async move {
+        while let Some(peers) = peer_browser.get_changed_peers().await {
+          shared_store
+            .write()
+            .await
+            .dispatch_action(DiscoveryAction::SetPeers(peers))
+            .await;
+        }
+      }));
+    }
+  }
+
+  /// Stop the browser (which is owned by the task that forwards the peers that it finds).
+  pub fn stop_looking_for_peers(&mut self) {
+    if let Some(task) = self.maybe_forward_peers_task.take() {
+      task.abort();
+    }
+    self.maybe_peer_browser = None;
+  }
+}
+
+mod chooser_helpers {
+  use super::*;
+
+  /// One item per peer, w/ the apps that it shares.
+  pub fn get_peer_items(state: &DiscoveryState) -> Vec<ChooserItem> {
+    state
+      .peers
+      .iter()
+      .map(|peer| {
+        let shared_app_names: Vec<&str> = peer
+          .shared_apps
+          .iter()
+          .map(|shared_app| shared_app.name.as_str())
+          .collect();
+        let text = if shared_app_names.is_empty() {
+          format!("{}  (shares nothing)", peer)
+        } else {
+          format!("{}  (shares: {})", peer, shared_app_names.join(", "))
+        };
+        ChooserItem {
+          id: peer.instance_id.to_string(),
+          text,
+        }
+      })
+      .collect()
+  }
+
+  /// The items are in the same order as the peers, so the index of an item is that of its peer.
+  pub fn choose_peer(chosen_items: Vec<ChosenItem>) -> DiscoveryAction {
+    match chosen_items.first() {
+      Some(chosen_item) => DiscoveryAction::ChoosePeer(chosen_item.index),
+      None => DiscoveryAction::Noop,
+    }
+  }
+}
+
+mod render_helpers {
+  use super::*;
+
+  pub fn render_title(queue: &mut TWCommandQueue, state: &DiscoveryState) {
+    let title = if state.peers.is_empty() {
+      " Looking for peers on the local network … "
+    } else {
+      " Peers on the local network "
+    };
+    *queue += TWCommand::MoveCursorPositionAbs((0, 0).into());
+    *queue += styled_texts! { styled_text! { title, style!(attrib: [bold]) } }.render();
+  }
+}
+
+mod status_bar_helpers {
+  use super::*;
+
+  /// Shows helpful messages (or which peer has been chosen) at the bottom row of the screen.
+  pub fn render(queue: &mut TWCommandQueue, size: Size, maybe_chosen_peer: &Option<PeerInfo>) {
+    let st_vec = match maybe_chosen_peer {
+      Some(chosen_peer) => styled_texts! {
+        styled_text! { format!(" Chose {} ", chosen_peer), style!(attrib: [bold]) },
+        styled_text! { " … ",                               style!(attrib: [dim])  },
+        styled_text! { " Ctrl+q : Exit & join ",            style!(attrib: [bold]) }
+      },
+      None => styled_texts! {
+        styled_text! { "Hints:",              style!(attrib: [dim])       },
+        styled_text! { " Ctrl+q : Exit ⛔ ",  style!(attrib: [bold])      },
+        styled_text! { " … ",                 style!(attrib: [dim])       },
+        styled_text! { " ↑ / ↓ : select ",    style!(attrib: [underline]) },
+        styled_text! { " … ",                 style!(attrib: [dim])       },
+        styled_text! { " type : filter ",     style!(attrib: [underline]) },
+        styled_text! { " … ",                 style!(attrib: [dim])       },
+        styled_text! { " Enter : choose ",    style!(attrib: [underline]) }
+      },
+    };
+
+    let display_width = st_vec.unicode_string().display_width;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    let center: Position = (col_center, row_bottom).into();
+
+    *queue += TWCommand::MoveCursorPositionAbs(center);
+    *queue += st_vec.render();
+  }
+}
@@ -0,0 +1,88 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use crate::discovery::*;
+
+// Create a new store and attach the reducer.
+pub async fn create_store() -> Store<DiscoveryState, DiscoveryAction> {
+  let mut store: Store<DiscoveryState, DiscoveryAction> = Store::default();
+  store.add_reducer(DiscoveryReducer::new()).await;
+  store
+}
+
+/// Action.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+#[allow(dead_code)]
+pub enum DiscoveryAction {
+  /// The peers on the local network changed.
+  SetPeers(Vec<PeerInfo>),
+  /// The peer at this index was chosen in the chooser.
+  ChoosePeer(usize),
+  #[default]
+  Noop,
+}
+
+impl Display for DiscoveryAction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{:?}", self) }
+}
+
+/// State. The peers that have been found, & which one of them was chosen (if any).
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct DiscoveryState {
+  pub peers: Vec<PeerInfo>,
+  pub maybe_chosen_peer: Option<PeerInfo>,
+}
+
+impl Display for DiscoveryState {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "State {{ peers: {}, maybe_chosen_peer: {:?} }}",
+      self.peers.len(),
+      self.maybe_chosen_peer.as_ref().map(|peer| peer.to_string())
+    )
+  }
+}
+
+/// Reducer. The chosen peer stays chosen, even if it goes away (since it might come back).
+#[derive(Default)]
+pub struct DiscoveryReducer;
+
+#[async_trait]
+impl AsyncReducer<DiscoveryState, DiscoveryAction> for DiscoveryReducer {
+  async fn run(&self, action: &DiscoveryAction, state: &DiscoveryState) -> DiscoveryState {
+    let mut new_state = state.clone();
+
+    match action {
+      DiscoveryAction::SetPeers(peers) => new_state.peers = peers.clone(),
+      DiscoveryAction::ChoosePeer(index) => {
+        if let Some(peer) = state.peers.get(*index) {
+          new_state.maybe_chosen_peer = Some(peer.clone());
+        }
+      }
+      _ => {}
+    }
+
+    new_state
+  }
+}
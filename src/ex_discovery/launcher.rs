@@ -0,0 +1,61 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::sync::Arc;
+
+use r3bl_rs_utils::*;
+use tokio::sync::RwLock;
+
+use super::*;
+use crate::discovery::*;
+
+/// Announce this instance on the local network, & list the other ones. Returns the peer that was
+/// chosen (if any) when the app exits.
+pub async fn run_app() -> CommonResult<Option<PeerInfo>> {
+  throws_with_return!({
+    // Create store.
+    let store = create_store().await;
+
+    // Start announcing this instance, & looking for others.
+    let transport: SharedDiscoveryTransport = Arc::new(UdpMulticastTransport::bind()?);
+    let local_peer = PeerInfo::new_local(vec![]);
+    let announcer = Announcer::start(transport.clone(), local_peer.clone(), ANNOUNCE_INTERVAL);
+    let peer_browser = PeerBrowser::start(transport, local_peer.instance_id, PEER_TTL);
+
+    // Create an App (renders & responds to user input). A handle to it is kept, to get the chosen
+    // peer from it when it exits.
+    let app = Arc::new(RwLock::new(AppDiscovery {
+      maybe_peer_browser: Some(peer_browser),
+      ..Default::default()
+    }));
+    let shared_app: SharedTWApp<DiscoveryState, DiscoveryAction> = app.clone();
+
+    // Exit if these keys are pressed. Typed chars (like 'x') filter the peers.
+    let exit_keys: Vec<TWInputEvent> = vec![TWInputEvent::NonDisplayableKeypress(Keypress {
+      maybe_modifier_keys: Some(ModifierKeys::CTRL),
+      non_modifier_key: NonModifierKey::Character('q'),
+    })];
+
+    // Create a window.
+    TerminalWindow::main_event_loop(store, shared_app, exit_keys).await?;
+
+    announcer.stop().await?;
+    let mut app = app.write().await;
+    app.stop_looking_for_peers();
+    app.maybe_chosen_peer.take()
+  });
+}
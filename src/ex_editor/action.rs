@@ -0,0 +1,73 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+use r3bl_rs_utils::*;
+
+use super::*;
+
+/// Action.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+#[allow(dead_code)]
+pub enum EditorAction {
+  /// Apply the [EditorEvent] to the buffer for the given id, which is rendered in a box w/ the
+  /// given [Size].
+  ApplyEvent(String, EditorEvent, Size),
+  /// Replace the buffer for the given id (eg: w/ one that has been loaded from a file).
+  SetEditorBuffer(String, EditorBuffer),
+  /// The buffer for the given id has been written to its file. The lines that were written are the
+  /// ones w/ the given revision id (they may have been edited since).
+  MarkSaved(String, u64),
+  /// Undo the last change to the buffer for the given id.
+  Undo(String),
+  /// Redo the last undone change to the buffer for the given id.
+  Redo(String),
+  /// Apply the [SearchEvent] to the search in the buffer for the given id, which is rendered in a
+  /// box w/ the given [Size].
+  Search(String, SearchEvent, Size),
+  /// Copy the selected text (or the caret line) in the buffer for the given id to the clipboard.
+  Copy(String),
+  /// Copy the selected text (or the caret line) in the buffer for the given id to the clipboard, &
+  /// delete it. The buffer is rendered in a box w/ the given [Size].
+  Cut(String, Size),
+  /// Insert the text in the clipboard at the carets of the buffer for the given id, which is
+  /// rendered in a box w/ the given [Size].
+  Paste(String, Size),
+  /// Turn on (or off) copying to the terminal's clipboard, as well as to the in-process one.
+  SetOsc52Clipboard(bool),
+  /// Share the buffer of the editor in the [CollabConfig] w/ a peer, as the site w/ the given id.
+  /// The session itself is run by the app (see [spawn_collab_session]).
+  StartCollab(CollabConfig, u64),
+  /// Apply a message from the peer in the collab session.
+  ApplyCollabMessage(CollabMessage),
+  /// Show a message in the status bar (until the next edit).
+  SetStatusMessage(String),
+  /// Paint the app again, w/out changing the state (eg: when the candidates for a completion popup
+  /// arrive).
+  Repaint,
+  Noop,
+}
+
+impl Default for EditorAction {
+  fn default() -> Self { EditorAction::Noop }
+}
+
+impl Display for EditorAction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{:?}", self) }
+}
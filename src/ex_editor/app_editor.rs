@@ -0,0 +1,472 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::sync::RwLock;
+
+use super::*;
+use crate::common::*;
+
+// Constants for the ids.
+const CONTAINER_ID: &str = "container";
+pub const EDITOR_1_ID: &str = "editor_1";
+pub const EDITOR_2_ID: &str = "editor_2";
+
+/// Async trait object that implements the [TWApp] trait.
+#[derive(Default)]
+pub struct AppEditor {
+  pub component_registry: ComponentRegistry<EditorState, EditorAction>,
+  pub has_focus: HasFocus,
+  /// The collab session (if there is one in the state) is started the 1st time the app renders,
+  /// since that is when the app is given the store that the session dispatches actions to.
+  pub is_collab_session_started: bool,
+}
+
+#[async_trait]
+impl TWApp<EditorState, EditorAction> for AppEditor {
+  async fn app_handle_event(
+    &mut self, input_event: &TWInputEvent, state: &EditorState,
+    shared_store: &SharedStore<EditorState, EditorAction>, _terminal_size: Size,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      // Try to handle Ctrl+Left and Ctrl+Right input events & return if handled.
+      if let Continuation::Return = self.handle_ctrl_left_right_input_to_switch_focus(input_event) {
+        return Ok(EventPropagation::ConsumedRerender);
+      }
+
+      // If component has focus, then route input_event to it. Return its propagation enum.
+      if let Some(shared_component_has_focus) =
+        self.component_registry.get_has_focus(&self.has_focus)
+      {
+        let result_event_propagation = shared_component_has_focus
+          .write()
+          .await
+          .handle_event(input_event, state, shared_store)
+          .await?;
+        return Ok(result_event_propagation);
+      };
+
+      // input_event not handled, propagate it.
+      EventPropagation::Propagate
+    });
+  }
+
+  async fn app_render(
+    &mut self, state: &EditorState, shared_store: &SharedStore<EditorState, EditorAction>,
+    window_size: Size,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      self.create_components_populate_registry_init_focus().await;
+      self.start_collab_session_once(state, shared_store);
+
+      let mut surface = surface_start! {
+        stylesheet: style_helpers::create_stylesheet()?,
+        pos: (0, 0).into(),
+        size: (window_size.cols, window_size.rows.saturating_sub(1)).into(), // Leave row at bottom for message.
+      };
+
+      self
+        .create_main_container(&mut surface, state, shared_store)
+        .await?;
+
+      surface.surface_end()?;
+
+      status_bar_helpers::render(
+        &mut surface.render_buffer,
+        window_size,
+        state,
+        &self.has_focus,
+      );
+
+      surface.render_buffer
+    });
+  }
+}
+
+// Handle focus.
+mod focus {
+  use super::*;
+
+  impl AppEditor {
+    /// Left and Right (w/out modifiers) move the caret inside the editor that has focus, so Ctrl
+    /// is used to switch focus between the editors.
+    pub fn handle_ctrl_left_right_input_to_switch_focus(
+      &mut self, input_event: &TWInputEvent,
+    ) -> Continuation {
+      let mut event_consumed = false;
+
+      if let TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: Some(ModifierKeys::CTRL),
+        non_modifier_key: NonModifierKey::Special(special_key),
+      }) = input_event
+      {
+        match special_key {
+          SpecialKey::Left => {
+            event_consumed = true;
+            self.has_focus.set_id(EDITOR_1_ID);
+          }
+          SpecialKey::Right => {
+            event_consumed = true;
+            self.has_focus.set_id(EDITOR_2_ID);
+          }
+          _ => {}
+        }
+      }
+
+      if event_consumed {
+        call_if_true!(
+          is_logging_enabled(),
+          log_no_err!(
+            INFO,
+            "👀 AppEditor::app_handle_event -> focus change & rerender: {:?}",
+            self.has_focus
+          )
+        );
+        Continuation::Return
+      } else {
+        Continuation::Continue
+      }
+    }
+  }
+}
+
+// Handle component registry and rendering.
+mod component_construction_and_rendering {
+  use super::*;
+
+  impl AppEditor {
+    /// Each editor gets its own component (w/ its own id), so each has a separate buffer & caret.
+    pub async fn create_components_populate_registry_init_focus(&mut self) {
+      let maybe_selection_style = style_helpers::create_stylesheet()
+        .ok()
+        .and_then(|stylesheet| stylesheet.find_style_by_id("style_selection"));
+
+      for id in [EDITOR_1_ID, EDITOR_2_ID] {
+        if self.component_registry.id_does_not_exist(id) {
+          let shared_component = Arc::new(RwLock::new(EditorComponent {
+            maybe_selection_style: maybe_selection_style.clone(),
+            ..EditorComponent::new(id)
+          }));
+          self.component_registry.put(id, shared_component);
+        }
+      }
+
+      // Init has focus.
+      if self.has_focus.get_id().is_none() {
+        self.has_focus.set_id(EDITOR_1_ID);
+      }
+    }
+
+    /// Main container CONTAINER_ID.
+    pub async fn create_main_container<'a>(
+      &mut self, surface: &mut Surface, state: &'a EditorState,
+      shared_store: &'a SharedStore<EditorState, EditorAction>,
+    ) -> CommonResult<()> {
+      throws!({
+        surface.box_start(box_props! {
+          id:   CONTAINER_ID,
+          dir:  Direction::Horizontal,
+          size: (100, 100).try_into()?,
+        })?;
+        self.create_left_col(surface, state, shared_store).await?;
+        self.create_right_col(surface, state, shared_store).await?;
+        surface.box_end()?;
+      });
+    }
+
+    /// Left column EDITOR_1_ID.
+    async fn create_left_col<'a>(
+      &mut self, surface: &mut Surface, state: &'a EditorState,
+      shared_store: &'a SharedStore<EditorState, EditorAction>,
+    ) -> CommonResult<()> {
+      throws!({
+        make_box! {
+          in:     surface,
+          id:     EDITOR_1_ID,
+          dir:    Direction::Vertical,
+          size:   (50, 100).try_into()?,
+          styles: ["style_editor_1"],
+          render: {
+            from:         self.component_registry,
+            has_focus:    self.has_focus,
+            state:        state,
+            shared_store: shared_store
+          }
+        }
+      });
+    }
+
+    /// Right column EDITOR_2_ID.
+    async fn create_right_col<'a>(
+      &mut self, surface: &mut Surface, state: &'a EditorState,
+      shared_store: &'a SharedStore<EditorState, EditorAction>,
+    ) -> CommonResult<()> {
+      throws!({
+        make_box! {
+          in:     surface,
+          id:     EDITOR_2_ID,
+          dir:    Direction::Vertical,
+          size:   (50, 100).try_into()?,
+          styles: ["style_editor_2"],
+          render: {
+            from:         self.component_registry,
+            has_focus:    self.has_focus,
+            state:        state,
+            shared_store: shared_store
+          }
+        }
+      });
+    }
+  }
+}
+
+mod app_editor_helpers {
+  use super::*;
+
+  impl Debug for AppEditor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      f.debug_struct("AppEditor")
+        .field("component_registry", &self.component_registry)
+        .field("state_manage_focus_data", &self.has_focus)
+        .field("is_collab_session_started", &self.is_collab_session_started)
+        .finish()
+    }
+  }
+
+  impl AppEditor {
+    pub fn start_collab_session_once(
+      &mut self, state: &EditorState, shared_store: &SharedStore<EditorState, EditorAction>,
+    ) {
+      if let (Some(collab), false) = (&state.collab, self.is_collab_session_started) {
+        spawn_collab_session(collab.config.clone(), shared_store.clone());
+        self.is_collab_session_started = true;
+      }
+    }
+  }
+}
+
+mod style_helpers {
+  use super::*;
+
+  pub fn create_stylesheet() -> CommonResult<Stylesheet> {
+    throws_with_return!({
+      stylesheet! {
+        style! {
+          id: style_editor_1
+          margin: 1
+          color_bg: TWColor::Rgb { r: 55, g: 55, b: 100 }
+        },
+        style! {
+          id: style_editor_2
+          margin: 1
+          color_bg: TWColor::Rgb { r: 55, g: 55, b: 248 }
+        },
+        style! {
+          id: style_selection
+          color_fg: TWColor::Rgb { r: 0, g: 0, b: 0 }
+          color_bg: TWColor::Rgb { r: 180, g: 180, b: 240 }
+        }
+      }
+    })
+  }
+}
+
+mod status_bar_helpers {
+  use super::*;
+
+  /// Shows the file info for the editor that has focus on the left, and the search prompt (if the
+  /// search is open), the status message (if any), the pairing code (if a collab host is waiting
+  /// for a peer) or helpful messages in the center of the bottom row of the screen.
+  pub fn render(queue: &mut TWCommandQueue, size: Size, state: &EditorState, has_focus: &HasFocus) {
+    render_file_info(queue, size, state, has_focus);
+    match (&state.search, &state.status_message) {
+      (Some(search), _) => render_search_prompt(queue, size, state, search),
+      (None, Some(message)) => render_status_message(queue, size, message),
+      (None, None) => match state
+        .collab
+        .as_ref()
+        .filter(|collab| collab.is_waiting_for_peer())
+      {
+        Some(collab) => render_pairing_code(queue, size, collab),
+        None => render_hints(queue, size),
+      },
+    }
+  }
+
+  /// File name of the buffer that has focus, and whether it has been modified since it was saved.
+  fn render_file_info(
+    queue: &mut TWCommandQueue, size: Size, state: &EditorState, has_focus: &HasFocus,
+  ) {
+    let maybe_editor_buffer = has_focus
+      .get_id()
+      .and_then(|id| state.get_editor_buffer(&id));
+
+    let (file_name, modified_indicator) = match maybe_editor_buffer {
+      Some(editor_buffer) => (
+        editor_buffer
+          .file_path
+          .clone()
+          .unwrap_or_else(|| "[scratch]".into()),
+        if editor_buffer.is_dirty {
+          " ● "
+        } else {
+          " ✔ "
+        },
+      ),
+      None => ("[scratch]".into(), " ✔ "),
+    };
+
+    let st_vec = styled_texts! {
+      styled_text! { modified_indicator, style!(attrib: [bold]) },
+      styled_text! { file_name,          style!(attrib: [dim])  }
+    };
+
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    *queue += TWCommand::MoveCursorPositionAbs((0, row_bottom).into());
+    *queue += st_vec.render();
+  }
+
+  fn render_status_message(queue: &mut TWCommandQueue, size: Size, message: &str) {
+    let st_vec = styled_texts! {
+      styled_text! { message, style!(attrib: [bold]) }
+    };
+
+    let display_width = st_vec.unicode_string().display_width;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    let center: Position = (col_center, row_bottom).into();
+
+    *queue += TWCommand::MoveCursorPositionAbs(center);
+    *queue += st_vec.render();
+  }
+
+  /// The query (& the replacement, for find & replace), the case sensitive & regex toggles (which
+  /// are highlighted when they are on), and which match the caret is on. The field that typed chars
+  /// go into is underlined.
+  fn render_search_prompt(
+    queue: &mut TWCommandQueue, size: Size, state: &EditorState, search: &SearchState,
+  ) {
+    let matches = &search.matches;
+    let match_info = match (
+      search.get_regex(),
+      state.get_editor_buffer(&search.editor_id),
+    ) {
+      (Err(_), _) => "invalid regex".to_string(),
+      (Ok(Some(_)), Some(editor_buffer)) => {
+        match matches
+          .iter()
+          .position(|search_match| search_match.pos == editor_buffer.caret)
+        {
+          Some(index) => format!("{} of {}", index + 1, matches.len()),
+          None if matches.is_empty() => "no matches".to_string(),
+          None => format!("{} matches", matches.len()),
+        }
+      }
+      _ => "".to_string(),
+    };
+
+    let get_toggle_style = |is_on: bool| {
+      if is_on {
+        style!(attrib: [bold, reverse])
+      } else {
+        style!(attrib: [dim])
+      }
+    };
+
+    let get_field_style = |field: SearchField| {
+      if search.focused_field == field {
+        style!(attrib: [underline])
+      } else {
+        style!(attrib: [dim])
+      }
+    };
+
+    let (replace_label, replace_field, replace_hint, enter_hint) = match &search.maybe_replacement {
+      Some(replacement) => (
+        "  Replace: ",
+        format!("{}▏", replacement),
+        " Tab : field … Alt+a : replace all ",
+        " Enter : replace / next ",
+      ),
+      None => ("", String::new(), "", " Enter / Shift+Enter : next / prev "),
+    };
+
+    let st_vec = styled_texts! {
+      styled_text! { "Find: ",                      style!(attrib: [bold])                   },
+      styled_text! { format!("{}▏", search.query),  get_field_style(SearchField::Find)       },
+      styled_text! { replace_label,                 style!(attrib: [bold])                   },
+      styled_text! { replace_field,                 get_field_style(SearchField::Replace)    },
+      styled_text! { " ",                           style!(attrib: [dim])                    },
+      styled_text! { " Aa ",                        get_toggle_style(search.is_case_sensitive) },
+      styled_text! { " .* ",                        get_toggle_style(search.is_regex)        },
+      styled_text! { format!(" {} ", match_info),   style!(attrib: [bold])                   },
+      styled_text! { " … ",                         style!(attrib: [dim])                    },
+      styled_text! { enter_hint,                    style!(attrib: [underline])              },
+      styled_text! { replace_hint,                  style!(attrib: [underline])              },
+      styled_text! { " … ",                         style!(attrib: [dim])                    },
+      styled_text! { " Alt+c / r : case / regex ",  style!(attrib: [underline])              },
+      styled_text! { " … ",                         style!(attrib: [dim])                    },
+      styled_text! { " Esc : close ",               style!(attrib: [underline])              }
+    };
+
+    let display_width = st_vec.unicode_string().display_width;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    let center: Position = (col_center, row_bottom).into();
+
+    *queue += TWCommand::MoveCursorPositionAbs(center);
+    *queue += st_vec.render();
+  }
+
+  /// Where the host is waiting for a peer, & the code that the peer has to type in to join.
+  fn render_pairing_code(queue: &mut TWCommandQueue, size: Size, collab: &CollabState) {
+    let message = format!(
+      "Waiting for a peer to join at {} w/ pairing code {}",
+      collab.config.mode.get_addr(),
+      collab.config.pairing_code
+    );
+    render_status_message(queue, size, &message);
+  }
+
+  fn render_hints(queue: &mut TWCommandQueue, size: Size) {
+    let st_vec = styled_texts! {
+      styled_text! { "Hints:",                     style!(attrib: [dim])       },
+      styled_text! { " Ctrl+q : Exit ⛔ ",         style!(attrib: [bold])      },
+      styled_text! { " … ",                        style!(attrib: [dim])       },
+      styled_text! { " ↑ / ↓ / ← / → : move ",     style!(attrib: [underline]) },
+      styled_text! { " … ",                        style!(attrib: [dim])       },
+      styled_text! { " Ctrl+← / → : focus ",       style!(attrib: [underline]) },
+      styled_text! { " … ",                        style!(attrib: [dim])       },
+      styled_text! { " Ctrl+s : save ",            style!(attrib: [underline]) },
+      styled_text! { " … ",                        style!(attrib: [dim])       },
+      styled_text! { " Ctrl+f / r : find / replace ", style!(attrib: [underline]) },
+      styled_text! { " … ",                        style!(attrib: [dim])       },
+      styled_text! { " Ctrl+z / y : undo / redo ", style!(attrib: [underline]) }
+    };
+
+    let display_width = st_vec.unicode_string().display_width;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    let center: Position = (col_center, row_bottom).into();
+
+    *queue += TWCommand::MoveCursorPositionAbs(center);
+    *queue += st_vec.render();
+  }
+}
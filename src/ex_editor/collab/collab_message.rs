@@ -0,0 +1,87 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils::*;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use super::*;
+use crate::pairing::*;
+
+/// Messages between the peers in a collab session. Each one is sent as a frame of JSON over the
+/// [SecureChannel] between the peers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollabMessage {
+  /// The 1st message that each peer sends.
+  Hello {
+    site_id: u64,
+    user_name: String,
+  },
+  Ops {
+    site_id: u64,
+    ops: Vec<CrdtOp>,
+  },
+  /// The sender's caret moved (see [CrdtDoc::get_anchor]).
+  Caret {
+    site_id: u64,
+    anchor: Option<CharId>,
+  },
+  /// The sender has applied the first `op_count` ops that the receiver sent it, so the receiver
+  /// can drop the tombstones that neither peer refers to any more (see
+  /// [CrdtDoc::purge_tombstones]).
+  Applied {
+    site_id: u64,
+    op_count: u64,
+  },
+}
+
+/// The most ops that are sent in one [CollabMessage::Ops]. The JSON for an op is ~120 bytes, &
+/// never more than ~200 (w/ the biggest ids & an escaped char), so each frame stays well under
+/// [MAX_FRAME_SIZE].
+pub const MAX_OPS_PER_MESSAGE: usize = 1024;
+
+impl CollabMessage {
+  /// The ops are split into as many messages as it takes (see [MAX_OPS_PER_MESSAGE]), so that a
+  /// big buffer (or paste) can be sent.
+  pub fn new_ops_messages(site_id: u64, ops: &[CrdtOp]) -> Vec<CollabMessage> {
+    ops
+      .chunks(MAX_OPS_PER_MESSAGE)
+      .map(|ops| CollabMessage::Ops {
+        site_id,
+        ops: ops.to_vec(),
+      })
+      .collect()
+  }
+}
+
+pub async fn write_collab_message<W: AsyncWrite + Unpin>(
+  writer: &mut SecureWriter<W>, message: &CollabMessage,
+) -> CommonResult<()> {
+  throws!({
+    writer.write_frame(&serde_json::to_vec(message)?).await?;
+  });
+}
+
+/// Returns [None] when the other peer has closed the connection.
+pub async fn read_collab_message<R: AsyncRead + Unpin>(
+  reader: &mut SecureReader<R>,
+) -> CommonResult<Option<CollabMessage>> {
+  throws_with_return!(match reader.read_frame().await? {
+    Some(frame) => Some(serde_json::from_slice(&frame)?),
+    None => None,
+  });
+}
@@ -0,0 +1,633 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{net::SocketAddr,
+          sync::{atomic::{AtomicBool, Ordering},
+                 Arc,
+                 Mutex},
+          time::Duration};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::{io::{AsyncRead, AsyncWrite},
+            net::{TcpListener, TcpStream},
+            sync::{mpsc::{self, UnboundedSender},
+                   RwLock}};
+
+use super::*;
+use crate::{common::*, discovery::*, ex_editor::*, pairing::*};
+
+/// Name of the [SharedApp] that a host announces (see [crate::discovery]).
+pub const COLLAB_SHARED_APP_NAME: &str = "editor";
+
+/// A stream that a collab session runs over (eg: a TCP or Unix socket).
+pub trait CollabStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> CollabStream for T {}
+
+/// Peers that don't finish the pairing handshake in time are dropped.
+pub const PAIRING_TIMEOUT: Duration = Duration::from_secs(10);
+/// The host gives up after this many peers failed to pair (eg: w/ the wrong pairing code), so the
+/// code can't be guessed by trying them all.
+pub const MAX_PAIRING_ATTEMPTS: usize = 3;
+
+/// The stream to the peer, once the peers have been paired.
+pub type CollabChannel = SecureChannel<Box<dyn CollabStream>>;
+
+/// Run the collab session (in the [CollabState] of the store) in the background. When it ends, the
+/// reason is shown in the status bar.
+pub fn spawn_collab_session(
+  config: CollabConfig, shared_store: SharedStore<EditorState, EditorAction>,
+) {
+  tokio::spawn(async move {
+    let message = match run_collab_session(&config, &shared_store).await {
+      Ok(_) => "The peer left the collab session".to_string(),
+      Err(error) => format!("Collab session failed: {}", error),
+    };
+    set_status_message(&shared_store, message).await;
+  });
+}
+
+async fn run_collab_session(
+  config: &CollabConfig, shared_store: &SharedStore<EditorState, EditorAction>,
+) -> CommonResult<()> {
+  throws!({
+    let channel = match &config.mode {
+      CollabMode::Host(addr) => {
+        let maybe_announcer = start_announcer(addr);
+        let result = accept_paired_peer(addr, &config.pairing_code, shared_store).await;
+        if let Some(announcer) = maybe_announcer {
+          announcer.stop().await?;
+        }
+        result?
+      }
+      CollabMode::Join(addr) => {
+        let stream = connect_collab_stream(addr).await?;
+        pair_w_timeout(stream, PairingRole::Guest, &config.pairing_code).await?
+      }
+    };
+    run_collab_connection(channel, shared_store).await?;
+  });
+}
+
+async fn set_status_message(
+  shared_store: &SharedStore<EditorState, EditorAction>, message: String,
+) {
+  shared_store
+    .write()
+    .await
+    .dispatch_action(EditorAction::SetStatusMessage(message))
+    .await;
+}
+
+/// A host on a TCP socket is announced on the local network (see [crate::discovery]) until a peer
+/// joins. If discovery doesn't work (eg: there is no network), the peer can still join by address.
+/// A host that only listens on localhost can't be joined from another machine, so it isn't
+/// announced.
+fn start_announcer(addr: &CollabAddr) -> Option<Announcer> {
+  let addr = match addr {
+    CollabAddr::Tcp(addr) if !is_loopback_addr(addr) => addr,
+    _ => return None,
+  };
+  let transport = match UdpMulticastTransport::bind() {
+    Ok(transport) => transport,
+    Err(error) => {
+      call_if_true!(
+        is_logging_enabled(),
+        log_no_err!(ERROR, "📡 Could not announce the collab session: {}", error)
+      );
+      return None;
+    }
+  };
+  let local_peer = PeerInfo::new_local(vec![SharedApp {
+    name: COLLAB_SHARED_APP_NAME.into(),
+    addr: addr.clone(),
+  }]);
+  Some(Announcer::start(
+    Arc::new(transport),
+    local_peer,
+    ANNOUNCE_INTERVAL,
+  ))
+}
+
+fn is_loopback_addr(addr: &str) -> bool {
+  match addr.parse::<SocketAddr>() {
+    Ok(addr) => addr.ip().is_loopback(),
+    Err(_) => addr.starts_with("localhost:"),
+  }
+}
+
+/// Wait for a peer that has the same pairing code. Peers that fail to pair are rejected (& the
+/// host keeps waiting), up to [MAX_PAIRING_ATTEMPTS].
+async fn accept_paired_peer(
+  addr: &CollabAddr, pairing_code: &PairingCode,
+  shared_store: &SharedStore<EditorState, EditorAction>,
+) -> CommonResult<CollabChannel> {
+  let listener = CollabListener::bind(addr).await?;
+  for attempt in 1..=MAX_PAIRING_ATTEMPTS {
+    let stream = listener.accept().await?;
+    match pair_w_timeout(stream, PairingRole::Host, pairing_code).await {
+      Ok(channel) => return Ok(channel),
+      Err(error) => {
+        let message = format!(
+          "Rejected a peer ({}), {} attempt(s) left",
+          error,
+          MAX_PAIRING_ATTEMPTS - attempt
+        );
+        set_status_message(shared_store, message).await;
+      }
+    }
+  }
+  Err("Too many peers failed to pair".into())
+}
+
+async fn pair_w_timeout(
+  stream: Box<dyn CollabStream>, role: PairingRole, pairing_code: &PairingCode,
+) -> CommonResult<CollabChannel> {
+  match tokio::time::timeout(PAIRING_TIMEOUT, pair(stream, role, pairing_code)).await {
+    Ok(result) => result,
+    Err(_) => Err("Timed out while pairing".into()),
+  }
+}
+
+enum CollabListener {
+  Tcp(TcpListener),
+  #[cfg(unix)]
+  Unix(tokio::net::UnixListener),
+}
+
+impl CollabListener {
+  async fn bind(addr: &CollabAddr) -> CommonResult<Self> {
+    throws_with_return!(match addr {
+      CollabAddr::Tcp(addr) => CollabListener::Tcp(TcpListener::bind(addr).await?),
+      #[cfg(unix)]
+      CollabAddr::Unix(path) => {
+        remove_stale_unix_socket(path)?;
+        CollabListener::Unix(tokio::net::UnixListener::bind(path)?)
+      }
+      #[cfg(not(unix))]
+      CollabAddr::Unix(_) => return Err("Unix sockets are not supported on this platform".into()),
+    });
+  }
+
+  async fn accept(&self) -> CommonResult<Box<dyn CollabStream>> {
+    throws_with_return!({
+      let stream: Box<dyn CollabStream> = match self {
+        CollabListener::Tcp(listener) => Box::new(listener.accept().await?.0),
+        #[cfg(unix)]
+        CollabListener::Unix(listener) => Box::new(listener.accept().await?.0),
+      };
+      stream
+    });
+  }
+}
+
+async fn connect_collab_stream(addr: &CollabAddr) -> CommonResult<Box<dyn CollabStream>> {
+  throws_with_return!({
+    let stream: Box<dyn CollabStream> = match addr {
+      CollabAddr::Tcp(addr) => Box::new(TcpStream::connect(addr).await?),
+      #[cfg(unix)]
+      CollabAddr::Unix(path) => Box::new(tokio::net::UnixStream::connect(path).await?),
+      #[cfg(not(unix))]
+      CollabAddr::Unix(_) => return Err("Unix sockets are not supported on this platform".into()),
+    };
+    stream
+  });
+}
+
+/// A socket file that is left over from an earlier session can't be bound to again. Any other kind
+/// of file at the `path` is left alone (& binding to it fails).
+#[cfg(unix)]
+fn remove_stale_unix_socket(path: &str) -> CommonResult<()> {
+  use std::os::unix::fs::FileTypeExt;
+
+  throws!({
+    if let Ok(metadata) = std::fs::metadata(path) {
+      if metadata.file_type().is_socket() {
+        std::fs::remove_file(path)?;
+      }
+    }
+  });
+}
+
+/// Exchange messages w/ the peer until it disconnects, or until sending to it (or receiving from
+/// it) fails, which ends the session w/ that error. Local changes are sent by a subscriber to the
+/// store, and messages from the peer are dispatched to the store.
+pub async fn run_collab_connection(
+  channel: CollabChannel, shared_store: &SharedStore<EditorState, EditorAction>,
+) -> CommonResult<()> {
+  throws!({
+    let state = shared_store.read().await.get_state();
+    let collab = state.collab.as_ref().ok_or("There is no collab session")?;
+
+    let SecureChannel {
+      mut reader,
+      mut writer,
+    } = channel;
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    sender.send(CollabMessage::Hello {
+      site_id: collab.get_site_id(),
+      user_name: collab.config.user_name.clone(),
+    })?;
+
+    // The changes that were made before the peer connected (eg: the host's text) are sent first.
+    let outbox = Arc::new(CollabOutbox::new(sender));
+    outbox.send_local_changes(&state);
+    shared_store
+      .write()
+      .await
+      .add_subscriber(Arc::new(RwLock::new(CollabSubscriber {
+        outbox: outbox.clone(),
+      })))
+      .await;
+    outbox.send_local_changes(&shared_store.read().await.get_state());
+
+    // The session ends when either direction fails (or the peer disconnects).
+    let write_messages = async move {
+      throws!({
+        while let Some(message) = receiver.recv().await {
+          write_collab_message(&mut writer, &message).await?;
+        }
+      });
+    };
+    let read_messages = async {
+      throws!({
+        while let Some(message) = read_collab_message(&mut reader).await? {
+          shared_store
+            .write()
+            .await
+            .dispatch_action(EditorAction::ApplyCollabMessage(message))
+            .await;
+        }
+      });
+    };
+    let result: CommonResult<()> = tokio::select! {
+      result = write_messages => result,
+      result = read_messages => result,
+    };
+
+    // The store has no way to remove a subscriber, so it is turned off instead.
+    outbox.close();
+    result?;
+  });
+}
+
+/// Sends the local changes (in the [CollabState]) to the peer, & keeps track of which ones have
+/// been sent.
+#[derive(Debug)]
+struct CollabOutbox {
+  sender: UnboundedSender<CollabMessage>,
+  sent: Mutex<SentChanges>,
+  /// Set once the connection is closed, after which nothing is sent.
+  is_closed: AtomicBool,
+}
+
+#[derive(Debug, Default)]
+struct SentChanges {
+  maybe_caret_anchor: Option<Option<CharId>>,
+  applied_op_count: u64,
+}
+
+impl CollabOutbox {
+  fn new(sender: UnboundedSender<CollabMessage>) -> Self {
+    Self {
+      sender,
+      sent: Mutex::new(SentChanges::default()),
+      is_closed: AtomicBool::new(false),
+    }
+  }
+
+  fn close(&self) { self.is_closed.store(true, Ordering::Relaxed); }
+
+  /// Send the ops that haven't been sent yet (which are dropped from the doc), the caret if it has
+  /// moved, & how many of the peer's ops have been applied (if more have been). The ops & the
+  /// count are read together, so that the peer gets every op that was made before its ops were
+  /// applied first. Send errors are ignored, since they only happen once the connection has
+  /// closed.
+  fn send_local_changes(&self, state: &EditorState) {
+    if self.is_closed.load(Ordering::Relaxed) {
+      return;
+    }
+    let (collab, mut sent) = match (&state.collab, self.sent.lock()) {
+      (Some(collab), Ok(sent)) => (collab, sent),
+      _ => return,
+    };
+
+    let mut collab_doc = collab.doc.lock();
+    let unsent_ops = std::mem::take(&mut collab_doc.unsent_ops);
+    for message in CollabMessage::new_ops_messages(collab.get_site_id(), &unsent_ops) {
+      let _ = self.sender.send(message);
+    }
+
+    if sent.maybe_caret_anchor != Some(collab.local_caret_anchor) {
+      let _ = self.sender.send(CollabMessage::Caret {
+        site_id: collab.get_site_id(),
+        anchor: collab.local_caret_anchor,
+      });
+      sent.maybe_caret_anchor = Some(collab.local_caret_anchor);
+    }
+
+    let applied_op_count = collab_doc.doc.get_remote_op_count();
+    if applied_op_count > sent.applied_op_count {
+      let _ = self.sender.send(CollabMessage::Applied {
+        site_id: collab.get_site_id(),
+        op_count: applied_op_count,
+      });
+      sent.applied_op_count = applied_op_count;
+    }
+  }
+}
+
+struct CollabSubscriber {
+  outbox: Arc<CollabOutbox>,
+}
+
+#[async_trait]
+impl AsyncSubscriber<EditorState> for CollabSubscriber {
+  async fn run(&self, state: EditorState) { self.outbox.send_local_changes(&state); }
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::io::{ReadHalf, WriteHalf};
+
+  use super::*;
+
+  async fn send_ops<W: AsyncWrite + Unpin>(
+    writer: &mut SecureWriter<W>, site_id: u64, ops: Vec<CrdtOp>,
+  ) {
+    let message = CollabMessage::Ops { site_id, ops };
+    write_collab_message(writer, &message).await.unwrap();
+  }
+
+  async fn receive_ops<R: AsyncRead + Unpin>(reader: &mut SecureReader<R>) -> Vec<CrdtOp> {
+    match read_collab_message(reader).await.unwrap() {
+      Some(CollabMessage::Ops { ops, .. }) => ops,
+      other => panic!("Expected ops, got: {:?}", other),
+    }
+  }
+
+  /// Pair the ends of a connection (as a collab session does before any messages are sent).
+  async fn pair_streams<S: AsyncRead + AsyncWrite + Unpin>(
+    host_stream: S, guest_stream: S,
+  ) -> (SecureChannel<S>, SecureChannel<S>) {
+    let pairing_code = PairingCode::generate().unwrap();
+    let (host_channel, guest_channel) = tokio::join!(
+      pair(host_stream, PairingRole::Host, &pairing_code),
+      pair(guest_stream, PairingRole::Guest, &pairing_code)
+    );
+    (host_channel.unwrap(), guest_channel.unwrap())
+  }
+
+  /// One end of a collab session, w/ its own doc.
+  struct TestPeer<S> {
+    doc: CrdtDoc,
+    reader: SecureReader<ReadHalf<S>>,
+    writer: SecureWriter<WriteHalf<S>>,
+  }
+
+  impl<S: AsyncRead + AsyncWrite> TestPeer<S> {
+    fn new(site_id: u64, channel: SecureChannel<S>) -> Self {
+      Self {
+        doc: CrdtDoc::new(site_id),
+        reader: channel.reader,
+        writer: channel.writer,
+      }
+    }
+
+    async fn send_ops(&mut self, ops: Vec<CrdtOp>) {
+      send_ops(&mut self.writer, self.doc.site_id, ops).await;
+    }
+
+    async fn receive_ops(&mut self) {
+      let ops = receive_ops(&mut self.reader).await;
+      self.doc.apply_remote_ops(&ops);
+    }
+  }
+
+  async fn connect_loopback_peers() -> (TestPeer<TcpStream>, TestPeer<TcpStream>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (host_stream, guest_stream) =
+      tokio::join!(async { listener.accept().await.unwrap().0 }, async {
+        TcpStream::connect(addr).await.unwrap()
+      });
+    let (host_channel, guest_channel) = pair_streams(host_stream, guest_stream).await;
+    (
+      TestPeer::new(1, host_channel),
+      TestPeer::new(2, guest_channel),
+    )
+  }
+
+  #[tokio::test]
+  async fn test_concurrent_edits_converge() {
+    let (mut host, mut guest) = connect_loopback_peers().await;
+
+    let ops = host.doc.local_insert(0, "hello world");
+    host.send_ops(ops).await;
+    guest.receive_ops().await;
+    assert_eq!(guest.doc.get_text(), "hello world");
+
+    // Neither peer has seen the other's edits when it makes its own.
+    let mut host_ops = host.doc.local_delete(0, 5);
+    host_ops.extend(host.doc.local_insert(0, "goodbye"));
+    let mut guest_ops = guest.doc.local_insert(11, "!");
+    guest_ops.extend(guest.doc.local_delete(5, 1));
+    guest_ops.extend(guest.doc.local_insert(5, ", "));
+
+    tokio::join!(host.send_ops(host_ops), guest.send_ops(guest_ops));
+    tokio::join!(host.receive_ops(), guest.receive_ops());
+
+    assert_eq!(host.doc.get_text(), "goodbye, world!");
+    assert_eq!(guest.doc.get_text(), host.doc.get_text());
+  }
+
+  #[tokio::test]
+  async fn test_concurrent_inserts_at_the_same_place_converge() {
+    let (mut host, mut guest) = connect_loopback_peers().await;
+
+    let ops = host.doc.local_insert(0, "ac");
+    host.send_ops(ops).await;
+    guest.receive_ops().await;
+
+    let host_ops = host.doc.local_insert(1, "bbb");
+    let guest_ops = guest.doc.local_insert(1, "BBB");
+    tokio::join!(host.send_ops(host_ops), guest.send_ops(guest_ops));
+    tokio::join!(host.receive_ops(), guest.receive_ops());
+
+    // The runs aren't interleaved, & both peers order them the same way.
+    let text = host.doc.get_text();
+    assert!(text == "abbbBBBc" || text == "aBBBbbbc", "{}", text);
+    assert_eq!(guest.doc.get_text(), text);
+  }
+
+  #[tokio::test]
+  async fn test_edits_converge_after_tombstones_are_purged() {
+    let (mut host, mut guest) = connect_loopback_peers().await;
+
+    let ops = host.doc.local_insert(0, "abc");
+    host.send_ops(ops).await;
+    guest.receive_ops().await;
+    let deleted_anchor = host.doc.get_anchor(2);
+
+    // The host deletes the "b" while the guest types after it.
+    let host_ops = host.doc.local_delete(1, 1);
+    let guest_ops = guest.doc.local_insert(2, "X");
+    tokio::join!(host.send_ops(host_ops), guest.send_ops(guest_ops));
+    tokio::join!(host.receive_ops(), guest.receive_ops());
+    assert_eq!(host.doc.get_text(), "aXc");
+    assert_eq!(guest.doc.get_text(), host.doc.get_text());
+
+    // Once each peer knows that the other has applied its ops, the "b" is dropped by both.
+    host.doc.purge_tombstones(guest.doc.get_remote_op_count());
+    guest.doc.purge_tombstones(host.doc.get_remote_op_count());
+    assert_eq!(host.doc.get_index_after_anchor(deleted_anchor), 0);
+    assert_eq!(guest.doc.get_index_after_anchor(deleted_anchor), 0);
+
+    // Both peers type where the "b" was.
+    let host_ops = host.doc.local_insert(1, "hh");
+    let guest_ops = guest.doc.local_insert(1, "gg");
+    tokio::join!(host.send_ops(host_ops), guest.send_ops(guest_ops));
+    tokio::join!(host.receive_ops(), guest.receive_ops());
+    let text = host.doc.get_text();
+    assert!(text == "ahhggXc" || text == "agghhXc", "{}", text);
+    assert_eq!(guest.doc.get_text(), text);
+  }
+
+  #[tokio::test]
+  async fn test_buffer_bigger_than_a_frame_is_shared() {
+    let (mut host, mut guest) = connect_loopback_peers().await;
+    let host_writer = &mut host.writer;
+
+    let config = CollabConfig {
+      editor_id: "editor".into(),
+      mode: CollabMode::Host(CollabAddr::parse("127.0.0.1")),
+      user_name: "test".into(),
+      pairing_code: PairingCode::parse("123456").unwrap(),
+    };
+    let mut host_collab = CollabState::new(config.clone(), 1);
+    let mut guest_collab = CollabState::new(config, 2);
+    let line = "fn main() { println!(\"hello world\"); }";
+    let content = vec![line; 400].join("\n");
+    let host_buffer = EditorBuffer::new_from_content(&content, "main.rs");
+    let mut guest_buffer = EditorBuffer::default();
+    host_collab.record_local_edit(&host_buffer);
+
+    let all_ops_message = CollabMessage::Ops {
+      site_id: 1,
+      ops: host_collab.doc.lock().unsent_ops.clone(),
+    };
+    assert!(serde_json::to_vec(&all_ops_message).unwrap().len() > MAX_FRAME_SIZE);
+
+    // Send the buffer the way a collab session does (before the peer has seen any of it).
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let outbox = CollabOutbox::new(sender);
+    let host_state = EditorState {
+      collab: Some(host_collab),
+      ..Default::default()
+    };
+    outbox.send_local_changes(&host_state);
+    drop(outbox);
+
+    // The caret is sent after the ops.
+    let write_all = async {
+      while let Some(message) = receiver.recv().await {
+        write_collab_message(host_writer, &message).await.unwrap();
+      }
+    };
+    let read_all = async {
+      let mut ops_message_count = 0;
+      loop {
+        match read_collab_message(&mut guest.reader).await.unwrap() {
+          Some(CollabMessage::Ops { ops, .. }) => {
+            guest_collab.apply_remote_ops(&ops, &mut guest_buffer);
+            ops_message_count += 1;
+          }
+          Some(CollabMessage::Caret { .. }) => break ops_message_count,
+          other => panic!("Expected ops or the caret, got: {:?}", other),
+        }
+      }
+    };
+    let ((), ops_message_count) = tokio::join!(write_all, read_all);
+
+    assert!(ops_message_count > 1);
+    assert_eq!(guest_buffer.lines, host_buffer.lines);
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_edits_to_buffers_converge_over_unix_socket() {
+    let (host_stream, guest_stream) = tokio::net::UnixStream::pair().unwrap();
+    let (host_channel, guest_channel) = pair_streams(host_stream, guest_stream).await;
+    let SecureChannel {
+      reader: mut host_reader,
+      writer: mut host_writer,
+    } = host_channel;
+    let SecureChannel {
+      reader: mut guest_reader,
+      writer: mut guest_writer,
+    } = guest_channel;
+
+    let addr = CollabAddr::parse("unix:/tmp/cmdr.sock");
+    let config = |mode| CollabConfig {
+      editor_id: "editor".into(),
+      mode,
+      user_name: "test".into(),
+      pairing_code: PairingCode::parse("123456").unwrap(),
+    };
+    let mut host_collab = CollabState::new(config(CollabMode::Host(addr.clone())), 1);
+    let mut guest_collab = CollabState::new(config(CollabMode::Join(addr)), 2);
+    let mut host_buffer = EditorBuffer::new_from_content("fn main() {\n}\n", "main.rs");
+    let mut guest_buffer = EditorBuffer::default();
+
+    host_collab.record_local_edit(&host_buffer);
+    let host_ops = std::mem::take(&mut host_collab.doc.lock().unsent_ops);
+    send_ops(&mut host_writer, 1, host_ops).await;
+    let ops = receive_ops(&mut guest_reader).await;
+    guest_collab.apply_remote_ops(&ops, &mut guest_buffer);
+    assert_eq!(guest_buffer.lines, host_buffer.lines);
+
+    // Type on both ends at once.
+    let size: Size = (80, 10).into();
+    host_buffer.caret = (11, 0).into();
+    host_buffer.apply_editor_event(&EditorEvent::InsertNewLine, size);
+    host_buffer.apply_editor_event(&EditorEvent::InsertText("  todo!()".into()), size);
+    host_collab.record_local_edit(&host_buffer);
+    guest_buffer.caret = (3, 0).into();
+    for _ in 0..4 {
+      guest_buffer.apply_editor_event(&EditorEvent::Delete, size);
+    }
+    guest_buffer.apply_editor_event(&EditorEvent::InsertText("run".into()), size);
+    guest_collab.record_local_edit(&guest_buffer);
+
+    let host_ops = std::mem::take(&mut host_collab.doc.lock().unsent_ops);
+    let guest_ops = std::mem::take(&mut guest_collab.doc.lock().unsent_ops);
+    tokio::join!(
+      send_ops(&mut host_writer, 1, host_ops),
+      send_ops(&mut guest_writer, 2, guest_ops)
+    );
+    let (host_ops, guest_ops) = tokio::join!(
+      receive_ops(&mut host_reader),
+      receive_ops(&mut guest_reader)
+    );
+    host_collab.apply_remote_ops(&host_ops, &mut host_buffer);
+    guest_collab.apply_remote_ops(&guest_ops, &mut guest_buffer);
+
+    assert_eq!(host_buffer.lines, vec!["fn run() {", "  todo!()", "}"]);
+    assert_eq!(guest_buffer.lines, host_buffer.lines);
+  }
+}
@@ -0,0 +1,284 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{collections::BTreeMap,
+          fmt::{Display, Formatter},
+          sync::{Arc, Mutex, MutexGuard}};
+
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::{common::*, ex_editor::*, pairing::*};
+
+/// Port that a collab session is hosted on, if one isn't given.
+pub const DEFAULT_COLLAB_PORT: u16 = 8421;
+
+/// Where a collab session is hosted: a TCP address (eg: `192.168.1.5:8421`), or the path of a Unix
+/// socket (eg: `unix:/tmp/cmdr.sock`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CollabAddr {
+  Tcp(String),
+  Unix(String),
+}
+
+impl CollabAddr {
+  /// A TCP address w/out a port gets the [DEFAULT_COLLAB_PORT].
+  pub fn parse(addr: &str) -> Self {
+    match addr.strip_prefix("unix:") {
+      Some(path) => CollabAddr::Unix(path.to_string()),
+      None if addr.contains(':') => CollabAddr::Tcp(addr.to_string()),
+      None => CollabAddr::Tcp(format!("{}:{}", addr, DEFAULT_COLLAB_PORT)),
+    }
+  }
+}
+
+impl Display for CollabAddr {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      CollabAddr::Tcp(addr) => write!(f, "{}", addr),
+      CollabAddr::Unix(path) => write!(f, "unix:{}", path),
+    }
+  }
+}
+
+/// The host shares its buffer, & waits for a peer to join. The peer that joins starts w/ an empty
+/// buffer, which is filled in w/ the host's text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CollabMode {
+  Host(CollabAddr),
+  Join(CollabAddr),
+}
+
+impl CollabMode {
+  pub fn get_addr(&self) -> &CollabAddr {
+    match self {
+      CollabMode::Host(addr) | CollabMode::Join(addr) => addr,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CollabConfig {
+  /// Id of the editor whose buffer is shared.
+  pub editor_id: String,
+  pub mode: CollabMode,
+  pub user_name: String,
+  /// Shown by the host, & typed in by the peer that joins (see [crate::pairing]).
+  pub pairing_code: PairingCode,
+}
+
+/// A peer that has joined the session, & where their caret is.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemotePeer {
+  pub user_name: String,
+  pub caret_anchor: Option<CharId>,
+}
+
+/// The parts of a collab session that grow w/ it: the `doc` (w/ its tombstones), & the local ops
+/// (in order) that the session hasn't sent yet.
+#[derive(Debug)]
+pub struct CollabDoc {
+  pub doc: CrdtDoc,
+  pub unsent_ops: Vec<CrdtOp>,
+}
+
+/// A [CollabDoc] that is shared by every copy of the [CollabState], instead of being cloned along
+/// w/ the rest of the [EditorState] for each action. Copies are equal if they share the same doc.
+#[derive(Clone, Debug)]
+pub struct SharedCollabDoc(Arc<Mutex<CollabDoc>>);
+
+impl SharedCollabDoc {
+  pub fn new(site_id: u64) -> Self {
+    Self(Arc::new(Mutex::new(CollabDoc {
+      doc: CrdtDoc::new(site_id),
+      unsent_ops: vec![],
+    })))
+  }
+
+  /// The doc is never left half changed, so it can still be used if a thread panicked w/ it
+  /// locked.
+  pub fn lock(&self) -> MutexGuard<'_, CollabDoc> {
+    self.0.lock().unwrap_or_else(|error| error.into_inner())
+  }
+}
+
+impl PartialEq for SharedCollabDoc {
+  fn eq(&self, other: &Self) -> bool { Arc::ptr_eq(&self.0, &other.0) }
+}
+
+impl Eq for SharedCollabDoc {}
+
+/// State of the collab session (for the editor w/ the id in the `config`). The text of the shared
+/// buffer is kept in the `doc`, & every local change to the buffer is turned into ops (which are
+/// kept in the `doc` until the session sends them).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CollabState {
+  pub config: CollabConfig,
+  pub site_id: u64,
+  pub doc: SharedCollabDoc,
+  /// The local caret, as the id of the char before it (see [CrdtDoc::get_anchor]).
+  pub local_caret_anchor: Option<CharId>,
+  /// Keyed by site id.
+  pub remote_peers: BTreeMap<u64, RemotePeer>,
+  /// The [LinesRevision] id & the caret of the buffer that the `doc` (& the local caret anchor)
+  /// were last brought up to date w/.
+  recorded_lines_revision_id: u64,
+  maybe_recorded_caret: Option<Position>,
+}
+
+impl CollabState {
+  pub fn new(config: CollabConfig, site_id: u64) -> Self {
+    Self {
+      config,
+      site_id,
+      doc: SharedCollabDoc::new(site_id),
+      local_caret_anchor: None,
+      remote_peers: BTreeMap::new(),
+      recorded_lines_revision_id: 0,
+      maybe_recorded_caret: None,
+    }
+  }
+
+  pub fn get_site_id(&self) -> u64 { self.site_id }
+
+  /// The host is waiting for a peer until one says hello.
+  pub fn is_waiting_for_peer(&self) -> bool {
+    matches!(self.config.mode, CollabMode::Host(_)) && self.remote_peers.is_empty()
+  }
+
+  /// Turn the edits to the buffer (since its lines were last recorded, see [LinesRevision]) into
+  /// ops, & keep track of the local caret. Nothing is done if neither the lines nor the caret have
+  /// changed, so this can run after every action.
+  pub fn record_local_edit(&mut self, editor_buffer: &EditorBuffer) {
+    let lines_revision_id = editor_buffer.lines_revision.id;
+    let is_edited = lines_revision_id != self.recorded_lines_revision_id;
+    if !is_edited && self.maybe_recorded_caret == Some(editor_buffer.caret) {
+      return;
+    }
+
+    if is_edited {
+      self.record_local_text(&editor_buffer.lines);
+    }
+
+    let caret_index = get_char_index(&editor_buffer.lines, editor_buffer.caret);
+    self.local_caret_anchor = self.doc.lock().doc.get_anchor(caret_index);
+    self.recorded_lines_revision_id = lines_revision_id;
+    self.maybe_recorded_caret = Some(editor_buffer.caret);
+  }
+
+  /// The text between the common prefix & suffix (of the `doc` & the `lines`) is replaced.
+  fn record_local_text(&mut self, lines: &[String]) {
+    let CollabDoc { doc, unsent_ops } = &mut *self.doc.lock();
+    let old_text: Vec<char> = doc.get_text().chars().collect();
+    let new_text: Vec<char> = lines.join("\n").chars().collect();
+
+    if old_text != new_text {
+      let prefix_len = old_text
+        .iter()
+        .zip(&new_text)
+        .take_while(|(old_ch, new_ch)| old_ch == new_ch)
+        .count();
+      let max_suffix_len = old_text.len().min(new_text.len()) - prefix_len;
+      let suffix_len = old_text
+        .iter()
+        .rev()
+        .zip(new_text.iter().rev())
+        .take(max_suffix_len)
+        .take_while(|(old_ch, new_ch)| old_ch == new_ch)
+        .count();
+
+      let deleted_count = old_text.len() - prefix_len - suffix_len;
+      let inserted_text: String = new_text[prefix_len..new_text.len() - suffix_len]
+        .iter()
+        .collect();
+      let delete_ops = doc.local_delete(prefix_len, deleted_count);
+      let insert_ops = doc.local_insert(prefix_len, &inserted_text);
+      unsent_ops.extend(delete_ops);
+      unsent_ops.extend(insert_ops);
+    }
+  }
+
+  /// Apply ops from a peer to the `doc`, & replace the lines of the buffer w/ its text. The caret
+  /// stays next to the char that it was after, & is anchored to the char that is before it now
+  /// (since the one that it was after may have been deleted, & be purged later).
+  pub fn apply_remote_ops(&mut self, ops: &[CrdtOp], editor_buffer: &mut EditorBuffer) {
+    let doc = &mut self.doc.lock().doc;
+    doc.apply_remote_ops(ops);
+    let lines: Vec<String> = doc.get_text().split('\n').map(String::from).collect();
+    let first_edited_row = lines
+      .iter()
+      .zip(&editor_buffer.lines)
+      .take_while(|(line, old_line)| line == old_line)
+      .count();
+    editor_buffer.lines = lines;
+    editor_buffer.clear_extra_carets_and_selection();
+    let caret_index = doc.get_index_after_anchor(self.local_caret_anchor);
+    editor_buffer.caret = get_position(&editor_buffer.lines, caret_index);
+    self.local_caret_anchor = doc.get_anchor(caret_index);
+    editor_buffer.mark_edited(first_edited_row);
+    self.recorded_lines_revision_id = editor_buffer.lines_revision.id;
+    self.maybe_recorded_caret = Some(editor_buffer.caret);
+  }
+
+  /// Where the carets of the peers are in the `lines`, w/ their site ids.
+  pub fn get_remote_carets(&self, lines: &[String]) -> Vec<(u64, Position)> {
+    let doc = &self.doc.lock().doc;
+    self
+      .remote_peers
+      .iter()
+      .map(|(site_id, remote_peer)| {
+        let index = doc.get_index_after_anchor(remote_peer.caret_anchor);
+        (*site_id, get_position(lines, index))
+      })
+      .collect()
+  }
+}
+
+/// Index (in chars, counting a new line between each line) of the `pos` (line index & grapheme
+/// index) in the `lines`.
+pub fn get_char_index(lines: &[String], pos: Position) -> usize {
+  let row = (pos.row as usize).min(lines.len().saturating_sub(1));
+  let chars_before: usize = lines[..row]
+    .iter()
+    .map(|line| line.chars().count() + 1)
+    .sum();
+  let line = &lines[row];
+  chars_before
+    + line[..grapheme_byte_index(line, pos.col as usize)]
+      .chars()
+      .count()
+}
+
+/// The position (line index & grapheme index) of the char `index` in the `lines`.
+pub fn get_position(lines: &[String], index: usize) -> Position {
+  let mut chars_before = 0;
+  for (row, line) in lines.iter().enumerate() {
+    let line_char_count = line.chars().count();
+    if index <= chars_before + line_char_count || row + 1 == lines.len() {
+      let char_index = (index - chars_before).min(line_char_count);
+      let byte_index = line
+        .char_indices()
+        .nth(char_index)
+        .map(|(byte_index, _)| byte_index)
+        .unwrap_or(line.len());
+      let col = grapheme_count(&line[..byte_index]);
+      return position!(col: col as UnitType, row: row as UnitType);
+    }
+    chars_before += line_char_count + 1;
+  }
+  Position::default()
+}
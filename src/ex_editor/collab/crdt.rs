@@ -0,0 +1,277 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+/// Unique id of a char in a [CrdtDoc]: the (Lamport) clock of the site that inserted it, & the id
+/// of that site. Ids are ordered by clock & then by site, which is how chars that are inserted at
+/// the same place at the same time (by different sites) are ordered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CharId {
+  pub clock: u64,
+  pub site_id: u64,
+}
+
+/// Edits to a [CrdtDoc], which are sent to the other sites. They can be applied more than once, &
+/// in any order (ops that refer to chars that haven't arrived yet wait for them), until the chars
+/// that they refer to are purged (see [CrdtDoc::purge_tombstones]).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrdtOp {
+  /// Insert `ch` right after the char w/ id `after` (or at the start of the doc).
+  Insert {
+    id: CharId,
+    after: Option<CharId>,
+    ch: char,
+  },
+  Delete {
+    id: CharId,
+  },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CrdtChar {
+  ch: char,
+  /// Deleted chars are kept (as tombstones), since ops from other sites may refer to them.
+  is_deleted: bool,
+  /// The ids of the chars before & after this one in the doc.
+  prev: Option<CharId>,
+  next: Option<CharId>,
+}
+
+/// Text that can be edited at many sites at once, & converges to the same content at every site
+/// once they have all applied the same ops (a Replicated Growable Array). Indexes are in chars &
+/// only count the chars that aren't deleted.
+///
+/// The chars are kept in a list that is linked by their ids, so an op finds the char that it
+/// refers to (& inserts a char after it) w/out scanning the doc.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CrdtDoc {
+  pub site_id: u64,
+  clock: u64,
+  chars: HashMap<CharId, CrdtChar>,
+  first: Option<CharId>,
+  /// Remote ops that can't be applied until the chars that they refer to arrive.
+  pending_ops: Vec<CrdtOp>,
+  /// How many ops were made at this site, & how many were received from the other site.
+  local_op_count: u64,
+  remote_op_count: u64,
+  /// The deleted chars, in the order that they were deleted, w/ the `local_op_count` at the time.
+  tombstones: VecDeque<(u64, CharId)>,
+}
+
+impl CrdtDoc {
+  pub fn new(site_id: u64) -> Self {
+    Self {
+      site_id,
+      ..Default::default()
+    }
+  }
+
+  pub fn get_text(&self) -> String {
+    self
+      .visible_chars()
+      .map(|(_, crdt_char)| crdt_char.ch)
+      .collect()
+  }
+
+  /// Insert the `text` at `index`, & return the ops to send to the other sites.
+  pub fn local_insert(&mut self, index: usize, text: &str) -> Vec<CrdtOp> {
+    let mut after = self.get_anchor(index);
+    let mut ops = vec![];
+    for ch in text.chars() {
+      self.clock += 1;
+      let id = CharId {
+        clock: self.clock,
+        site_id: self.site_id,
+      };
+      let op = CrdtOp::Insert { id, after, ch };
+      self.local_op_count += 1;
+      self.apply_op(&op);
+      ops.push(op);
+      after = Some(id);
+    }
+    ops
+  }
+
+  /// Delete `count` chars starting at `index`, & return the ops to send to the other sites.
+  pub fn local_delete(&mut self, index: usize, count: usize) -> Vec<CrdtOp> {
+    let ids: Vec<CharId> = self
+      .visible_chars()
+      .skip(index)
+      .take(count)
+      .map(|(id, _)| id)
+      .collect();
+    ids
+      .into_iter()
+      .map(|id| {
+        let op = CrdtOp::Delete { id };
+        self.local_op_count += 1;
+        self.apply_op(&op);
+        op
+      })
+      .collect()
+  }
+
+  /// Apply the ops from another site. Ops that refer to chars that haven't arrived yet are kept
+  /// until they do.
+  pub fn apply_remote_ops(&mut self, ops: &[CrdtOp]) {
+    self.remote_op_count += ops.len() as u64;
+    self.pending_ops.extend(ops.iter().cloned());
+    loop {
+      let pending_ops = std::mem::take(&mut self.pending_ops);
+      let pending_count = pending_ops.len();
+      for op in pending_ops {
+        if !self.apply_op(&op) {
+          self.pending_ops.push(op);
+        }
+      }
+      if self.pending_ops.len() == pending_count {
+        break;
+      }
+    }
+  }
+
+  /// The number of ops that have been received from the other site, which it passes back to
+  /// [CrdtDoc::purge_tombstones] once it knows that they've been applied here.
+  pub fn get_remote_op_count(&self) -> u64 { self.remote_op_count }
+
+  /// Drop the deleted chars that no op can refer to any more, now that the other site has applied
+  /// the first `applied_op_count` ops that were made here. A char is dropped once the other site
+  /// has applied its delete (& every op that was made here before it was deleted). Every op that
+  /// the other site made before it applied those arrived here before it said so, & the ops that
+  /// it makes after never refer to a deleted char. That's only true of 2 sites that send their
+  /// ops to each other in order (as a collab session does).
+  pub fn purge_tombstones(&mut self, applied_op_count: u64) {
+    while let Some((_, id)) = self
+      .tombstones
+      .front()
+      .filter(|(local_op_count, _)| *local_op_count <= applied_op_count)
+      .copied()
+    {
+      self.tombstones.pop_front();
+      let crdt_char = match self.chars.remove(&id) {
+        Some(crdt_char) => crdt_char,
+        None => continue,
+      };
+      match crdt_char.prev.and_then(|prev| self.chars.get_mut(&prev)) {
+        Some(prev_char) => prev_char.next = crdt_char.next,
+        None => self.first = crdt_char.next,
+      }
+      if let Some(next_char) = crdt_char.next.and_then(|next| self.chars.get_mut(&next)) {
+        next_char.prev = crdt_char.prev;
+      }
+    }
+  }
+
+  /// Id of the (not deleted) char before `index`, which is where text inserted at `index` goes
+  /// after. It is also used to keep track of a caret, since it doesn't move when text is inserted
+  /// or deleted elsewhere.
+  pub fn get_anchor(&self, index: usize) -> Option<CharId> {
+    match index {
+      0 => None,
+      _ => self.visible_chars().nth(index - 1).map(|(id, _)| id),
+    }
+  }
+
+  /// Index right after the char w/ id `anchor` (which may have been deleted since).
+  pub fn get_index_after_anchor(&self, anchor: Option<CharId>) -> usize {
+    let anchor = match anchor.filter(|anchor| self.chars.contains_key(anchor)) {
+      Some(anchor) => anchor,
+      None => return 0,
+    };
+    let mut index = 0;
+    for (id, crdt_char) in self.iter_chars() {
+      if !crdt_char.is_deleted {
+        index += 1;
+      }
+      if id == anchor {
+        break;
+      }
+    }
+    index
+  }
+
+  /// Returns false if the op refers to a char that hasn't arrived yet. Applying an op again has no
+  /// effect.
+  fn apply_op(&mut self, op: &CrdtOp) -> bool {
+    match op {
+      CrdtOp::Insert { id, after, ch } => {
+        if self.chars.contains_key(id) {
+          return true;
+        }
+        let mut maybe_next = match after {
+          None => self.first,
+          Some(after) => match self.chars.get(after) {
+            Some(after_char) => after_char.next,
+            None => return false,
+          },
+        };
+        // Skip the chars that were inserted after the same char by (logically) later inserts, &
+        // the chars that were inserted after those.
+        let mut maybe_prev = *after;
+        while let Some(next) = maybe_next.filter(|next| next > id) {
+          maybe_prev = Some(next);
+          maybe_next = self.chars[&next].next;
+        }
+        self.chars.insert(
+          *id,
+          CrdtChar {
+            ch: *ch,
+            is_deleted: false,
+            prev: maybe_prev,
+            next: maybe_next,
+          },
+        );
+        match maybe_prev.and_then(|prev| self.chars.get_mut(&prev)) {
+          Some(prev_char) => prev_char.next = Some(*id),
+          None => self.first = Some(*id),
+        }
+        if let Some(next_char) = maybe_next.and_then(|next| self.chars.get_mut(&next)) {
+          next_char.prev = Some(*id);
+        }
+        self.clock = self.clock.max(id.clock);
+        true
+      }
+      CrdtOp::Delete { id } => match self.chars.get_mut(id) {
+        Some(crdt_char) => {
+          if !crdt_char.is_deleted {
+            crdt_char.is_deleted = true;
+            self.tombstones.push_back((self.local_op_count, *id));
+          }
+          true
+        }
+        None => false,
+      },
+    }
+  }
+
+  /// The chars (incl. the deleted ones) in the order of the doc.
+  fn iter_chars(&self) -> impl Iterator<Item = (CharId, &CrdtChar)> {
+    std::iter::successors(
+      self.first.map(|id| (id, &self.chars[&id])),
+      |(_, crdt_char)| crdt_char.next.map(|id| (id, &self.chars[&id])),
+    )
+  }
+
+  fn visible_chars(&self) -> impl Iterator<Item = (CharId, &CrdtChar)> {
+    self
+      .iter_chars()
+      .filter(|(_, crdt_char)| !crdt_char.is_deleted)
+  }
+}
@@ -0,0 +1,32 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Collaborative editing, where two `r3bl-cmdr` processes share an editor buffer over a TCP or
+//! Unix socket. The text is kept in a [CrdtDoc] (a replicated growable array), so edits that the
+//! peers make at the same time merge the same way on both ends, w/out a server to order them.
+
+// Attach sources.
+pub mod collab_message;
+pub mod collab_session;
+pub mod collab_state;
+pub mod crdt;
+
+// Re-export.
+pub use collab_message::*;
+pub use collab_session::*;
+pub use collab_state::*;
+pub use crdt::*;
@@ -0,0 +1,28 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::common::*;
+
+pub fn debug_log_editor_action(src: String, action: EditorAction) {
+  call_if_true!(
+    is_logging_enabled(),
+    log_no_err!(INFO, "🚀 {} -> dispatch action: {}", src, action,)
+  );
+}
@@ -0,0 +1,473 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use r3bl_rs_utils::*;
+
+use crate::common::*;
+
+/// Directions that the caret can be moved in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaretDirection {
+  Up,
+  Down,
+  Left,
+  Right,
+  PageUp,
+  PageDown,
+  Home,
+  End,
+}
+
+/// Data for a single editor:
+/// 1. `lines`: the text being edited, one [String] per line (there is always at least one line).
+/// 2. `caret`: the insertion point, in buffer coordinates (`row` is the line index, `col` is the
+///    index of the grapheme cluster in that line).
+/// 3. `extra_carets`: more insertion points (see [EditorBuffer::edit_at_each_caret]), which are
+///    edited & moved along w/ the `caret`. The viewport follows the `caret` only.
+/// 4. `maybe_selection_anchor`: the other end of the selection (the text between it & the `caret`),
+///    if there is one. There are no `extra_carets` while there is a selection.
+/// 5. `viewport`: the part of the lines that is painted inside the box (it is scrolled to keep the
+///    caret visible).
+/// 6. `soft_wrap`: true if lines that are wider than the box are laid out across multiple visual
+///    rows (see [wrap_line]), instead of scrolling horizontally.
+/// 7. `file_path`: the file that the lines were loaded from & are saved to (if any).
+/// 8. `is_dirty`: true if the lines have been modified since they were last loaded or saved.
+/// 9. `lines_revision`: which version of the lines these are (see [LinesRevision]).
+/// 10. `saved_lines_revision_id`: the id of the version of the lines that was last loaded or saved.
+///     Undoing (or redoing) back to that version makes the buffer clean again.
+/// 11. `line_ending` & `has_final_new_line`: how the lines were separated (& ended) in the file, so
+///     that they are written back the same way.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EditorBuffer {
+  pub lines: Vec<String>,
+  pub caret: Position,
+  pub extra_carets: Vec<Position>,
+  pub maybe_selection_anchor: Option<Position>,
+  pub viewport: Viewport,
+  pub soft_wrap: bool,
+  pub file_path: Option<String>,
+  pub is_dirty: bool,
+  pub lines_revision: LinesRevision,
+  pub saved_lines_revision_id: u64,
+  pub line_ending: LineEnding,
+  pub has_final_new_line: bool,
+}
+
+impl Default for EditorBuffer {
+  fn default() -> Self {
+    let lines_revision = LinesRevision::new();
+    Self {
+      lines: vec![String::new()],
+      caret: Position::default(),
+      extra_carets: vec![],
+      maybe_selection_anchor: None,
+      viewport: Viewport::default(),
+      soft_wrap: false,
+      file_path: None,
+      is_dirty: false,
+      lines_revision,
+      saved_lines_revision_id: lines_revision.id,
+      line_ending: LineEnding::default(),
+      has_final_new_line: true,
+    }
+  }
+}
+
+/// What the lines in a file are separated by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LineEnding {
+  #[default]
+  Lf,
+  CrLf,
+}
+
+impl LineEnding {
+  /// The line ending of the 1st line in the `content` (files w/ mixed line endings are saved w/
+  /// that one).
+  pub fn detect(content: &str) -> Self {
+    match content.find('\n') {
+      Some(index) if content[..index].ends_with('\r') => LineEnding::CrLf,
+      _ => LineEnding::Lf,
+    }
+  }
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      LineEnding::Lf => "\n",
+      LineEnding::CrLf => "\r\n",
+    }
+  }
+}
+
+/// Ids are never reused, so no two versions of the lines (in any buffer) have the same id.
+static NEXT_LINES_REVISION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A version of the lines in a buffer. Each edit gives the lines a new `id`, & records the id of
+/// the version that it was made to (`prior_id`) & the first line that it changed. Anything that was
+/// worked out from the lines above `first_edited_row` (eg: their syntax highlighting) can be kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinesRevision {
+  pub id: u64,
+  pub prior_id: u64,
+  pub first_edited_row: usize,
+}
+
+impl Default for LinesRevision {
+  fn default() -> Self { Self::new() }
+}
+
+impl LinesRevision {
+  /// A version that isn't based on any other (eg: for lines that were just loaded).
+  pub fn new() -> Self {
+    Self {
+      id: NEXT_LINES_REVISION_ID.fetch_add(1, Ordering::Relaxed),
+      prior_id: 0,
+      first_edited_row: 0,
+    }
+  }
+
+  /// The version after an edit (to this one) that didn't change the lines above `first_edited_row`.
+  pub fn next(&self, first_edited_row: usize) -> Self {
+    Self {
+      prior_id: self.id,
+      first_edited_row,
+      ..Self::new()
+    }
+  }
+}
+
+impl EditorBuffer {
+  /// Create a (clean) buffer w/ the given content, that is saved to `file_path`.
+  pub fn new_from_content(content: &str, file_path: &str) -> Self {
+    let line_ending = LineEnding::detect(content);
+    let maybe_content_wo_final_new_line = content
+      .strip_suffix(line_ending.as_str())
+      .or_else(|| content.strip_suffix('\n'));
+    let lines = maybe_content_wo_final_new_line
+      .unwrap_or(content)
+      .split('\n')
+      .map(|line| match line_ending {
+        LineEnding::Lf => line,
+        LineEnding::CrLf => line.strip_suffix('\r').unwrap_or(line),
+      })
+      .map(String::from)
+      .collect();
+
+    Self {
+      lines,
+      file_path: Some(file_path.to_string()),
+      line_ending,
+      has_final_new_line: maybe_content_wo_final_new_line.is_some(),
+      ..Default::default()
+    }
+  }
+
+  /// The lines joined (& ended) the way that they were in the file, which is what gets written to
+  /// it.
+  pub fn get_content(&self) -> String {
+    let mut content = self.lines.join(self.line_ending.as_str());
+    if self.has_final_new_line {
+      content.push_str(self.line_ending.as_str());
+    }
+    content
+  }
+
+  /// Mark the buffer dirty, & give its lines a new revision. The lines above `first_edited_row`
+  /// must not have been changed.
+  pub fn mark_edited(&mut self, first_edited_row: usize) {
+    self.is_dirty = true;
+    self.lines_revision = self.lines_revision.next(first_edited_row);
+  }
+
+  /// The lines w/ the revision id `saved_lines_revision_id` are the ones in the file now. The
+  /// buffer is only clean if it hasn't been edited since they were saved.
+  pub fn mark_saved(&mut self, saved_lines_revision_id: u64) {
+    self.is_dirty = self.lines_revision.id != saved_lines_revision_id;
+    self.saved_lines_revision_id = saved_lines_revision_id;
+  }
+}
+
+// Edit the lines in the buffer.
+impl EditorBuffer {
+  pub fn insert_char(&mut self, ch: char) {
+    let byte_index = self.caret_byte_index();
+    let line = self.current_line_mut();
+    line.insert(byte_index, ch);
+    // The char might combine w/ the grapheme before it (eg: a combining accent or a ZWJ), so the
+    // caret is moved past the grapheme that contains it, instead of by 1.
+    let new_caret_col = grapheme_count(&line[..byte_index + ch.len_utf8()]);
+    self.caret.col = new_caret_col as UnitType;
+  }
+
+  /// Split the current line at the caret, and move the caret to the start of the new line.
+  pub fn insert_new_line(&mut self) {
+    let byte_index = self.caret_byte_index();
+    let rest_of_line = self.current_line_mut().split_off(byte_index);
+    let row = self.caret.row as usize;
+    self.lines.insert(row + 1, rest_of_line);
+    self.caret = (0, self.caret.row + 1).into();
+  }
+
+  /// Backspace: delete the grapheme cluster to the left of the caret. If the caret is at the start
+  /// of the line, then join this line w/ the previous one. Returns false if there was nothing to
+  /// delete.
+  pub fn delete_prev_char(&mut self) -> bool {
+    if self.caret.col > 0 {
+      self.caret.col -= 1;
+      self.remove_caret_grapheme();
+    } else if self.caret.row > 0 {
+      let row = self.caret.row as usize;
+      let line = self.lines.remove(row);
+      let prev_line = &mut self.lines[row - 1];
+      let prev_line_len = grapheme_count(prev_line);
+      prev_line.push_str(&line);
+      self.caret = (prev_line_len as UnitType, self.caret.row - 1).into();
+    } else {
+      return false;
+    }
+    true
+  }
+
+  /// Delete: delete the grapheme cluster under the caret. If the caret is at the end of the line,
+  /// then join the next line w/ this one. Returns false if there was nothing to delete.
+  pub fn delete_caret_char(&mut self) -> bool {
+    let row = self.caret.row as usize;
+    if (self.caret.col as usize) < self.current_line_len() {
+      self.remove_caret_grapheme();
+    } else if row + 1 < self.lines.len() {
+      let next_line = self.lines.remove(row + 1);
+      self.current_line_mut().push_str(&next_line);
+    } else {
+      return false;
+    }
+    true
+  }
+
+  fn remove_caret_grapheme(&mut self) {
+    let col = self.caret.col as usize;
+    let line = self.current_line_mut();
+    let start_byte_index = grapheme_byte_index(line, col);
+    let end_byte_index = grapheme_byte_index(line, col + 1);
+    line.replace_range(start_byte_index..end_byte_index, "");
+  }
+}
+
+// Move the caret & scroll the viewport.
+impl EditorBuffer {
+  /// Left & Right move the caret by one grapheme cluster. Up & Down (and PageUp & PageDown, which
+  /// move by the number of rows in the viewport) keep the caret in the same display col (as far as
+  /// possible), so that it doesn't drift when lines have wide chars. In soft wrap mode they move by
+  /// visual row instead of by line. Home & End move the caret to the start & end of the line.
+  pub fn move_caret(&mut self, direction: CaretDirection, viewport_size: Size) {
+    let row = self.caret.row as usize;
+    match direction {
+      CaretDirection::Left => {
+        if self.caret.col > 0 {
+          self.caret.col -= 1;
+        } else if row > 0 {
+          self.caret = (self.line_len(row - 1) as UnitType, self.caret.row - 1).into();
+        }
+      }
+      CaretDirection::Right => {
+        if (self.caret.col as usize) < self.current_line_len() {
+          self.caret.col += 1;
+        } else if row + 1 < self.lines.len() {
+          self.caret = (0, self.caret.row + 1).into();
+        }
+      }
+      CaretDirection::Up | CaretDirection::Down if self.soft_wrap => {
+        self.move_caret_by_wrapped_row(direction == CaretDirection::Up, viewport_size.cols);
+      }
+      CaretDirection::Up => {
+        if row > 0 {
+          self.move_caret_to_row(row - 1);
+        }
+      }
+      CaretDirection::Down => {
+        if row + 1 < self.lines.len() {
+          self.move_caret_to_row(row + 1);
+        }
+      }
+      CaretDirection::PageUp | CaretDirection::PageDown if self.soft_wrap => {
+        let is_up = direction == CaretDirection::PageUp;
+        if is_up {
+          self.viewport.page_up(viewport_size);
+        } else {
+          let wrapped_row_count = wrap_lines(&self.lines, viewport_size.cols).len();
+          self.viewport.page_down(wrapped_row_count, viewport_size);
+        }
+        for _ in 0..viewport_size.rows {
+          self.move_caret_by_wrapped_row(is_up, viewport_size.cols);
+        }
+      }
+      CaretDirection::PageUp => {
+        self.viewport.page_up(viewport_size);
+        self.move_caret_to_row(row.saturating_sub(viewport_size.rows as usize));
+      }
+      CaretDirection::PageDown => {
+        self.viewport.page_down(self.lines.len(), viewport_size);
+        let last_row = self.lines.len() - 1;
+        self.move_caret_to_row((row + viewport_size.rows as usize).min(last_row));
+      }
+      CaretDirection::Home => self.caret.col = 0,
+      CaretDirection::End => self.caret.col = self.current_line_len() as UnitType,
+    }
+  }
+
+  /// Scroll the viewport (if needed) so that the caret is inside it. A wide grapheme under the
+  /// caret has to fit in the viewport, not just its 1st col.
+  pub fn scroll_caret_into_view(&mut self, viewport_size: Size) {
+    let caret_display_pos = self.caret_display_pos(viewport_size.cols);
+    self.viewport.scroll_into_view(
+      caret_display_pos.row,
+      caret_display_pos.col,
+      caret_display_pos.col + self.caret_display_width(),
+      viewport_size,
+    );
+    if self.soft_wrap {
+      self.viewport.offset.col = 0;
+    }
+  }
+
+  /// The viewport is reset, since its `offset.row` is a line index in one mode & a visual row index
+  /// in the other.
+  pub fn toggle_soft_wrap(&mut self) {
+    self.soft_wrap = !self.soft_wrap;
+    self.viewport = Viewport::default();
+  }
+
+  fn move_caret_to_row(&mut self, row: usize) {
+    let display_col = self.caret_display_col();
+    let col = grapheme_index_at_display_col(&self.lines[row], display_col);
+    self.caret = (col as UnitType, row as UnitType).into();
+  }
+
+  /// Move the caret to the visual row above or below it (which may be in the same line), keeping
+  /// its display col in the visual row.
+  fn move_caret_by_wrapped_row(&mut self, is_up: bool, width: UnitType) {
+    let row = self.caret.row as usize;
+    let line = self.current_line();
+    let wrapped_rows = wrap_line(line, width);
+    let wrapped_row_index = wrapped_row_index_of(&wrapped_rows, self.caret.col as usize);
+    let display_col = self.caret_display_col()
+      - display_col_of_grapheme(line, wrapped_rows[wrapped_row_index].start);
+
+    let (target_row, target_wrapped_rows, target_wrapped_row_index) = if is_up {
+      if wrapped_row_index > 0 {
+        (row, wrapped_rows, wrapped_row_index - 1)
+      } else if row > 0 {
+        let prev_wrapped_rows = wrap_line(&self.lines[row - 1], width);
+        let last_index = prev_wrapped_rows.len() - 1;
+        (row - 1, prev_wrapped_rows, last_index)
+      } else {
+        return;
+      }
+    } else if wrapped_row_index + 1 < wrapped_rows.len() {
+      (row, wrapped_rows, wrapped_row_index + 1)
+    } else if row + 1 < self.lines.len() {
+      (row + 1, wrap_line(&self.lines[row + 1], width), 0)
+    } else {
+      return;
+    };
+
+    // A caret at the end of a visual row (that isn't the last one) would be painted in the next
+    // row, so it is kept on the last grapheme of the row instead.
+    let target_line = &self.lines[target_row];
+    let target_wrapped_row = &target_wrapped_rows[target_wrapped_row_index];
+    let mut col = target_wrapped_row.start
+      + grapheme_index_at_display_col(
+        wrapped_row_text(target_line, target_wrapped_row),
+        display_col,
+      );
+    if target_wrapped_row_index + 1 < target_wrapped_rows.len() {
+      col = col.min(target_wrapped_row.end - 1);
+    }
+    self.caret = (col as UnitType, target_row as UnitType).into();
+  }
+}
+
+// Helpers.
+impl EditorBuffer {
+  /// Number of grapheme clusters in the line at `row`.
+  pub fn line_len(&self, row: usize) -> usize {
+    self
+      .lines
+      .get(row)
+      .map(|line| grapheme_count(line))
+      .unwrap_or(0)
+  }
+
+  /// Where the caret is painted, relative to the top left corner of the (unscrolled) buffer. This is
+  /// the line index & display col, or in soft wrap mode, the visual row index & display col in that
+  /// row (for a box that is `width` cols wide).
+  pub fn caret_display_pos(&self, width: UnitType) -> Position {
+    self.display_pos_of(self.caret, width)
+  }
+
+  /// Where a caret at `pos` (in buffer coordinates) is painted (see [Self::caret_display_pos]).
+  pub fn display_pos_of(&self, pos: Position, width: UnitType) -> Position {
+    let row = pos.row as usize;
+    let line = &self.lines[row];
+    let display_col = display_col_of_grapheme(line, pos.col as usize);
+    if !self.soft_wrap {
+      return position!(col: display_col, row: pos.row);
+    }
+
+    let wrapped_rows_before: usize = self.lines[..row]
+      .iter()
+      .map(|line| wrap_line(line, width).len())
+      .sum();
+    let wrapped_rows = wrap_line(line, width);
+    let wrapped_row_index = wrapped_row_index_of(&wrapped_rows, pos.col as usize);
+    let wrapped_row_start_col =
+      display_col_of_grapheme(line, wrapped_rows[wrapped_row_index].start);
+
+    position!(
+      col: display_col - wrapped_row_start_col,
+      row: (wrapped_rows_before + wrapped_row_index) as UnitType
+    )
+  }
+
+  /// Display col (relative to the start of the line) where the caret is painted.
+  pub fn caret_display_col(&self) -> UnitType {
+    display_col_of_grapheme(self.current_line(), self.caret.col as usize)
+  }
+
+  /// Display width of the grapheme under the caret, or 1 at the end of the line.
+  pub fn caret_display_width(&self) -> UnitType {
+    grapheme_at(self.current_line(), self.caret.col as usize)
+      .map(grapheme_display_width)
+      .unwrap_or(1)
+      .max(1)
+  }
+
+  fn current_line_len(&self) -> usize { self.line_len(self.caret.row as usize) }
+
+  fn current_line(&self) -> &str { &self.lines[self.caret.row as usize] }
+
+  fn current_line_mut(&mut self) -> &mut String {
+    let row = self.caret.row as usize;
+    &mut self.lines[row]
+  }
+
+  /// Convert the caret's grapheme index into a byte index in the current line.
+  fn caret_byte_index(&self) -> usize {
+    grapheme_byte_index(self.current_line(), self.caret.col as usize)
+  }
+}
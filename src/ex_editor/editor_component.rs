@@ -0,0 +1,548 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::{common::*, host_components::*};
+
+#[derive(Debug, Clone)]
+pub struct EditorComponent {
+  /// Id of the box that this component is rendered in. It is also the key for this component's
+  /// [EditorBuffer] in the [EditorState].
+  pub id: String,
+  /// Bounding size of the box that this component was last rendered in. It is sent w/ each
+  /// [EditorAction] so that the reducer can scroll the caret into view.
+  pub viewport_size: Size,
+  /// Spans of the lines of the buffer, which are kept between renders.
+  pub syntax_highlighter: SyntaxHighlighter,
+  /// Style (from the stylesheet) that the selected text is painted w/. If there isn't one, the
+  /// selected text is painted w/ its colors reversed.
+  pub maybe_selection_style: Option<Style>,
+  /// Completes the word before the caret w/ the other words in the buffer.
+  pub completion_popup: CompletionPopupComponent<EditorState, EditorAction>,
+  /// Completes the word that is being typed in the search prompt w/ the words in the buffer.
+  pub search_completion_popup: CompletionPopupComponent<EditorState, EditorAction>,
+}
+
+impl EditorComponent {
+  pub fn new(id: &str) -> Self {
+    Self {
+      id: id.to_string(),
+      viewport_size: Size::default(),
+      syntax_highlighter: SyntaxHighlighter::default(),
+      maybe_selection_style: None,
+      completion_popup: CompletionPopupComponent::new(
+        id,
+        Arc::new(TextWordsSource),
+        completion_helpers::get_completion_context,
+        completion_helpers::get_buffer_text,
+        completion_helpers::accept_completion,
+        EditorAction::Repaint,
+      ),
+      search_completion_popup: CompletionPopupComponent::new(
+        id,
+        Arc::new(TextWordsSource),
+        completion_helpers::get_search_completion_context,
+        completion_helpers::get_buffer_text,
+        completion_helpers::accept_search_completion,
+        EditorAction::Repaint,
+      ),
+    }
+  }
+}
+
+#[async_trait]
+impl Component<EditorState, EditorAction> for EditorComponent {
+  /// Handle following input events (and consume them):
+  /// - Displayable chars : insert the char at the caret
+  /// - Enter             : split the line at the caret
+  /// - Backspace, Delete : delete the char before, under the caret
+  /// - Up, Down, Left, Right : move the caret
+  /// - Ctrl+s            : save the buffer to its file
+  /// - Ctrl+z, Ctrl+y    : undo, redo
+  /// - Ctrl+w            : toggle soft wrap
+  /// - Ctrl+f            : open the search prompt (in the status bar)
+  /// - Ctrl+r            : open the find & replace prompt (in the status bar)
+  /// - Ctrl+d, Alt+Down  : add a caret on the next match of the word at the caret, on the line below
+  /// - Esc               : remove the extra carets
+  /// - Shift+arrows, Shift+Home / End, Ctrl+a : select text
+  /// - Ctrl+c, Ctrl+x, Ctrl+v : copy, cut, paste (the selection, or the caret line)
+  /// - Alt+d, Alt+Shift+Up / Down : duplicate, move the selected lines (or the caret line)
+  /// - Tab, Shift+Tab    : indent, outdent the selected lines
+  ///
+  /// While the prompt is open, the input events edit the prompt instead (see [SearchEvent]). While
+  /// a completion popup (for the buffer, or for the prompt) is open, it gets the input events 1st
+  /// (see [CompletionPopupComponent]).
+  async fn handle_event(
+    &mut self, input_event: &TWInputEvent, state: &EditorState,
+    shared_store: &SharedStore<EditorState, EditorAction>,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      let mut event_consumed = false;
+
+      let completion_popup = match state.get_search(&self.id) {
+        Some(_) => &mut self.search_completion_popup,
+        None => &mut self.completion_popup,
+      };
+      match completion_popup
+        .handle_event(input_event, state, shared_store)
+        .await?
+      {
+        EventPropagation::Propagate => {}
+        event_propagation => return Ok(event_propagation),
+      }
+
+      let maybe_action = match state.get_search(&self.id) {
+        Some(search) => SearchEvent::try_from(input_event).ok().map(|search_event| {
+          EditorAction::Search(
+            self.id.clone(),
+            search.resolve_search_event(search_event),
+            self.viewport_size,
+          )
+        }),
+        None => self.input_event_to_editor_action(input_event, state).await,
+      };
+
+      if let Some(action) = maybe_action {
+        debug_log_editor_action(
+          stringify!(EditorComponent::handle_event).into(),
+          action.clone(),
+        );
+        spawn_and_consume_event!(event_consumed, shared_store, action);
+      }
+
+      if event_consumed {
+        EventPropagation::Consumed
+      } else {
+        EventPropagation::Propagate
+      }
+    });
+  }
+
+  async fn render(
+    &mut self, has_focus: &HasFocus, current_box: &TWBox, state: &EditorState,
+    shared_store: &SharedStore<EditorState, EditorAction>,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      // Setup intermediate vars.
+      let box_origin_pos = current_box.origin_pos; // Adjusted for style margin (if any).
+      let box_bounding_size = current_box.bounding_size; // Adjusted for style margin (if any).
+      let default_editor_buffer = EditorBuffer::default();
+      let editor_buffer = state
+        .get_editor_buffer(&self.id)
+        .unwrap_or(&default_editor_buffer);
+      let mut queue: TWCommandQueue = tw_command_queue!();
+
+      // Remember the viewport size for the next dispatched action.
+      self.viewport_size = box_bounding_size;
+
+      self
+        .syntax_highlighter
+        .set_language(Language::from_file_path(editor_buffer.file_path.as_deref()));
+      let selection_style = self
+        .maybe_selection_style
+        .clone()
+        .unwrap_or_else(|| style! { attrib: [reverse] });
+      render_helpers::render_visible_lines(
+        &mut queue,
+        current_box,
+        editor_buffer,
+        &mut self.syntax_highlighter,
+        state.get_search(&self.id),
+        &selection_style,
+      );
+
+      // Paint the carets (the carets of the peers in a collab session are painted even if this box
+      // doesn't have focus).
+      if let Some(collab) = state.get_collab(&self.id) {
+        render_helpers::render_remote_carets(&mut queue, current_box, editor_buffer, collab);
+      }
+      if has_focus.does_current_box_have_focus(current_box) {
+        render_helpers::render_carets(&mut queue, current_box, editor_buffer);
+      }
+
+      // Paint the completion popup over the lines (if it is open). Only one of them is open at a
+      // time, since only the one for the search prompt gets the input events while it is open.
+      queue += self
+        .completion_popup
+        .render(has_focus, current_box, state, shared_store)
+        .await?;
+      queue += self
+        .search_completion_popup
+        .render(has_focus, current_box, state, shared_store)
+        .await?;
+
+      call_if_true!(is_logging_enabled(), {
+        log_no_err! {
+          INFO,
+          "\
+📝 EditorComponent::render ->
+  - current_box: {:?},
+  - box_origin_pos: {:?},
+  - box_bounding_size: {:?},
+  - caret: {:?},
+  - viewport: {:?},
+  - queue: {:?}",
+          current_box,
+          box_origin_pos,
+          box_bounding_size,
+          editor_buffer.caret,
+          editor_buffer.viewport,
+          queue
+        };
+      });
+
+      // Return the command queue.
+      queue
+    });
+  }
+}
+
+impl EditorComponent {
+  async fn input_event_to_editor_action(
+    &self, input_event: &TWInputEvent, state: &EditorState,
+  ) -> Option<EditorAction> {
+    match input_event {
+      TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: Some(ModifierKeys::CTRL),
+        non_modifier_key: NonModifierKey::Character(typed_char),
+      }) => match typed_char {
+        's' => Some(self.save_editor_buffer_to_file(state).await),
+        'z' => Some(EditorAction::Undo(self.id.clone())),
+        'y' => Some(EditorAction::Redo(self.id.clone())),
+        'd' => Some(EditorAction::ApplyEvent(
+          self.id.clone(),
+          EditorEvent::AddCaretAtNextMatch,
+          self.viewport_size,
+        )),
+        'a' => Some(EditorAction::ApplyEvent(
+          self.id.clone(),
+          EditorEvent::SelectAll,
+          self.viewport_size,
+        )),
+        'c' => Some(self.copy_to_clipboard(state, false)),
+        'x' => Some(self.copy_to_clipboard(state, true)),
+        'v' => Some(EditorAction::Paste(self.id.clone(), self.viewport_size)),
+        'w' => Some(EditorAction::ApplyEvent(
+          self.id.clone(),
+          EditorEvent::ToggleSoftWrap,
+          self.viewport_size,
+        )),
+        'f' => Some(EditorAction::Search(
+          self.id.clone(),
+          SearchEvent::Start,
+          self.viewport_size,
+        )),
+        'r' => Some(EditorAction::Search(
+          self.id.clone(),
+          SearchEvent::StartReplace,
+          self.viewport_size,
+        )),
+        _ => None,
+      },
+      _ => EditorEvent::try_from(input_event).ok().map(|editor_event| {
+        EditorAction::ApplyEvent(self.id.clone(), editor_event, self.viewport_size)
+      }),
+    }
+  }
+
+  /// The text is copied to the terminal's clipboard here too (if that is turned on), since the
+  /// reducer shouldn't write to the terminal. Errors doing that don't stop the in-process copy.
+  fn copy_to_clipboard(&self, state: &EditorState, is_cut: bool) -> EditorAction {
+    if let Some(editor_buffer) = state
+      .get_editor_buffer(&self.id)
+      .filter(|_| state.clipboard.is_osc52_enabled)
+    {
+      if let Err(error) = write_to_terminal_clipboard(&editor_buffer.get_text_to_copy()) {
+        call_if_true!(
+          is_logging_enabled(),
+          log_no_err!(
+            ERROR,
+            "📋 Could not copy to the terminal's clipboard: {}",
+            error
+          )
+        );
+      }
+    }
+
+    if is_cut {
+      EditorAction::Cut(self.id.clone(), self.viewport_size)
+    } else {
+      EditorAction::Copy(self.id.clone())
+    }
+  }
+
+  /// Save errors are shown in the status bar, instead of stopping the app.
+  async fn save_editor_buffer_to_file(&self, state: &EditorState) -> EditorAction {
+    let editor_buffer = match state.get_editor_buffer(&self.id) {
+      Some(editor_buffer) => editor_buffer,
+      None => return EditorAction::SetStatusMessage("Nothing to save".into()),
+    };
+
+    match save_editor_buffer(editor_buffer).await {
+      Ok(_) => EditorAction::MarkSaved(self.id.clone(), editor_buffer.lines_revision.id),
+      Err(error) => EditorAction::SetStatusMessage(format!("Could not save: {}", error)),
+    }
+  }
+}
+
+mod completion_helpers {
+  use super::*;
+  use crate::ex_editor::multi_caret::get_last_word;
+
+  /// Both popups complete words w/ the words in the buffer.
+  pub fn get_buffer_text(id: &str, state: &EditorState) -> String {
+    state
+      .get_editor_buffer(id)
+      .map(|editor_buffer| editor_buffer.lines.join("\n"))
+      .unwrap_or_default()
+  }
+
+  /// Words are only completed at the main caret, when nothing is selected & the search prompt is
+  /// closed.
+  pub fn get_completion_context(
+    id: &str, state: &EditorState, viewport_size: Size,
+  ) -> Option<CompletionContext> {
+    let editor_buffer = state.get_editor_buffer(id)?;
+    if state.get_search(id).is_some()
+      || !editor_buffer.extra_carets.is_empty()
+      || editor_buffer.get_selection().is_some()
+    {
+      return None;
+    }
+
+    let caret_display_pos = editor_buffer.caret_display_pos(viewport_size.cols);
+    if !editor_buffer
+      .viewport
+      .contains(caret_display_pos, viewport_size)
+    {
+      return None;
+    }
+
+    Some(CompletionContext {
+      prefix: editor_buffer.get_word_before_caret()?,
+      caret_pos: editor_buffer.viewport.box_position_of(caret_display_pos),
+      ..Default::default()
+    })
+  }
+
+  pub fn accept_completion(id: &str, viewport_size: Size, completion: Completion) -> EditorAction {
+    EditorAction::ApplyEvent(
+      id.to_string(),
+      EditorEvent::InsertText(completion.get_text_to_insert().to_string()),
+      viewport_size,
+    )
+  }
+
+  /// The prompt is in the status bar (below this box), so the popup is painted at the bottom of
+  /// this box, lined up w/ its left edge.
+  pub fn get_search_completion_context(
+    id: &str, state: &EditorState, viewport_size: Size,
+  ) -> Option<CompletionContext> {
+    let search = state.get_search(id)?;
+    let prefix = get_last_word(search.get_focused_field_text())?;
+
+    Some(CompletionContext {
+      prefix: prefix.to_string(),
+      caret_pos: position!(col: display_width(prefix), row: viewport_size.rows),
+      ..Default::default()
+    })
+  }
+
+  pub fn accept_search_completion(
+    id: &str, viewport_size: Size, completion: Completion,
+  ) -> EditorAction {
+    EditorAction::Search(
+      id.to_string(),
+      SearchEvent::InsertText(completion.get_text_to_insert().to_string()),
+      viewport_size,
+    )
+  }
+}
+
+mod render_helpers {
+  use std::ops::Range;
+
+  use super::*;
+
+  /// Only the lines (and the display cols in each line) that fit inside the viewport are painted. In
+  /// soft wrap mode, the lines are laid out across visual rows that fit in the width of the box.
+  /// Each line is painted as styled texts, one for each span that the syntax highlighter found.
+  /// Matches for the search (if any) are painted over the spans, & the one at the caret stands out.
+  /// The selection (if any) is painted over all of them.
+  pub fn render_visible_lines(
+    queue: &mut TWCommandQueue, current_box: &TWBox, editor_buffer: &EditorBuffer,
+    syntax_highlighter: &mut SyntaxHighlighter, maybe_search: Option<&SearchState>,
+    selection_style: &Style,
+  ) {
+    let box_bounding_size = current_box.bounding_size;
+    let visible_line_slices = editor_buffer.viewport.visible_line_slices(
+      &editor_buffer.lines,
+      box_bounding_size,
+      editor_buffer.soft_wrap,
+    );
+
+    let first_line_index = match visible_line_slices.first() {
+      Some(visible_line_slice) => visible_line_slice.line_index,
+      None => return,
+    };
+    let line_range =
+      first_line_index..visible_line_slices[visible_line_slices.len() - 1].line_index + 1;
+    let highlighted_lines = syntax_highlighter.highlight_lines(
+      &editor_buffer.lines,
+      &editor_buffer.lines_revision,
+      line_range,
+    );
+
+    for visible_line_slice in &visible_line_slices {
+      let line_index = visible_line_slice.line_index;
+      let line = &editor_buffer.lines[line_index];
+      let mut spans = highlighted_lines[line_index - first_line_index].clone();
+
+      if let Some(search) = maybe_search {
+        let search_matches = search.get_matches_in_line(line_index);
+        let match_ranges: Vec<Range<usize>> = search_matches
+          .iter()
+          .map(|search_match| search_match.byte_range.clone())
+          .collect();
+        spans = overlay_spans(&spans, &match_ranges, TokenKind::SearchMatch);
+        let current_match_ranges: Vec<Range<usize>> = search_matches
+          .iter()
+          .filter(|search_match| search_match.pos == editor_buffer.caret)
+          .map(|search_match| search_match.byte_range.clone())
+          .collect();
+        spans = overlay_spans(&spans, &current_match_ranges, TokenKind::CurrentSearchMatch);
+      }
+
+      if let Some(selection_byte_range) = editor_buffer.get_selection_byte_range(line_index) {
+        spans = overlay_spans(&spans, &[selection_byte_range], TokenKind::Selection);
+      }
+
+      let styled_texts = spans_to_styled_texts(
+        line,
+        &spans,
+        visible_line_slice,
+        box_bounding_size.cols,
+        &current_box.get_computed_style(),
+        selection_style,
+      );
+
+      tw_command_queue! {
+        queue push
+        TWCommand::MoveCursorPositionRelTo(
+          current_box.origin_pos,
+          position!(col: 0, row: visible_line_slice.row_index)
+        ),
+        TWCommand::ApplyColors(current_box.get_computed_style())
+      };
+      *queue += styled_texts.render();
+      *queue += TWCommand::ResetColor;
+    }
+  }
+
+  /// Paint the grapheme cluster under each caret (or a space if the caret is at the end of the
+  /// line) w/ the foreground & background colors reversed. The extra carets are painted in a
+  /// different color, so the one that the viewport follows stands out. Each caret is placed using
+  /// the display width of the graphemes before it, so wide chars (eg: `中`, `😀`) don't throw it
+  /// off.
+  pub fn render_carets(
+    queue: &mut TWCommandQueue, current_box: &TWBox, editor_buffer: &EditorBuffer,
+  ) {
+    let extra_caret_style = Style {
+      color_fg: Some(TWColor::Rgb { r: 0, g: 0, b: 0 }),
+      color_bg: Some(TWColor::Rgb {
+        r: 97,
+        g: 175,
+        b: 239,
+      }),
+      ..Default::default()
+    };
+    for extra_caret in &editor_buffer.extra_carets {
+      render_caret(
+        queue,
+        current_box,
+        editor_buffer,
+        *extra_caret,
+        extra_caret_style.clone(),
+      );
+    }
+    render_caret(
+      queue,
+      current_box,
+      editor_buffer,
+      editor_buffer.caret,
+      style! { attrib: [reverse] },
+    );
+  }
+
+  /// Colors of the carets of the peers in a collab session, picked by site id.
+  const REMOTE_CARET_COLORS: [(u8, u8, u8); 4] = [
+    (224, 108, 117),
+    (152, 195, 121),
+    (198, 120, 221),
+    (229, 192, 123),
+  ];
+
+  /// Each peer's caret is painted in its own color (which is the same in every render, & different
+  /// from the colors of the local carets).
+  pub fn render_remote_carets(
+    queue: &mut TWCommandQueue, current_box: &TWBox, editor_buffer: &EditorBuffer,
+    collab: &CollabState,
+  ) {
+    for (site_id, caret) in collab.get_remote_carets(&editor_buffer.lines) {
+      let (r, g, b) = REMOTE_CARET_COLORS[(site_id % REMOTE_CARET_COLORS.len() as u64) as usize];
+      let remote_caret_style = Style {
+        color_fg: Some(TWColor::Rgb { r: 0, g: 0, b: 0 }),
+        color_bg: Some(TWColor::Rgb { r, g, b }),
+        ..Default::default()
+      };
+      render_caret(queue, current_box, editor_buffer, caret, remote_caret_style);
+    }
+  }
+
+  fn render_caret(
+    queue: &mut TWCommandQueue, current_box: &TWBox, editor_buffer: &EditorBuffer, caret: Position,
+    caret_style: Style,
+  ) {
+    let caret_display_pos = editor_buffer.display_pos_of(caret, current_box.bounding_size.cols);
+    let viewport = editor_buffer.viewport;
+
+    // The box may have been resized since the viewport was last scrolled.
+    if !viewport.contains(caret_display_pos, current_box.bounding_size) {
+      return;
+    }
+
+    let grapheme_under_caret: String =
+      grapheme_at(&editor_buffer.lines[caret.row as usize], caret.col as usize)
+        .unwrap_or(" ")
+        .into();
+
+    tw_command_queue! {
+      queue push
+      TWCommand::MoveCursorPositionRelTo(
+        current_box.origin_pos,
+        viewport.box_position_of(caret_display_pos)
+      ),
+      TWCommand::PrintWithAttributes(grapheme_under_caret, Some(caret_style)),
+      TWCommand::ResetColor
+    };
+  }
+}
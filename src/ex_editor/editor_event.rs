@@ -0,0 +1,234 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils::*;
+
+use super::*;
+
+/// Editing operations that can be applied to an [EditorBuffer]. These are created from
+/// [TWInputEvent]s and dispatched (inside of an [EditorAction]) to the store.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EditorEvent {
+  InsertChar(char),
+  InsertNewLine,
+  Backspace,
+  Delete,
+  MoveCaret(CaretDirection),
+  ToggleSoftWrap,
+  /// Add a caret on the line below the last caret.
+  AddCaretBelow,
+  /// Add a caret on the next match of the word at the caret.
+  AddCaretAtNextMatch,
+  /// Go back to a single caret.
+  ClearExtraCarets,
+  /// Move the caret, extending the selection.
+  Select(CaretDirection),
+  SelectAll,
+  /// Insert text that may have new lines in it (eg: from the clipboard).
+  InsertText(String),
+  DeleteSelection,
+  /// Insert a copy of the selected lines (or the caret line) below them.
+  DuplicateLines,
+  /// Swap the selected lines (or the caret line) w/ the line above them.
+  MoveLinesUp,
+  /// Swap the selected lines (or the caret line) w/ the line below them.
+  MoveLinesDown,
+  /// Indent the selected lines, or insert an [INDENT] at each caret if nothing is selected.
+  Indent,
+  /// Outdent the selected lines (or the caret line).
+  Outdent,
+}
+
+impl EditorEvent {
+  /// Consecutive events that can be undone in a single step.
+  pub fn can_coalesce(&self) -> bool { matches!(self, EditorEvent::InsertChar(_)) }
+
+  /// Events that only move (or add, or remove) carets, & never modify the lines.
+  pub fn is_caret_only(&self) -> bool {
+    matches!(
+      self,
+      EditorEvent::MoveCaret(_)
+        | EditorEvent::AddCaretBelow
+        | EditorEvent::AddCaretAtNextMatch
+        | EditorEvent::ClearExtraCarets
+        | EditorEvent::Select(_)
+        | EditorEvent::SelectAll
+    )
+  }
+}
+
+impl TryFrom<&TWInputEvent> for EditorEvent {
+  type Error = String;
+
+  fn try_from(input_event: &TWInputEvent) -> Result<Self, Self::Error> {
+    match input_event {
+      TWInputEvent::DisplayableKeypress(typed_char) => Ok(EditorEvent::InsertChar(*typed_char)),
+      TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: None,
+        non_modifier_key: NonModifierKey::Special(special_key),
+      }) => match special_key {
+        SpecialKey::Enter => Ok(EditorEvent::InsertNewLine),
+        SpecialKey::Backspace => Ok(EditorEvent::Backspace),
+        SpecialKey::Delete => Ok(EditorEvent::Delete),
+        SpecialKey::Up => Ok(EditorEvent::MoveCaret(CaretDirection::Up)),
+        SpecialKey::Down => Ok(EditorEvent::MoveCaret(CaretDirection::Down)),
+        SpecialKey::Left => Ok(EditorEvent::MoveCaret(CaretDirection::Left)),
+        SpecialKey::Right => Ok(EditorEvent::MoveCaret(CaretDirection::Right)),
+        SpecialKey::PageUp => Ok(EditorEvent::MoveCaret(CaretDirection::PageUp)),
+        SpecialKey::PageDown => Ok(EditorEvent::MoveCaret(CaretDirection::PageDown)),
+        SpecialKey::Home => Ok(EditorEvent::MoveCaret(CaretDirection::Home)),
+        SpecialKey::End => Ok(EditorEvent::MoveCaret(CaretDirection::End)),
+        SpecialKey::Esc => Ok(EditorEvent::ClearExtraCarets),
+        SpecialKey::Tab => Ok(EditorEvent::Indent),
+        SpecialKey::BackTab => Ok(EditorEvent::Outdent),
+        _ => Err(format!("Unsupported special key: {:?}", special_key)),
+      },
+      TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: Some(ModifierKeys::SHIFT),
+        non_modifier_key: NonModifierKey::Special(special_key),
+      }) => match special_key {
+        SpecialKey::Up => Ok(EditorEvent::Select(CaretDirection::Up)),
+        SpecialKey::Down => Ok(EditorEvent::Select(CaretDirection::Down)),
+        SpecialKey::Left => Ok(EditorEvent::Select(CaretDirection::Left)),
+        SpecialKey::Right => Ok(EditorEvent::Select(CaretDirection::Right)),
+        SpecialKey::Home => Ok(EditorEvent::Select(CaretDirection::Home)),
+        SpecialKey::End => Ok(EditorEvent::Select(CaretDirection::End)),
+        SpecialKey::Tab | SpecialKey::BackTab => Ok(EditorEvent::Outdent),
+        _ => Err(format!("Unsupported special key: {:?}", special_key)),
+      },
+      TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: Some(ModifierKeys::ALT),
+        non_modifier_key: NonModifierKey::Special(SpecialKey::Down),
+      }) => Ok(EditorEvent::AddCaretBelow),
+      TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: Some(ModifierKeys::ALT),
+        non_modifier_key: NonModifierKey::Character('d'),
+      }) => Ok(EditorEvent::DuplicateLines),
+      TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: Some(modifier_keys),
+        non_modifier_key: NonModifierKey::Special(special_key @ (SpecialKey::Up | SpecialKey::Down)),
+      }) if *modifier_keys == ModifierKeys::ALT | ModifierKeys::SHIFT => match special_key {
+        SpecialKey::Up => Ok(EditorEvent::MoveLinesUp),
+        _ => Ok(EditorEvent::MoveLinesDown),
+      },
+      _ => Err(format!("Unsupported input event: {}", input_event)),
+    }
+  }
+}
+
+impl EditorBuffer {
+  /// Apply the `editor_event` (at every caret) & then make sure the caret is visible in a box of
+  /// `viewport_size`. The buffer is marked dirty (and true is returned) if its lines were modified.
+  pub fn apply_editor_event(&mut self, editor_event: &EditorEvent, viewport_size: Size) -> bool {
+    let first_edited_row = self.get_first_editable_row();
+    let is_modified = match editor_event {
+      EditorEvent::InsertChar(_)
+      | EditorEvent::InsertNewLine
+      | EditorEvent::InsertText(_)
+      | EditorEvent::Backspace
+      | EditorEvent::Delete => self.edit_at_each_caret(editor_event),
+      EditorEvent::Indent if self.get_selection().is_none() => {
+        self.edit_at_each_caret(&EditorEvent::InsertText(INDENT.into()))
+      }
+      EditorEvent::Indent => {
+        self.indent_lines();
+        true
+      }
+      EditorEvent::Outdent => self.outdent_lines(),
+      EditorEvent::DuplicateLines => {
+        self.duplicate_lines();
+        true
+      }
+      EditorEvent::MoveLinesUp => self.move_lines(true),
+      EditorEvent::MoveLinesDown => self.move_lines(false),
+      EditorEvent::DeleteSelection => self.delete_selection(),
+      EditorEvent::Select(direction) => {
+        self.select_caret(*direction, viewport_size);
+        false
+      }
+      EditorEvent::SelectAll => {
+        self.select_all();
+        false
+      }
+      EditorEvent::MoveCaret(direction) => {
+        self.move_each_caret(*direction, viewport_size);
+        false
+      }
+      EditorEvent::ToggleSoftWrap => {
+        self.toggle_soft_wrap();
+        false
+      }
+      EditorEvent::AddCaretBelow => {
+        self.maybe_selection_anchor = None;
+        self.add_caret_below();
+        false
+      }
+      EditorEvent::AddCaretAtNextMatch => {
+        self.maybe_selection_anchor = None;
+        self.add_caret_at_next_match();
+        false
+      }
+      EditorEvent::ClearExtraCarets => {
+        self.extra_carets.clear();
+        false
+      }
+    };
+    if is_modified {
+      self.mark_edited(first_edited_row);
+    }
+    self.scroll_caret_into_view(viewport_size);
+    is_modified
+  }
+
+  /// The first line that an event can modify. Events are applied at the carets (& the selection),
+  /// but they can also change the line above the top one (eg: Backspace at the start of a line).
+  fn get_first_editable_row(&self) -> usize {
+    let top_row = std::iter::once(self.caret)
+      .chain(self.extra_carets.iter().copied())
+      .chain(self.maybe_selection_anchor)
+      .map(|pos| pos.row as usize)
+      .min()
+      .unwrap_or_default();
+    top_row.saturating_sub(1)
+  }
+
+  /// Apply an edit (that inserts or deletes text) at the caret only. Inserted text replaces the
+  /// selection (if any), and Backspace & Delete delete just the selection. Returns false if there
+  /// was nothing to delete.
+  pub fn edit_at_caret(&mut self, editor_event: &EditorEvent) -> bool {
+    match editor_event {
+      EditorEvent::InsertChar(ch) => {
+        self.delete_selection();
+        self.insert_char(*ch);
+        true
+      }
+      EditorEvent::InsertNewLine => {
+        self.delete_selection();
+        self.insert_new_line();
+        true
+      }
+      EditorEvent::InsertText(text) => {
+        self.delete_selection();
+        self.insert_text(text);
+        true
+      }
+      EditorEvent::Backspace => self.delete_selection() || self.delete_prev_char(),
+      EditorEvent::Delete => self.delete_selection() || self.delete_caret_char(),
+      _ => false,
+    }
+  }
+}
@@ -0,0 +1,49 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::io::ErrorKind;
+
+use r3bl_rs_utils::*;
+use tokio::fs;
+
+use super::*;
+
+/// Load the file at `file_path` into a new [EditorBuffer]. If the file does not exist, then an
+/// empty buffer is returned, and the file is created (w/ a final new line) when the buffer is first
+/// saved.
+pub async fn load_editor_buffer(file_path: &str) -> CommonResult<EditorBuffer> {
+  throws_with_return!({
+    match fs::read_to_string(file_path).await {
+      Ok(content) => EditorBuffer::new_from_content(&content, file_path),
+      Err(error) if error.kind() == ErrorKind::NotFound => EditorBuffer {
+        file_path: Some(file_path.to_string()),
+        ..Default::default()
+      },
+      Err(error) => return Err(error.into()),
+    }
+  });
+}
+
+/// Write the lines in the `editor_buffer` to its file.
+pub async fn save_editor_buffer(editor_buffer: &EditorBuffer) -> CommonResult<()> {
+  throws!({
+    match editor_buffer.file_path {
+      Some(ref file_path) => fs::write(file_path, editor_buffer.get_content()).await?,
+      None => return Err("This buffer does not have a file to save to".into()),
+    }
+  });
+}
@@ -0,0 +1,82 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::{common::*, pairing::*};
+
+/// If `maybe_file_path` is given, that file is loaded into the left editor (or created when it is
+/// first saved, if it does not exist yet). If `maybe_collab` is given, the left editor is shared w/
+/// another `r3bl-cmdr` process (see [CollabMode]) that has the same pairing code.
+pub async fn run_app(
+  maybe_file_path: Option<String>, maybe_collab: Option<(CollabMode, PairingCode)>,
+) -> CommonResult<()> {
+  throws!({
+    // Create store.
+    let mut store = create_store().await;
+
+    // Load the file.
+    if let Some(file_path) = maybe_file_path {
+      let editor_buffer = load_editor_buffer(&file_path).await?;
+      store
+        .dispatch_action(EditorAction::SetEditorBuffer(
+          EDITOR_1_ID.into(),
+          editor_buffer,
+        ))
+        .await;
+    }
+
+    // Copy to the terminal's clipboard too, if it has been turned on.
+    if std::env::var_os(OSC52_ENV_VAR).is_some() {
+      store
+        .dispatch_action(EditorAction::SetOsc52Clipboard(true))
+        .await;
+    }
+
+    // Share the left editor. The session is started by the app, once it has the shared store.
+    if let Some((collab_mode, pairing_code)) = maybe_collab {
+      let config = CollabConfig {
+        editor_id: EDITOR_1_ID.into(),
+        mode: collab_mode,
+        pairing_code,
+        user_name: std::env::var("USER").unwrap_or_else(|_| "anonymous".into()),
+      };
+      store
+        .dispatch_action(EditorAction::StartCollab(config, generate_random_id()))
+        .await;
+    }
+
+    // Create an App (renders & responds to user input).
+    let shared_app = AppEditor::new_shared();
+
+    // Exit if these keys are pressed. Typed chars (like 'x') are inserted into the editor.
+    let exit_keys: Vec<TWInputEvent> = vec![TWInputEvent::NonDisplayableKeypress(Keypress {
+      maybe_modifier_keys: Some(ModifierKeys::CTRL),
+      non_modifier_key: NonModifierKey::Character('q'),
+    })];
+
+    // Create a window.
+    TerminalWindow::main_event_loop(store, shared_app, exit_keys).await?
+  });
+}
+
+pub async fn create_store() -> Store<EditorState, EditorAction> {
+  let mut store: Store<EditorState, EditorAction> = Store::default();
+  store.add_reducer(EditorReducer::new()).await;
+  store
+}
@@ -14,3 +14,36 @@
  *   See the License for the specific language governing permissions and
  *   limitations under the License.
  */
+
+// Attach sources.
+pub mod action;
+pub mod app_editor;
+pub mod collab;
+pub mod debug;
+pub mod editor_buffer;
+pub mod editor_component;
+pub mod editor_event;
+pub mod file_io;
+pub mod launcher;
+pub mod multi_caret;
+pub mod reducer;
+pub mod search;
+pub mod selection;
+pub mod state;
+pub mod syntax_highlighting;
+
+// Re-export.
+pub use action::*;
+pub use app_editor::*;
+pub use collab::*;
+pub use debug::*;
+pub use editor_buffer::*;
+pub use editor_component::*;
+pub use editor_event::*;
+pub use file_io::*;
+pub use launcher::*;
+pub use reducer::*;
+pub use search::*;
+pub use selection::*;
+pub use state::*;
+pub use syntax_highlighting::*;
@@ -0,0 +1,252 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils::*;
+use regex::Regex;
+
+use super::*;
+use crate::common::*;
+
+// Add & remove carets.
+impl EditorBuffer {
+  /// All the carets (the `caret` & the `extra_carets`), in the order that they appear in the lines.
+  pub fn get_all_carets(&self) -> Vec<Position> {
+    let mut carets = self.extra_carets.clone();
+    carets.push(self.caret);
+    carets.sort_by_key(caret_sort_key);
+    carets
+  }
+
+  /// Add a caret on the line below the last caret, in the same display col (as far as possible).
+  pub fn add_caret_below(&mut self) {
+    let carets = self.get_all_carets();
+    let last_caret = carets[carets.len() - 1];
+    let row = last_caret.row as usize + 1;
+    if row >= self.lines.len() {
+      return;
+    }
+
+    let display_col = display_col_of_grapheme(
+      &self.lines[last_caret.row as usize],
+      last_caret.col as usize,
+    );
+    let col = grapheme_index_at_display_col(&self.lines[row], display_col);
+    self
+      .extra_carets
+      .push(position!(col: col as UnitType, row: row as UnitType));
+  }
+
+  /// Add a caret on the next (whole word) match of the word at the caret, after the last caret. The
+  /// new caret is at the same offset in the match as the caret is in the word. The search wraps
+  /// around the end of the lines.
+  pub fn add_caret_at_next_match(&mut self) {
+    let (word, offset_in_word) = match self.get_word_at_caret() {
+      Some(word_at_caret) => word_at_caret,
+      None => return,
+    };
+    let regex = match Regex::new(&format!(r"\b{}\b", regex::escape(&word))) {
+      Ok(regex) => regex,
+      Err(_) => return,
+    };
+
+    let carets = self.get_all_carets();
+    let last_caret = carets[carets.len() - 1];
+    let candidates: Vec<Position> = find_all_matches(&self.lines, &regex)
+      .into_iter()
+      .map(|search_match| search_match.pos)
+      .map(|match_pos| position!(col: match_pos.col + offset_in_word, row: match_pos.row))
+      .filter(|candidate| !carets.contains(candidate))
+      .collect();
+
+    let maybe_next_caret = candidates
+      .iter()
+      .find(|candidate| caret_sort_key(candidate) > caret_sort_key(&last_caret))
+      .or_else(|| candidates.first());
+    if let Some(next_caret) = maybe_next_caret {
+      self.extra_carets.push(*next_caret);
+    }
+  }
+
+  /// The word (a run of alphanumeric or `_` chars) that the caret is in (or right after), & the
+  /// offset of the caret from the start of it (in grapheme clusters).
+  fn get_word_at_caret(&self) -> Option<(String, UnitType)> {
+    let line = &self.lines[self.caret.row as usize];
+    let caret_col = self.caret.col as usize;
+    let start = get_word_start(line, caret_col);
+    let mut end = caret_col;
+    while is_word_grapheme(line, end) {
+      end += 1;
+    }
+    if start == end {
+      return None;
+    }
+
+    let word = &line[grapheme_byte_index(line, start)..grapheme_byte_index(line, end)];
+    Some((word.to_string(), (caret_col - start) as UnitType))
+  }
+
+  /// The part of the word that is before the caret (eg: to complete it), if the caret is right
+  /// after a word char.
+  pub fn get_word_before_caret(&self) -> Option<String> {
+    let line = &self.lines[self.caret.row as usize];
+    let caret_col = self.caret.col as usize;
+    let start = get_word_start(line, caret_col);
+    if start == caret_col {
+      return None;
+    }
+
+    let word = &line[grapheme_byte_index(line, start)..grapheme_byte_index(line, caret_col)];
+    Some(word.to_string())
+  }
+
+  /// Go back to a single caret, w/ nothing selected (eg: when the lines are changed in a way that
+  /// the other carets can't follow).
+  pub fn clear_extra_carets_and_selection(&mut self) {
+    self.extra_carets.clear();
+    self.maybe_selection_anchor = None;
+  }
+
+  /// Sort the `carets`, drop the duplicates (& the one that is on top of the `caret`), and make
+  /// them the `extra_carets`.
+  fn set_extra_carets(&mut self, mut carets: Vec<Position>) {
+    carets.sort_by_key(caret_sort_key);
+    carets.dedup();
+    carets.retain(|caret| *caret != self.caret);
+    self.extra_carets = carets;
+  }
+}
+
+// Edit & move at every caret.
+impl EditorBuffer {
+  /// Apply the edit at each caret in turn (from the first one in the lines to the last). After each
+  /// edit, the other carets are shifted by the text that was inserted or deleted, so that they stay
+  /// on the same text. Carets that end up in the same place are merged. Returns true if any of the
+  /// edits modified the lines.
+  pub fn edit_at_each_caret(&mut self, editor_event: &EditorEvent) -> bool {
+    if self.extra_carets.is_empty() {
+      return self.edit_at_caret(editor_event);
+    }
+
+    let mut carets = self.get_all_carets();
+    let primary_index = carets
+      .iter()
+      .position(|caret| *caret == self.caret)
+      .unwrap_or_default();
+    let mut is_modified = false;
+
+    for index in 0..carets.len() {
+      let caret_before_edit = carets[index];
+      self.caret = caret_before_edit;
+      let edit_end = match editor_event {
+        EditorEvent::Delete => self.get_position_after_caret(),
+        _ => caret_before_edit,
+      };
+      if !self.edit_at_caret(editor_event) {
+        continue;
+      }
+      is_modified = true;
+
+      let edit_start = std::cmp::min_by_key(caret_before_edit, self.caret, caret_sort_key);
+      for caret in carets.iter_mut() {
+        *caret = shift_caret(*caret, edit_start, edit_end, self.caret);
+      }
+      carets[index] = self.caret;
+    }
+
+    self.caret = carets[primary_index];
+    self.set_extra_carets(carets);
+    is_modified
+  }
+
+  /// Move each caret in the `direction` (which ends the selection, if any). PageUp & PageDown scroll
+  /// the viewport (which follows the `caret`), so they go back to a single caret.
+  pub fn move_each_caret(&mut self, direction: CaretDirection, viewport_size: Size) {
+    self.maybe_selection_anchor = None;
+    if matches!(direction, CaretDirection::PageUp | CaretDirection::PageDown) {
+      self.extra_carets.clear();
+    }
+
+    let extra_carets = std::mem::take(&mut self.extra_carets);
+    self.move_caret(direction, viewport_size);
+    let primary_caret = self.caret;
+
+    let moved_extra_carets = extra_carets
+      .into_iter()
+      .map(|extra_caret| {
+        self.caret = extra_caret;
+        self.move_caret(direction, viewport_size);
+        self.caret
+      })
+      .collect();
+
+    self.caret = primary_caret;
+    self.set_extra_carets(moved_extra_carets);
+  }
+
+  /// The position that the Delete key deletes up to.
+  fn get_position_after_caret(&self) -> Position {
+    let row = self.caret.row as usize;
+    if (self.caret.col as usize) < self.line_len(row) {
+      position!(col: self.caret.col + 1, row: self.caret.row)
+    } else if row + 1 < self.lines.len() {
+      position!(col: 0, row: self.caret.row + 1)
+    } else {
+      self.caret
+    }
+  }
+}
+
+fn is_word_grapheme(line: &str, index: usize) -> bool {
+  grapheme_at(line, index)
+    .is_some_and(|grapheme| grapheme.chars().all(|ch| ch.is_alphanumeric() || ch == '_'))
+}
+
+/// Index of the 1st grapheme cluster of the word that ends at `col` (which is `col` if there is no
+/// word char before it).
+fn get_word_start(line: &str, col: usize) -> usize {
+  let mut start = col;
+  while start > 0 && is_word_grapheme(line, start - 1) {
+    start -= 1;
+  }
+  start
+}
+
+/// The word at the end of the `text` (eg: the one that is being typed in a prompt), if any.
+pub fn get_last_word(text: &str) -> Option<&str> {
+  let end = grapheme_count(text);
+  let start = get_word_start(text, end);
+  (start < end).then(|| &text[grapheme_byte_index(text, start)..])
+}
+
+fn caret_sort_key(caret: &Position) -> (UnitType, UnitType) { (caret.row, caret.col) }
+
+/// Where a caret at `pos` ends up after the text from `edit_start` to `edit_end` is replaced w/ text
+/// that ends at `new_edit_end`. Carets inside of the replaced text are moved to the end of the new
+/// text, and carets after it are shifted by the rows & cols that were added (or removed).
+fn shift_caret(
+  pos: Position, edit_start: Position, edit_end: Position, new_edit_end: Position,
+) -> Position {
+  if caret_sort_key(&pos) < caret_sort_key(&edit_start) {
+    pos
+  } else if caret_sort_key(&pos) < caret_sort_key(&edit_end) {
+    new_edit_end
+  } else if pos.row == edit_end.row {
+    position!(col: new_edit_end.col + (pos.col - edit_end.col), row: new_edit_end.row)
+  } else {
+    position!(col: pos.col, row: pos.row - edit_end.row + new_edit_end.row)
+  }
+}
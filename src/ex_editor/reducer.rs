@@ -0,0 +1,301 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::common::*;
+
+/// Reducer. Each editor's [UndoHistory] keeps at most `undo_history_depth` changes.
+pub struct EditorReducer {
+  pub undo_history_depth: usize,
+}
+
+impl Default for EditorReducer {
+  fn default() -> Self {
+    Self {
+      undo_history_depth: DEFAULT_UNDO_HISTORY_DEPTH,
+    }
+  }
+}
+
+#[async_trait]
+impl AsyncReducer<EditorState, EditorAction> for EditorReducer {
+  async fn run(&self, action: &EditorAction, state: &EditorState) -> EditorState {
+    let mut new_state = state.clone();
+
+    match action {
+      EditorAction::ApplyEvent(id, editor_event, viewport_size) => {
+        new_state.status_message = None;
+        self.apply_editor_event(&mut new_state, id, editor_event, *viewport_size);
+      }
+      EditorAction::SetEditorBuffer(id, editor_buffer) => {
+        new_state
+          .editor_buffers
+          .insert(id.clone(), editor_buffer.clone());
+        new_state.undo_histories.remove(id);
+      }
+      EditorAction::MarkSaved(id, saved_lines_revision_id) => {
+        if let Some(editor_buffer) = new_state.editor_buffers.get_mut(id) {
+          editor_buffer.mark_saved(*saved_lines_revision_id);
+          new_state.status_message = editor_buffer
+            .file_path
+            .as_ref()
+            .map(|file_path| format!("Saved {}", file_path));
+        }
+      }
+      EditorAction::Undo(id) => {
+        new_state.status_message = None;
+        self.undo_or_redo(&mut new_state, id, UndoHistory::undo);
+      }
+      EditorAction::Redo(id) => {
+        new_state.status_message = None;
+        self.undo_or_redo(&mut new_state, id, UndoHistory::redo);
+      }
+      EditorAction::Search(id, search_event, viewport_size) => {
+        new_state.status_message = None;
+        self.apply_search_event(&mut new_state, id, search_event, *viewport_size);
+      }
+      EditorAction::Copy(id) => {
+        if let Some(editor_buffer) = new_state.editor_buffers.get(id) {
+          new_state.clipboard.copy(&editor_buffer.get_text_to_copy());
+        }
+      }
+      EditorAction::Cut(id, viewport_size) => {
+        new_state.status_message = None;
+        self.cut(&mut new_state, id, *viewport_size);
+      }
+      EditorAction::Paste(id, viewport_size) => {
+        new_state.status_message = None;
+        if let Some(text) = new_state.clipboard.get_text().map(String::from) {
+          let editor_event = EditorEvent::InsertText(text);
+          self.apply_editor_event(&mut new_state, id, &editor_event, *viewport_size);
+        }
+      }
+      EditorAction::SetOsc52Clipboard(is_enabled) => {
+        new_state.clipboard.is_osc52_enabled = *is_enabled
+      }
+      EditorAction::StartCollab(config, site_id) => {
+        self.start_collab(&mut new_state, config, *site_id)
+      }
+      EditorAction::ApplyCollabMessage(message) => {
+        self.apply_collab_message(&mut new_state, message);
+        return new_state;
+      }
+      EditorAction::SetStatusMessage(message) => new_state.status_message = Some(message.clone()),
+      _ => {}
+    }
+
+    record_local_collab_edit(&mut new_state);
+    refresh_search_matches(&mut new_state);
+    new_state
+  }
+}
+
+impl EditorReducer {
+  /// Changes to the lines are recorded in the undo history (a run of typed chars is recorded as one
+  /// change). Moving (or adding) carets ends the current run.
+  fn apply_editor_event(
+    &self, state: &mut EditorState, id: &str, editor_event: &EditorEvent, viewport_size: Size,
+  ) {
+    let editor_buffer = state.editor_buffers.entry(id.to_string()).or_default();
+    let undo_history = state
+      .undo_histories
+      .entry(id.to_string())
+      .or_insert_with(|| UndoHistory::new(self.undo_history_depth));
+
+    if editor_event.is_caret_only() {
+      editor_buffer.apply_editor_event(editor_event, viewport_size);
+      undo_history.break_coalescing();
+      return;
+    }
+
+    let prior_editor_buffer = editor_buffer.clone();
+    if editor_buffer.apply_editor_event(editor_event, viewport_size) {
+      undo_history.push(&prior_editor_buffer, editor_event.can_coalesce());
+    }
+  }
+
+  /// If nothing is selected, then the whole caret line is cut. The cut is recorded in the undo
+  /// history as one change.
+  fn cut(&self, state: &mut EditorState, id: &str, viewport_size: Size) {
+    let editor_buffer = match state.editor_buffers.get_mut(id) {
+      Some(editor_buffer) => editor_buffer,
+      None => return,
+    };
+    state.clipboard.copy(&editor_buffer.get_text_to_copy());
+    if editor_buffer.get_selection().is_none() {
+      editor_buffer.select_caret_line();
+    }
+    self.apply_editor_event(state, id, &EditorEvent::DeleteSelection, viewport_size);
+  }
+
+  /// Jumping to a match (like moving the caret) ends the current run of typed chars in the undo
+  /// history. Each replace (even one that replaces every match in the buffer) is recorded as one
+  /// change, so it can be undone in one step. Replacing every match closes the prompt, & the number
+  /// of matches that were replaced is shown in the status bar.
+  fn apply_search_event(
+    &self, state: &mut EditorState, id: &str, search_event: &SearchEvent, viewport_size: Size,
+  ) {
+    let editor_buffer = state.editor_buffers.entry(id.to_string()).or_default();
+    let undo_history = state
+      .undo_histories
+      .entry(id.to_string())
+      .or_insert_with(|| UndoHistory::new(self.undo_history_depth));
+
+    match search_event {
+      SearchEvent::Start | SearchEvent::StartReplace => {
+        state.search = Some(SearchState {
+          editor_id: id.to_string(),
+          origin: editor_buffer.caret,
+          maybe_replacement: (*search_event == SearchEvent::StartReplace).then(String::new),
+          ..Default::default()
+        })
+      }
+      SearchEvent::End => state.search = None,
+      _ => {
+        if let Some(search) = state
+          .search
+          .as_mut()
+          .filter(|search| search.editor_id == id)
+        {
+          let maybe_prior_editor_buffer = matches!(
+            search_event,
+            SearchEvent::ReplaceNext | SearchEvent::ReplaceAll
+          )
+          .then(|| editor_buffer.clone());
+          let replaced_count =
+            search.apply_search_event(search_event, editor_buffer, viewport_size);
+          if let Some(prior_editor_buffer) =
+            maybe_prior_editor_buffer.filter(|_| replaced_count > 0)
+          {
+            undo_history.push(&prior_editor_buffer, false);
+          }
+          if *search_event == SearchEvent::ReplaceAll {
+            state.status_message = Some(format!(
+              "Replaced {} match{}",
+              replaced_count,
+              if replaced_count == 1 { "" } else { "es" }
+            ));
+            state.search = None;
+          }
+        }
+      }
+    }
+
+    undo_history.break_coalescing();
+  }
+
+  /// The host shares the text that is in its buffer, & the peer that joins starts w/ an empty buffer
+  /// (which the host's text is merged into).
+  fn start_collab(&self, state: &mut EditorState, config: &CollabConfig, site_id: u64) {
+    // While the host waits for a peer, the status bar shows the pairing code.
+    if let CollabMode::Join(addr) = &config.mode {
+      state
+        .editor_buffers
+        .insert(config.editor_id.clone(), EditorBuffer::default());
+      state.undo_histories.remove(&config.editor_id);
+      state.status_message = Some(format!("Joining {}", addr));
+    }
+    state.collab = Some(CollabState::new(config.clone(), site_id));
+  }
+
+  /// Edits from the peer can't be undone (& they would be sent back to the peer as local edits if
+  /// they were), so the undo history is cleared.
+  fn apply_collab_message(&self, state: &mut EditorState, message: &CollabMessage) {
+    let collab = match state.collab.as_mut() {
+      Some(collab) => collab,
+      None => return,
+    };
+
+    match message {
+      CollabMessage::Hello { site_id, user_name } => {
+        collab.remote_peers.entry(*site_id).or_default().user_name = user_name.clone();
+        state.status_message = Some(format!("{} joined", user_name));
+      }
+      CollabMessage::Ops { ops, .. } => {
+        let id = collab.config.editor_id.clone();
+        let editor_buffer = state.editor_buffers.entry(id.clone()).or_default();
+        collab.apply_remote_ops(ops, editor_buffer);
+        state.undo_histories.remove(&id);
+        if let Some(search) = state
+          .search
+          .as_mut()
+          .filter(|search| search.editor_id == id)
+        {
+          search.find_matches(editor_buffer);
+        }
+      }
+      CollabMessage::Caret { site_id, anchor } => {
+        collab
+          .remote_peers
+          .entry(*site_id)
+          .or_default()
+          .caret_anchor = *anchor;
+      }
+      CollabMessage::Applied { op_count, .. } => collab.doc.lock().doc.purge_tombstones(*op_count),
+    }
+  }
+
+  /// Replace the buffer w/ the one returned by `undo_or_redo_fn` (if any). The restored buffer is
+  /// clean only if its lines are the ones that were last saved. The soft wrap mode (& which lines
+  /// were saved) is not part of the history.
+  fn undo_or_redo(
+    &self, state: &mut EditorState, id: &str,
+    undo_or_redo_fn: fn(&mut UndoHistory<EditorBuffer>, &EditorBuffer) -> Option<EditorBuffer>,
+  ) {
+    let (editor_buffer, undo_history) = match (
+      state.editor_buffers.get_mut(id),
+      state.undo_histories.get_mut(id),
+    ) {
+      (Some(editor_buffer), Some(undo_history)) => (editor_buffer, undo_history),
+      _ => return,
+    };
+
+    if let Some(mut restored_editor_buffer) = undo_or_redo_fn(undo_history, editor_buffer) {
+      restored_editor_buffer.saved_lines_revision_id = editor_buffer.saved_lines_revision_id;
+      restored_editor_buffer.is_dirty =
+        restored_editor_buffer.lines_revision.id != editor_buffer.saved_lines_revision_id;
+      if restored_editor_buffer.soft_wrap != editor_buffer.soft_wrap {
+        restored_editor_buffer.toggle_soft_wrap();
+      }
+      *editor_buffer = restored_editor_buffer;
+    }
+  }
+}
+
+/// Turn any change to the shared buffer (made by the action that was just run) into ops for the
+/// peer in the collab session.
+fn record_local_collab_edit(state: &mut EditorState) {
+  if let Some(collab) = state.collab.as_mut() {
+    if let Some(editor_buffer) = state.editor_buffers.get(&collab.config.editor_id) {
+      collab.record_local_edit(editor_buffer);
+    }
+  }
+}
+
+/// The buffer that is being searched may have been changed by the action that was just run (eg:
+/// undone, or edited by the peer in a collab session), so the matches that are painted are found
+/// again.
+fn refresh_search_matches(state: &mut EditorState) {
+  if let Some(search) = state.search.as_mut() {
+    if let Some(editor_buffer) = state.editor_buffers.get(&search.editor_id) {
+      search.refresh_matches(editor_buffer);
+    }
+  }
+}
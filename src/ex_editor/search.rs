@@ -0,0 +1,479 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::ops::Range;
+
+use r3bl_rs_utils::*;
+use regex::{Regex, RegexBuilder};
+
+use super::*;
+use crate::common::*;
+
+/// The incremental search that is open in the status bar (for the editor w/ `editor_id`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchState {
+  pub editor_id: String,
+  pub query: String,
+  pub is_case_sensitive: bool,
+  pub is_regex: bool,
+  /// Where the caret was when the search was started. As the query is typed, the caret jumps to the
+  /// 1st match from here.
+  pub origin: Position,
+  /// What the matches are replaced w/, if the prompt was opened for find & replace. In regex mode,
+  /// `$1`, `${name}` etc are expanded to the groups captured by the match.
+  pub maybe_replacement: Option<String>,
+  /// Which field of the prompt the typed chars go into.
+  pub focused_field: SearchField,
+  /// The query, compiled when it (or how it is matched) changes.
+  pub compiled_query: CompiledQuery,
+  /// Where the matches are in the buffer (in order). They are found again when the query (or the
+  /// buffer) changes, instead of every time that the buffer & the prompt are rendered.
+  pub matches: Vec<SearchMatch>,
+  /// The revision of the lines that the `matches` were found in.
+  pub matches_lines_revision_id: u64,
+}
+
+/// A match for the query in the buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchMatch {
+  /// Where the match starts (line index & grapheme index).
+  pub pos: Position,
+  /// The bytes of the line that it spans.
+  pub byte_range: Range<usize>,
+}
+
+/// The regex for the query (which is escaped unless it is in regex mode), or the error if the query
+/// is not a valid regex.
+#[derive(Clone, Debug, Default)]
+pub enum CompiledQuery {
+  #[default]
+  Empty,
+  Regex(Regex),
+  Invalid(String),
+}
+
+impl PartialEq for CompiledQuery {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (CompiledQuery::Empty, CompiledQuery::Empty) => true,
+      (CompiledQuery::Regex(regex), CompiledQuery::Regex(other_regex)) => {
+        regex.as_str() == other_regex.as_str()
+      }
+      (CompiledQuery::Invalid(error), CompiledQuery::Invalid(other_error)) => error == other_error,
+      _ => false,
+    }
+  }
+}
+
+impl Eq for CompiledQuery {}
+
+/// The fields of the find & replace prompt.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchField {
+  #[default]
+  Find,
+  Replace,
+}
+
+/// Editing operations on the search prompt. These are created from [TWInputEvent]s (while the
+/// search is open) and dispatched (inside of an [EditorAction]) to the store.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchEvent {
+  Start,
+  StartReplace,
+  End,
+  InsertChar(char),
+  /// Insert a completion (see [crate::host_components::CompletionPopupComponent]).
+  InsertText(String),
+  Backspace,
+  ToggleCaseSensitive,
+  ToggleRegex,
+  NextMatch,
+  PrevMatch,
+  /// Switch between the find & replace fields.
+  ToggleField,
+  /// Replace the match at the caret (if any), & move to the next match.
+  ReplaceNext,
+  /// Replace every match in the buffer, as one change.
+  ReplaceAll,
+}
+
+impl TryFrom<&TWInputEvent> for SearchEvent {
+  type Error = String;
+
+  fn try_from(input_event: &TWInputEvent) -> Result<Self, Self::Error> {
+    match input_event {
+      TWInputEvent::DisplayableKeypress(typed_char) => Ok(SearchEvent::InsertChar(*typed_char)),
+      TWInputEvent::NonDisplayableKeypress(keypress) => match keypress {
+        Keypress {
+          maybe_modifier_keys: None,
+          non_modifier_key: NonModifierKey::Special(special_key),
+        } => match special_key {
+          SpecialKey::Enter => Ok(SearchEvent::NextMatch),
+          SpecialKey::Backspace => Ok(SearchEvent::Backspace),
+          SpecialKey::Esc => Ok(SearchEvent::End),
+          SpecialKey::Tab => Ok(SearchEvent::ToggleField),
+          _ => Err(format!("Unsupported special key: {:?}", special_key)),
+        },
+        Keypress {
+          maybe_modifier_keys: Some(ModifierKeys::SHIFT),
+          non_modifier_key: NonModifierKey::Special(SpecialKey::Enter),
+        } => Ok(SearchEvent::PrevMatch),
+        Keypress {
+          maybe_modifier_keys: Some(ModifierKeys::ALT),
+          non_modifier_key: NonModifierKey::Character(typed_char),
+        } => match typed_char {
+          'c' => Ok(SearchEvent::ToggleCaseSensitive),
+          'r' => Ok(SearchEvent::ToggleRegex),
+          'a' => Ok(SearchEvent::ReplaceAll),
+          _ => Err(format!("Unsupported input event: {}", input_event)),
+        },
+        Keypress {
+          maybe_modifier_keys: Some(ModifierKeys::CTRL),
+          non_modifier_key: NonModifierKey::Character('f' | 'r'),
+        } => Ok(SearchEvent::End),
+        _ => Err(format!("Unsupported input event: {}", input_event)),
+      },
+      _ => Err(format!("Unsupported input event: {}", input_event)),
+    }
+  }
+}
+
+impl SearchState {
+  /// The (compiled) regex for the query, or [None] if the query is empty. Returns an error if the
+  /// query is not a valid regex.
+  pub fn get_regex(&self) -> Result<Option<&Regex>, &str> {
+    match &self.compiled_query {
+      CompiledQuery::Empty => Ok(None),
+      CompiledQuery::Regex(regex) => Ok(Some(regex)),
+      CompiledQuery::Invalid(error) => Err(error),
+    }
+  }
+
+  /// Compile the query, after it (or how it is matched) has changed.
+  fn compile_query(&mut self) {
+    if self.query.is_empty() {
+      self.compiled_query = CompiledQuery::Empty;
+      return;
+    }
+
+    let pattern = if self.is_regex {
+      self.query.clone()
+    } else {
+      regex::escape(&self.query)
+    };
+
+    self.compiled_query = match RegexBuilder::new(&pattern)
+      .case_insensitive(!self.is_case_sensitive)
+      .build()
+    {
+      Ok(regex) => CompiledQuery::Regex(regex),
+      Err(error) => CompiledQuery::Invalid(error.to_string()),
+    };
+  }
+
+  /// The matches (out of the `matches`) in the line at `row`.
+  pub fn get_matches_in_line(&self, row: usize) -> &[SearchMatch] {
+    let start = self
+      .matches
+      .partition_point(|search_match| (search_match.pos.row as usize) < row);
+    let end = self
+      .matches
+      .partition_point(|search_match| (search_match.pos.row as usize) <= row);
+    &self.matches[start..end]
+  }
+
+  pub fn is_replace(&self) -> bool { self.maybe_replacement.is_some() }
+
+  /// The text in the field that has focus (which is where the typed chars go).
+  pub fn get_focused_field_text(&self) -> &str {
+    match &self.maybe_replacement {
+      Some(replacement) if self.focused_field == SearchField::Replace => replacement,
+      _ => &self.query,
+    }
+  }
+
+  /// Enter replaces the match at the caret (instead of just moving to the next match) when the
+  /// replace field has focus.
+  pub fn resolve_search_event(&self, search_event: SearchEvent) -> SearchEvent {
+    match search_event {
+      SearchEvent::NextMatch if self.focused_field == SearchField::Replace => {
+        SearchEvent::ReplaceNext
+      }
+      _ => search_event,
+    }
+  }
+
+  /// Apply the `search_event` to the prompt, & move the caret in the `editor_buffer` to the match
+  /// that it selects. Returns the number of matches that were replaced in the `editor_buffer`.
+  pub fn apply_search_event(
+    &mut self, search_event: &SearchEvent, editor_buffer: &mut EditorBuffer, viewport_size: Size,
+  ) -> usize {
+    let mut replaced_count = 0;
+
+    let (from, direction) = match search_event {
+      SearchEvent::InsertChar(ch) => match self.maybe_replacement.as_mut() {
+        Some(replacement) if self.focused_field == SearchField::Replace => {
+          replacement.push(*ch);
+          return 0;
+        }
+        _ => {
+          self.query.push(*ch);
+          self.compile_query();
+          (self.origin, SearchDirection::ForwardFrom)
+        }
+      },
+      SearchEvent::InsertText(text) => match self.maybe_replacement.as_mut() {
+        Some(replacement) if self.focused_field == SearchField::Replace => {
+          replacement.push_str(text);
+          return 0;
+        }
+        _ => {
+          self.query.push_str(text);
+          self.compile_query();
+          (self.origin, SearchDirection::ForwardFrom)
+        }
+      },
+      SearchEvent::Backspace => match self.maybe_replacement.as_mut() {
+        Some(replacement) if self.focused_field == SearchField::Replace => {
+          replacement.pop();
+          return 0;
+        }
+        _ => {
+          self.query.pop();
+          self.compile_query();
+          (self.origin, SearchDirection::ForwardFrom)
+        }
+      },
+      SearchEvent::ToggleField => {
+        self.focused_field = match self.focused_field {
+          SearchField::Find if self.is_replace() => SearchField::Replace,
+          _ => SearchField::Find,
+        };
+        return 0;
+      }
+      SearchEvent::ReplaceNext => {
+        replaced_count = self.replace_match_at_caret(editor_buffer);
+        (editor_buffer.caret, SearchDirection::ForwardFrom)
+      }
+      SearchEvent::ReplaceAll => {
+        replaced_count = self.replace_all_matches(editor_buffer);
+        self.find_matches(editor_buffer);
+        editor_buffer.scroll_caret_into_view(viewport_size);
+        return replaced_count;
+      }
+      SearchEvent::ToggleCaseSensitive => {
+        self.is_case_sensitive = !self.is_case_sensitive;
+        self.compile_query();
+        (self.origin, SearchDirection::ForwardFrom)
+      }
+      SearchEvent::ToggleRegex => {
+        self.is_regex = !self.is_regex;
+        self.compile_query();
+        (self.origin, SearchDirection::ForwardFrom)
+      }
+      SearchEvent::NextMatch => (editor_buffer.caret, SearchDirection::ForwardAfter),
+      SearchEvent::PrevMatch => (editor_buffer.caret, SearchDirection::BackwardBefore),
+      SearchEvent::Start | SearchEvent::StartReplace | SearchEvent::End => return 0,
+    };
+
+    // While the query doesn't match (or isn't valid), the caret goes back to where it started.
+    self.find_matches(editor_buffer);
+    let maybe_match_pos = find_match(&self.matches, from, direction);
+    editor_buffer.caret = match maybe_match_pos {
+      Some(match_pos) => match_pos,
+      None if direction == SearchDirection::ForwardFrom && replaced_count == 0 => self.origin,
+      None => editor_buffer.caret,
+    };
+    editor_buffer.scroll_caret_into_view(viewport_size);
+
+    replaced_count
+  }
+
+  /// Find the matches for the query (none if it is empty or isn't a valid regex) in the buffer.
+  pub fn find_matches(&mut self, editor_buffer: &EditorBuffer) {
+    self.matches = match self.get_regex() {
+      Ok(Some(regex)) => find_all_matches(&editor_buffer.lines, regex),
+      _ => vec![],
+    };
+    self.matches_lines_revision_id = editor_buffer.lines_revision.id;
+  }
+
+  /// Find the matches again if the lines in the buffer have changed since they were found (eg: by
+  /// a peer in a collab session).
+  pub fn refresh_matches(&mut self, editor_buffer: &EditorBuffer) {
+    if self.matches_lines_revision_id != editor_buffer.lines_revision.id {
+      self.find_matches(editor_buffer);
+    }
+  }
+
+  /// Replace the match that starts at the caret (if there is one), & leave the caret after the
+  /// replacement (so that the replacement itself isn't matched again).
+  fn replace_match_at_caret(&self, editor_buffer: &mut EditorBuffer) -> usize {
+    let (regex, replacement) = match (self.get_regex(), &self.maybe_replacement) {
+      (Ok(Some(regex)), Some(replacement)) => (regex, replacement),
+      _ => return 0,
+    };
+
+    let caret = editor_buffer.caret;
+    let line = &editor_buffer.lines[caret.row as usize];
+    let caret_byte_index = grapheme_byte_index(line, caret.col as usize);
+    let (new_line, replaced_ranges) = replace_matches_in_line(
+      line,
+      regex,
+      replacement,
+      self.is_regex,
+      Some(caret_byte_index),
+    );
+    let replaced_range = match replaced_ranges.first() {
+      Some(replaced_range) => replaced_range.clone(),
+      None => return 0,
+    };
+
+    let caret_col = grapheme_count(&new_line[..replaced_range.end]);
+    editor_buffer.clear_extra_carets_and_selection();
+    editor_buffer.lines[caret.row as usize] = new_line;
+    editor_buffer.caret.col = caret_col as UnitType;
+    editor_buffer.mark_edited(caret.row as usize);
+    1
+  }
+
+  /// Replace every match in every line. The caret stays on its line (clamped to the end of it).
+  fn replace_all_matches(&self, editor_buffer: &mut EditorBuffer) -> usize {
+    let (regex, replacement) = match (self.get_regex(), &self.maybe_replacement) {
+      (Ok(Some(regex)), Some(replacement)) => (regex, replacement),
+      _ => return 0,
+    };
+
+    let mut replaced_count = 0;
+    let mut maybe_first_replaced_row = None;
+    for (row, line) in editor_buffer.lines.iter_mut().enumerate() {
+      let (new_line, replaced_ranges) =
+        replace_matches_in_line(line, regex, replacement, self.is_regex, None);
+      if !replaced_ranges.is_empty() {
+        replaced_count += replaced_ranges.len();
+        maybe_first_replaced_row.get_or_insert(row);
+        *line = new_line;
+      }
+    }
+
+    if let Some(first_replaced_row) = maybe_first_replaced_row {
+      editor_buffer.clear_extra_carets_and_selection();
+      let line_len = editor_buffer.line_len(editor_buffer.caret.row as usize);
+      editor_buffer.caret.col = editor_buffer.caret.col.min(line_len as UnitType);
+      editor_buffer.mark_edited(first_replaced_row);
+    }
+    replaced_count
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchDirection {
+  /// The 1st match at or after the position.
+  ForwardFrom,
+  /// The 1st match after the position.
+  ForwardAfter,
+  /// The last match before the position.
+  BackwardBefore,
+}
+
+/// Byte ranges of the (non empty) matches of the `regex` in the `line`.
+pub fn find_matches_in_line(line: &str, regex: &Regex) -> Vec<Range<usize>> {
+  regex
+    .find_iter(line)
+    .filter(|found| !found.as_str().is_empty())
+    .map(|found| found.range())
+    .collect()
+}
+
+/// Replace the (non empty) matches of the `regex` in the `line` w/ the `replacement`, or just the
+/// one that starts at `maybe_only_at` (a byte index). If `expand_captures` is true, `$1`, `${name}`
+/// etc in the `replacement` are expanded to the groups captured by each match. Returns the new line
+/// & the byte ranges (in the new line) of the replacements.
+pub fn replace_matches_in_line(
+  line: &str, regex: &Regex, replacement: &str, expand_captures: bool, maybe_only_at: Option<usize>,
+) -> (String, Vec<Range<usize>>) {
+  let mut new_line = String::with_capacity(line.len());
+  let mut replaced_ranges = vec![];
+  let mut last_end = 0;
+
+  for captures in regex.captures_iter(line) {
+    let found = match captures.get(0) {
+      Some(found) if !found.as_str().is_empty() => found,
+      _ => continue,
+    };
+    if maybe_only_at.is_some_and(|only_at| only_at != found.start()) {
+      continue;
+    }
+
+    new_line.push_str(&line[last_end..found.start()]);
+    let replaced_start = new_line.len();
+    if expand_captures {
+      captures.expand(replacement, &mut new_line);
+    } else {
+      new_line.push_str(replacement);
+    }
+    replaced_ranges.push(replaced_start..new_line.len());
+    last_end = found.end();
+  }
+
+  new_line.push_str(&line[last_end..]);
+  (new_line, replaced_ranges)
+}
+
+/// All the matches in the `lines`, in order.
+pub fn find_all_matches(lines: &[String], regex: &Regex) -> Vec<SearchMatch> {
+  lines
+    .iter()
+    .enumerate()
+    .flat_map(|(row, line)| {
+      find_matches_in_line(line, regex)
+        .into_iter()
+        .map(move |byte_range| {
+          let col = grapheme_count(&line[..byte_range.start]);
+          SearchMatch {
+            pos: position!(col: col as UnitType, row: row as UnitType),
+            byte_range,
+          }
+        })
+    })
+    .collect()
+}
+
+/// The match (out of the `matches`, which are in order) in the `direction` from the `from`
+/// position. The search wraps around the end (or start) of the lines.
+pub fn find_match(
+  matches: &[SearchMatch], from: Position, direction: SearchDirection,
+) -> Option<Position> {
+  let key = |pos: &Position| (pos.row, pos.col);
+  let mut positions = matches.iter().map(|search_match| search_match.pos);
+  match direction {
+    SearchDirection::ForwardFrom => positions
+      .clone()
+      .find(|pos| key(pos) >= key(&from))
+      .or_else(|| positions.next()),
+    SearchDirection::ForwardAfter => positions
+      .clone()
+      .find(|pos| key(pos) > key(&from))
+      .or_else(|| positions.next()),
+    SearchDirection::BackwardBefore => positions
+      .clone()
+      .rev()
+      .find(|pos| key(pos) < key(&from))
+      .or_else(|| positions.next_back()),
+  }
+}
@@ -0,0 +1,264 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::ops::Range;
+
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::common::*;
+
+/// What Tab inserts at the start of each selected line (& Shift+Tab removes).
+pub const INDENT: &str = "  ";
+
+// Select text.
+impl EditorBuffer {
+  /// The start & end of the selection (in the order they appear in the lines), or [None] if
+  /// nothing is selected.
+  pub fn get_selection(&self) -> Option<(Position, Position)> {
+    let anchor = self.maybe_selection_anchor?;
+    let key = |pos: &Position| (pos.row, pos.col);
+    match key(&anchor).cmp(&key(&self.caret)) {
+      std::cmp::Ordering::Less => Some((anchor, self.caret)),
+      std::cmp::Ordering::Greater => Some((self.caret, anchor)),
+      std::cmp::Ordering::Equal => None,
+    }
+  }
+
+  /// Move the caret in the `direction`, keeping the other end of the selection where it is. A
+  /// selection is made w/ a single caret, so the extra carets are removed.
+  pub fn select_caret(&mut self, direction: CaretDirection, viewport_size: Size) {
+    self.extra_carets.clear();
+    let anchor = self.maybe_selection_anchor.unwrap_or(self.caret);
+    self.move_caret(direction, viewport_size);
+    self.maybe_selection_anchor = Some(anchor);
+  }
+
+  pub fn select_all(&mut self) {
+    self.extra_carets.clear();
+    let last_row = self.lines.len() - 1;
+    self.maybe_selection_anchor = Some(Position::default());
+    self.caret = (self.line_len(last_row) as UnitType, last_row as UnitType).into();
+  }
+
+  /// Select the whole line that the caret is on (including its new line, if it isn't the last
+  /// line).
+  pub fn select_caret_line(&mut self) {
+    let row = self.caret.row as usize;
+    self.extra_carets.clear();
+    self.maybe_selection_anchor = Some((0, self.caret.row).into());
+    self.caret = if row + 1 < self.lines.len() {
+      (0, self.caret.row + 1).into()
+    } else {
+      (self.line_len(row) as UnitType, self.caret.row).into()
+    };
+  }
+
+  /// The selected text, w/ its lines joined by new lines.
+  pub fn get_selected_text(&self) -> Option<String> {
+    let (start, end) = self.get_selection()?;
+    let selected_lines: Vec<&str> = (start.row..=end.row)
+      .filter_map(|row| {
+        let byte_range = self.get_selection_byte_range(row as usize)?;
+        Some(&self.lines[row as usize][byte_range])
+      })
+      .collect();
+    Some(selected_lines.join("\n"))
+  }
+
+  /// What is copied (or cut) to the clipboard: the selected text, or if nothing is selected, the
+  /// whole line that the caret is on.
+  pub fn get_text_to_copy(&self) -> String {
+    self
+      .get_selected_text()
+      .unwrap_or_else(|| format!("{}\n", self.lines[self.caret.row as usize]))
+  }
+
+  /// Byte range of the selected part of the line at `row`, if it is in the selection.
+  pub fn get_selection_byte_range(&self, row: usize) -> Option<Range<usize>> {
+    let (start, end) = self.get_selection()?;
+    if row < start.row as usize || row > end.row as usize {
+      return None;
+    }
+
+    let line = &self.lines[row];
+    let start_byte_index = if row == start.row as usize {
+      grapheme_byte_index(line, start.col as usize)
+    } else {
+      0
+    };
+    let end_byte_index = if row == end.row as usize {
+      grapheme_byte_index(line, end.col as usize)
+    } else {
+      line.len()
+    };
+    Some(start_byte_index..end_byte_index)
+  }
+}
+
+// Edit the selected text.
+impl EditorBuffer {
+  /// Delete the selected text, & leave the caret where it started. Returns false if nothing was
+  /// selected.
+  pub fn delete_selection(&mut self) -> bool {
+    let maybe_selection = self.get_selection();
+    self.maybe_selection_anchor = None;
+    let (start, end) = match maybe_selection {
+      Some(selection) => selection,
+      None => return false,
+    };
+
+    let (start_row, end_row) = (start.row as usize, end.row as usize);
+    let end_byte_index = grapheme_byte_index(&self.lines[end_row], end.col as usize);
+    let rest_of_end_line = self.lines[end_row][end_byte_index..].to_string();
+    let start_line = &mut self.lines[start_row];
+    start_line.truncate(grapheme_byte_index(start_line, start.col as usize));
+    start_line.push_str(&rest_of_end_line);
+    self.lines.drain(start_row + 1..=end_row);
+
+    self.caret = start;
+    true
+  }
+
+  /// Insert the `text` (which may have new lines in it) at the caret, & leave the caret after it.
+  pub fn insert_text(&mut self, text: &str) {
+    let row = self.caret.row as usize;
+    let byte_index = grapheme_byte_index(&self.lines[row], self.caret.col as usize);
+    let rest_of_line = self.lines[row].split_off(byte_index);
+
+    let mut last_row = row;
+    for (index, text_line) in text.split('\n').enumerate() {
+      let text_line = text_line.strip_suffix('\r').unwrap_or(text_line);
+      if index == 0 {
+        self.lines[row].push_str(text_line);
+      } else {
+        last_row += 1;
+        self.lines.insert(last_row, text_line.to_string());
+      }
+    }
+
+    let caret_col = grapheme_count(&self.lines[last_row]);
+    self.lines[last_row].push_str(&rest_of_line);
+    self.caret = (caret_col as UnitType, last_row as UnitType).into();
+  }
+}
+
+// Operate on whole lines: the ones that are (partly) selected, or the one that the caret is on.
+impl EditorBuffer {
+  /// The rows of the lines that the selection is in. A selection that ends at the start of a line
+  /// doesn't include that line.
+  pub fn get_selected_rows(&self) -> Range<usize> {
+    match self.get_selection() {
+      Some((start, end)) if end.col == 0 && end.row > start.row => {
+        start.row as usize..end.row as usize
+      }
+      Some((start, end)) => start.row as usize..end.row as usize + 1,
+      None => self.caret.row as usize..self.caret.row as usize + 1,
+    }
+  }
+
+  /// Insert a copy of the selected lines below them, & move the caret (& selection) to the copy.
+  pub fn duplicate_lines(&mut self) {
+    self.extra_carets.clear();
+    let rows = self.get_selected_rows();
+    let copied_lines: Vec<String> = self.lines[rows.clone()].to_vec();
+    self.lines.splice(rows.end..rows.end, copied_lines);
+    self.shift_caret_and_anchor_rows(rows.len() as isize);
+  }
+
+  /// Swap the selected lines w/ the line above (or below) them. Returns false if they are already
+  /// at the top (or bottom).
+  pub fn move_lines(&mut self, is_up: bool) -> bool {
+    self.extra_carets.clear();
+    let rows = self.get_selected_rows();
+    if is_up {
+      if rows.start == 0 {
+        return false;
+      }
+      self.lines[rows.start - 1..rows.end].rotate_left(1);
+      self.shift_caret_and_anchor_rows(-1);
+    } else {
+      if rows.end >= self.lines.len() {
+        return false;
+      }
+      self.lines[rows.start..rows.end + 1].rotate_right(1);
+      self.shift_caret_and_anchor_rows(1);
+    }
+    true
+  }
+
+  /// Add an [INDENT] to the start of each (non empty) selected line.
+  pub fn indent_lines(&mut self) {
+    self.extra_carets.clear();
+    let indent_len = grapheme_count(INDENT) as UnitType;
+    for row in self.get_selected_rows() {
+      if self.lines[row].is_empty() {
+        continue;
+      }
+      self.lines[row].insert_str(0, INDENT);
+      self.shift_caret_and_anchor_cols(row, |col| col + indent_len);
+    }
+  }
+
+  /// Remove (up to) an [INDENT] worth of spaces, or a tab, from the start of each selected line.
+  /// Returns false if none of the lines were indented.
+  pub fn outdent_lines(&mut self) -> bool {
+    self.extra_carets.clear();
+    let mut is_modified = false;
+    for row in self.get_selected_rows() {
+      let line = &self.lines[row];
+      let removed_len = if line.starts_with('\t') {
+        1
+      } else {
+        line
+          .chars()
+          .take(INDENT.len())
+          .take_while(|ch| *ch == ' ')
+          .count()
+      };
+      if removed_len == 0 {
+        continue;
+      }
+      self.lines[row].replace_range(..removed_len, "");
+      self.shift_caret_and_anchor_cols(row, |col| col.saturating_sub(removed_len as UnitType));
+      is_modified = true;
+    }
+    is_modified
+  }
+
+  fn shift_caret_and_anchor_rows(&mut self, row_count: isize) {
+    let shift = |pos: &mut Position| pos.row = (pos.row as isize + row_count) as UnitType;
+    shift(&mut self.caret);
+    if let Some(anchor) = self.maybe_selection_anchor.as_mut() {
+      shift(anchor);
+    }
+  }
+
+  fn shift_caret_and_anchor_cols(&mut self, row: usize, shift_fn: impl Fn(UnitType) -> UnitType) {
+    let row = row as UnitType;
+    if self.caret.row == row {
+      self.caret.col = shift_fn(self.caret.col);
+    }
+    if let Some(anchor) = self
+      .maybe_selection_anchor
+      .as_mut()
+      .filter(|anchor| anchor.row == row)
+    {
+      anchor.col = shift_fn(anchor.col);
+    }
+  }
+}
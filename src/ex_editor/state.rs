@@ -0,0 +1,75 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{collections::HashMap,
+          fmt::{Display, Formatter}};
+
+use super::*;
+use crate::common::*;
+
+/// State. Each editor component has its own [EditorBuffer] & [UndoHistory], keyed by the id of its
+/// box. At most one editor is searched at a time (the search prompt is in the status bar). The
+/// [Clipboard] is shared by all the editors. At most one editor is shared in a collab session.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct EditorState {
+  pub editor_buffers: HashMap<String, EditorBuffer>,
+  pub undo_histories: HashMap<String, UndoHistory<EditorBuffer>>,
+  pub status_message: Option<String>,
+  pub search: Option<SearchState>,
+  pub clipboard: Clipboard,
+  pub collab: Option<CollabState>,
+}
+
+impl EditorState {
+  pub fn get_editor_buffer(&self, id: &str) -> Option<&EditorBuffer> { self.editor_buffers.get(id) }
+
+  /// The search, if it is open for the editor w/ the given id.
+  pub fn get_search(&self, id: &str) -> Option<&SearchState> {
+    self.search.as_ref().filter(|search| search.editor_id == id)
+  }
+
+  /// The collab session, if the editor w/ the given id is shared in it.
+  pub fn get_collab(&self, id: &str) -> Option<&CollabState> {
+    self
+      .collab
+      .as_ref()
+      .filter(|collab| collab.config.editor_id == id)
+  }
+}
+
+impl Display for EditorState {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let mut ids: Vec<&String> = self.editor_buffers.keys().collect();
+    ids.sort();
+
+    write!(f, "State {{")?;
+    for id in ids {
+      let editor_buffer = &self.editor_buffers[id];
+      write!(
+        f,
+        " {}: {{ file_path: {:?}, is_dirty: {}, lines: {}, caret: {:?}, viewport: {:?} }}",
+        id,
+        editor_buffer.file_path,
+        editor_buffer.is_dirty,
+        editor_buffer.lines.len(),
+        editor_buffer.caret,
+        editor_buffer.viewport
+      )?;
+    }
+    write!(f, " }}")
+  }
+}
@@ -0,0 +1,55 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::path::Path;
+
+/// Languages that the editor can highlight.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+  Rust,
+  Toml,
+  Markdown,
+  #[default]
+  PlainText,
+}
+
+impl Language {
+  /// Pick the language using the extension of the file (if any).
+  pub fn from_file_path(maybe_file_path: Option<&str>) -> Self {
+    let maybe_extension = maybe_file_path
+      .and_then(|file_path| Path::new(file_path).extension())
+      .and_then(|extension| extension.to_str())
+      .map(str::to_lowercase);
+
+    match maybe_extension.as_deref() {
+      Some("rs") => Language::Rust,
+      Some("toml") => Language::Toml,
+      Some("md" | "markdown" | "todo") => Language::Markdown,
+      _ => Language::PlainText,
+    }
+  }
+
+  /// Pick the language using the tag of a fenced code block in Markdown (eg: ```rust). Markdown
+  /// blocks aren't highlighted, since their own fences would end the outer block.
+  pub fn from_fence_tag(tag: &str) -> Option<Self> {
+    match tag.to_lowercase().as_str() {
+      "rust" | "rs" => Some(Language::Rust),
+      "toml" => Some(Language::Toml),
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,147 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use super::*;
+
+/// What a lexer was in the middle of at the end of a line, which is where it picks up from at the
+/// start of the next line. Lines that don't end inside of a multi line token end in
+/// [LexState::Normal].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LexState {
+  #[default]
+  Normal,
+  /// Nesting depth of the `/* */` comments.
+  RustBlockComment(usize),
+  RustString,
+  /// Number of `#`s in the `r#"` that started the raw string.
+  RustRawString(usize),
+  /// The quote char of the `"""` or `'''` that started the string.
+  TomlMultilineString(char),
+  /// Inside a fenced code block, w/ the language from its tag (if it is known), & the state of the
+  /// lexer for that language.
+  MarkdownFence(Option<Language>, Box<LexState>),
+}
+
+/// Split the `line` into spans (using the lexer for the `language`), starting from the `start_state`
+/// that the previous line ended in. Returns the spans & the state that this line ends in.
+pub fn lex_line(language: Language, line: &str, start_state: &LexState) -> (Vec<Span>, LexState) {
+  let mut scanner = Scanner::new(line);
+  let end_state = lex_rest_of_line(language, &mut scanner, start_state.clone());
+  (scanner.into_spans(), end_state)
+}
+
+/// Run the lexer for the `language` from the current position of the `scanner`.
+pub fn lex_rest_of_line(
+  language: Language, scanner: &mut Scanner<'_>, start_state: LexState,
+) -> LexState {
+  match language {
+    Language::Rust => lex_rust_line(scanner, start_state),
+    Language::Toml => lex_toml_line(scanner, start_state),
+    Language::Markdown => lex_markdown_line(scanner, start_state),
+    Language::PlainText => LexState::Normal,
+  }
+}
+
+/// Walks through a line one char at a time, & collects the [Span]s that the lexer marks. Any text
+/// that isn't marked is [TokenKind::Plain].
+#[derive(Debug)]
+pub struct Scanner<'a> {
+  pub line: &'a str,
+  pub pos: usize,
+  spans: Vec<Span>,
+}
+
+impl<'a> Scanner<'a> {
+  pub fn new(line: &'a str) -> Self {
+    Self {
+      line,
+      pos: 0,
+      spans: vec![],
+    }
+  }
+
+  pub fn is_done(&self) -> bool { self.pos >= self.line.len() }
+
+  pub fn rest(&self) -> &'a str { &self.line[self.pos..] }
+
+  pub fn peek(&self) -> Option<char> { self.rest().chars().next() }
+
+  pub fn peek_nth(&self, n: usize) -> Option<char> { self.rest().chars().nth(n) }
+
+  pub fn starts_with(&self, pattern: &str) -> bool { self.rest().starts_with(pattern) }
+
+  pub fn advance(&mut self) -> Option<char> {
+    let ch = self.peek()?;
+    self.pos += ch.len_utf8();
+    Some(ch)
+  }
+
+  /// Move forward by `byte_count` bytes, which must end on a char boundary.
+  pub fn advance_by(&mut self, byte_count: usize) {
+    self.pos = (self.pos + byte_count).min(self.line.len());
+  }
+
+  pub fn advance_while(&mut self, predicate: impl Fn(char) -> bool) {
+    while let Some(ch) = self.peek() {
+      if !predicate(ch) {
+        break;
+      }
+      self.pos += ch.len_utf8();
+    }
+  }
+
+  pub fn advance_to_end(&mut self) { self.pos = self.line.len(); }
+
+  /// Mark the text from `start` up to the current position as a token of `kind`.
+  pub fn push_span(&mut self, kind: TokenKind, start: usize) {
+    if start >= self.pos {
+      return;
+    }
+    match self.spans.last_mut() {
+      Some(last_span) if last_span.kind == kind && last_span.range.end == start => {
+        last_span.range.end = self.pos;
+      }
+      _ => self.spans.push(Span {
+        kind,
+        range: start..self.pos,
+      }),
+    }
+  }
+
+  /// The marked spans, w/ the gaps between them filled w/ [TokenKind::Plain] spans.
+  pub fn into_spans(self) -> Vec<Span> {
+    let mut spans = vec![];
+    let mut pos = 0;
+    for span in self.spans {
+      if span.range.start > pos {
+        spans.push(Span {
+          kind: TokenKind::Plain,
+          range: pos..span.range.start,
+        });
+      }
+      pos = span.range.end;
+      spans.push(span);
+    }
+    if pos < self.line.len() {
+      spans.push(Span {
+        kind: TokenKind::Plain,
+        range: pos..self.line.len(),
+      });
+    }
+    spans
+  }
+}
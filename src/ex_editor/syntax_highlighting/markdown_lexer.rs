@@ -0,0 +1,188 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use super::*;
+
+/// Markers for list items (incl. the `☐`, `✔` & `✘` used in `.todo` files).
+const BULLET_MARKERS: &[&str] = &["- ", "* ", "+ ", "☐ ", "✔ ", "✘ "];
+const TASK_MARKERS: &[&str] = &["[ ] ", "[x] ", "[X] "];
+
+/// Marks headings, block quotes, list markers, emphasis, links & code. Fenced code blocks w/ a known
+/// language tag (eg: ```rust) are highlighted using the lexer for that language.
+pub fn lex_markdown_line(scanner: &mut Scanner<'_>, start_state: LexState) -> LexState {
+  let trimmed_line = scanner.line.trim_start();
+  let is_fence = trimmed_line.starts_with("```");
+
+  // Inside a fenced code block.
+  if let LexState::MarkdownFence(maybe_language, inner_state) = start_state {
+    if is_fence {
+      scanner.advance_to_end();
+      scanner.push_span(TokenKind::Code, 0);
+      return LexState::Normal;
+    }
+    return match maybe_language {
+      Some(language) => {
+        let inner_state = lex_rest_of_line(language, scanner, *inner_state);
+        LexState::MarkdownFence(Some(language), Box::new(inner_state))
+      }
+      None => {
+        scanner.advance_to_end();
+        scanner.push_span(TokenKind::Code, 0);
+        LexState::MarkdownFence(None, Box::new(LexState::Normal))
+      }
+    };
+  }
+
+  // Start of a fenced code block.
+  if is_fence {
+    let tag = trimmed_line
+      .trim_start_matches('`')
+      .split(|ch: char| ch.is_whitespace() || ch == ',' || ch == '{')
+      .next()
+      .unwrap_or_default();
+    scanner.advance_to_end();
+    scanner.push_span(TokenKind::Code, 0);
+    return LexState::MarkdownFence(Language::from_fence_tag(tag), Box::new(LexState::Normal));
+  }
+
+  if is_heading(trimmed_line) {
+    scanner.advance_to_end();
+    scanner.push_span(TokenKind::Heading, 0);
+    return LexState::Normal;
+  }
+
+  if trimmed_line.starts_with('>') {
+    scanner.advance_to_end();
+    scanner.push_span(TokenKind::Quote, 0);
+    return LexState::Normal;
+  }
+
+  lex_list_marker(scanner);
+  lex_inline(scanner);
+  LexState::Normal
+}
+
+/// `# Heading` up to `###### Heading`.
+fn is_heading(trimmed_line: &str) -> bool {
+  let level = trimmed_line.chars().take_while(|ch| *ch == '#').count();
+  (1..=6).contains(&level)
+    && trimmed_line[level..]
+      .chars()
+      .next()
+      .is_none_or(char::is_whitespace)
+}
+
+/// Bullets (`- `, `* `, `+ `, `☐ `), numbers (`1. `, `1) `) & task boxes (`- [ ] `) at the start of
+/// the line.
+fn lex_list_marker(scanner: &mut Scanner<'_>) {
+  scanner.advance_while(char::is_whitespace);
+  let start = scanner.pos;
+  let rest = scanner.rest();
+
+  let maybe_marker_len = BULLET_MARKERS
+    .iter()
+    .find(|marker| rest.starts_with(*marker))
+    .map(|marker| marker.len())
+    .or_else(|| {
+      let digit_count = rest.chars().take_while(char::is_ascii_digit).count();
+      let after_digits = &rest[digit_count..];
+      (digit_count > 0 && (after_digits.starts_with(". ") || after_digits.starts_with(") ")))
+        .then_some(digit_count + 2)
+    });
+
+  if let Some(marker_len) = maybe_marker_len {
+    scanner.advance_by(marker_len);
+    if let Some(task_marker) = TASK_MARKERS
+      .iter()
+      .find(|marker| scanner.starts_with(marker))
+    {
+      scanner.advance_by(task_marker.len());
+    }
+    scanner.push_span(TokenKind::ListMarker, start);
+  }
+}
+
+/// Code spans, links, images, strong (`**`, `__`) & emphasis (`*`, `_`).
+fn lex_inline(scanner: &mut Scanner<'_>) {
+  while let Some(ch) = scanner.peek() {
+    let start = scanner.pos;
+    let rest = scanner.rest();
+
+    let maybe_token = match ch {
+      '`' => {
+        let backticks_len = rest.chars().take_while(|ch| *ch == '`').count();
+        let backticks = &rest[..backticks_len];
+        rest[backticks_len..]
+          .find(backticks)
+          .map(|index| (TokenKind::Code, index + backticks_len * 2))
+      }
+      '[' => link_len(rest).map(|len| (TokenKind::Link, len)),
+      '!' if rest[1..].starts_with('[') => {
+        link_len(&rest[1..]).map(|len| (TokenKind::Link, len + 1))
+      }
+      '*' | '_' if rest.starts_with("**") || rest.starts_with("__") => {
+        delimited_len(rest, &rest[..2]).map(|len| (TokenKind::Strong, len))
+      }
+      '*' | '_' => {
+        // `_` inside of a word (eg: snake_case) isn't emphasis.
+        let is_in_word = ch == '_'
+          && scanner.line[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+        if is_in_word {
+          None
+        } else {
+          delimited_len(rest, &rest[..1]).map(|len| (TokenKind::Emphasis, len))
+        }
+      }
+      _ => None,
+    };
+
+    match maybe_token {
+      Some((kind, len)) => {
+        scanner.advance_by(len);
+        scanner.push_span(kind, start);
+      }
+      None => {
+        scanner.advance();
+      }
+    }
+  }
+}
+
+/// Length of `[text](url)` at the start of `text`.
+fn link_len(text: &str) -> Option<usize> {
+  let label_end = text.find("](")?;
+  let url_end = text[label_end..].find(')')?;
+  Some(label_end + url_end + 1)
+}
+
+/// Length of the `delimiter` at the start of `text`, some text, & the closing `delimiter`. The text
+/// can't start or end w/ whitespace (eg: `2 * 3 * 4` isn't emphasis).
+fn delimited_len(text: &str, delimiter: &str) -> Option<usize> {
+  let content = &text[delimiter.len()..];
+  let content_len = content.find(delimiter)?;
+  let inner_text = &content[..content_len];
+  if inner_text.is_empty()
+    || inner_text.starts_with(char::is_whitespace)
+    || inner_text.ends_with(char::is_whitespace)
+  {
+    return None;
+  }
+  Some(delimiter.len() * 2 + content_len)
+}
@@ -0,0 +1,39 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Syntax highlighting for the editor. Each line is split into [Span]s (by a lexer for the
+//! [Language] of the file) which are painted w/ a style per [TokenKind]. The lexers work one line
+//! at a time & pass a [LexState] from each line to the next one (eg: inside a block comment), which
+//! lets [SyntaxHighlighter] keep the lines above an edit, & stop at the last line that is painted.
+
+// Attach sources.
+pub mod language;
+pub mod lexer;
+pub mod markdown_lexer;
+pub mod rust_lexer;
+pub mod span;
+pub mod syntax_highlighter;
+pub mod toml_lexer;
+
+// Re-export.
+pub use language::*;
+pub use lexer::*;
+pub use markdown_lexer::*;
+pub use rust_lexer::*;
+pub use span::*;
+pub use syntax_highlighter::*;
+pub use toml_lexer::*;
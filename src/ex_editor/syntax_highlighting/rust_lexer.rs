@@ -0,0 +1,210 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use super::*;
+
+const RUST_KEYWORDS: &[&str] = &[
+  "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+  "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+  "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "union",
+  "unsafe", "use", "where", "while",
+];
+
+/// Marks comments (incl. nested block comments), strings (incl. raw & multi line strings), char
+/// literals, numbers, keywords, types (capitalized identifiers), macros & attributes.
+pub fn lex_rust_line(scanner: &mut Scanner<'_>, start_state: LexState) -> LexState {
+  let start = scanner.pos;
+  let mut state = match start_state {
+    LexState::RustBlockComment(depth) => lex_block_comment(scanner, start, depth),
+    LexState::RustString => lex_string(scanner, start),
+    LexState::RustRawString(hash_count) => lex_raw_string(scanner, start, hash_count),
+    _ => LexState::Normal,
+  };
+
+  while state == LexState::Normal && !scanner.is_done() {
+    state = lex_token(scanner);
+  }
+
+  state
+}
+
+fn lex_token(scanner: &mut Scanner<'_>) -> LexState {
+  let start = scanner.pos;
+  let rest = scanner.rest();
+  let ch = match scanner.peek() {
+    Some(ch) => ch,
+    None => return LexState::Normal,
+  };
+
+  // Comments.
+  if rest.starts_with("//") {
+    scanner.advance_to_end();
+    scanner.push_span(TokenKind::Comment, start);
+    return LexState::Normal;
+  }
+  if rest.starts_with("/*") {
+    scanner.advance_by(2);
+    return lex_block_comment(scanner, start, 1);
+  }
+
+  // Strings: "..", b"..", r#".."#, br#".."#.
+  if let Some(prefix_len) = ["\"", "b\""]
+    .iter()
+    .find_map(|prefix| rest.starts_with(prefix).then_some(prefix.len()))
+  {
+    scanner.advance_by(prefix_len);
+    return lex_string(scanner, start);
+  }
+  let after_b = rest.strip_prefix('b').unwrap_or(rest);
+  if let Some(after_r) = after_b.strip_prefix('r') {
+    let hash_count = after_r.chars().take_while(|ch| *ch == '#').count();
+    if after_r[hash_count..].starts_with('"') {
+      scanner.advance_by(rest.len() - after_r.len() + hash_count + 1);
+      return lex_raw_string(scanner, start, hash_count);
+    }
+  }
+
+  // Char literals ('a', '\n') or lifetimes ('a).
+  if ch == '\'' {
+    match char_literal_len(rest) {
+      Some(len) => {
+        scanner.advance_by(len);
+        scanner.push_span(TokenKind::String, start);
+      }
+      None => {
+        scanner.advance();
+        scanner.advance_while(is_ident_char);
+      }
+    }
+    return LexState::Normal;
+  }
+
+  // Numbers (eg: 10, 0xff, 1_000, 2.5e3, 10u8).
+  if ch.is_ascii_digit() {
+    scanner.advance_while(is_ident_char);
+    if scanner.peek() == Some('.') && scanner.peek_nth(1).is_some_and(|ch| ch.is_ascii_digit()) {
+      scanner.advance();
+      scanner.advance_while(is_ident_char);
+    }
+    scanner.push_span(TokenKind::Number, start);
+    return LexState::Normal;
+  }
+
+  // Identifiers, keywords, types & macros.
+  if ch.is_alphabetic() || ch == '_' {
+    scanner.advance_while(is_ident_char);
+    let word = &scanner.line[start..scanner.pos];
+    if scanner.peek() == Some('!') && scanner.peek_nth(1) != Some('=') {
+      scanner.advance();
+      scanner.push_span(TokenKind::Macro, start);
+    } else if RUST_KEYWORDS.contains(&word) {
+      scanner.push_span(TokenKind::Keyword, start);
+    } else if word.starts_with(char::is_uppercase) {
+      scanner.push_span(TokenKind::Type, start);
+    }
+    return LexState::Normal;
+  }
+
+  // Attributes: #[..] & #![..].
+  if rest.starts_with("#[") || rest.starts_with("#![") {
+    let mut depth = 0;
+    while let Some(ch) = scanner.advance() {
+      match ch {
+        '[' => depth += 1,
+        ']' => {
+          depth -= 1;
+          if depth == 0 {
+            break;
+          }
+        }
+        _ => {}
+      }
+    }
+    scanner.push_span(TokenKind::Attribute, start);
+    return LexState::Normal;
+  }
+
+  scanner.advance();
+  LexState::Normal
+}
+
+fn lex_block_comment(scanner: &mut Scanner<'_>, start: usize, mut depth: usize) -> LexState {
+  while !scanner.is_done() {
+    if scanner.starts_with("*/") {
+      scanner.advance_by(2);
+      depth -= 1;
+      if depth == 0 {
+        scanner.push_span(TokenKind::Comment, start);
+        return LexState::Normal;
+      }
+    } else if scanner.starts_with("/*") {
+      scanner.advance_by(2);
+      depth += 1;
+    } else {
+      scanner.advance();
+    }
+  }
+  scanner.push_span(TokenKind::Comment, start);
+  LexState::RustBlockComment(depth)
+}
+
+fn lex_string(scanner: &mut Scanner<'_>, start: usize) -> LexState {
+  while let Some(ch) = scanner.advance() {
+    match ch {
+      '\\' => {
+        scanner.advance();
+      }
+      '"' => {
+        scanner.push_span(TokenKind::String, start);
+        return LexState::Normal;
+      }
+      _ => {}
+    }
+  }
+  scanner.push_span(TokenKind::String, start);
+  LexState::RustString
+}
+
+fn lex_raw_string(scanner: &mut Scanner<'_>, start: usize, hash_count: usize) -> LexState {
+  let closing_quote = format!("\"{}", "#".repeat(hash_count));
+  while !scanner.is_done() {
+    if scanner.starts_with(&closing_quote) {
+      scanner.advance_by(closing_quote.len());
+      scanner.push_span(TokenKind::String, start);
+      return LexState::Normal;
+    }
+    scanner.advance();
+  }
+  scanner.push_span(TokenKind::String, start);
+  LexState::RustRawString(hash_count)
+}
+
+/// Length (in bytes) of the char literal at the start of `text` (if it is one, & not a lifetime).
+fn char_literal_len(text: &str) -> Option<usize> {
+  let mut chars = text.char_indices().skip(1);
+  match chars.next()? {
+    // The escaped char is skipped, since it might be a quote.
+    (_, '\\') => text.get(3..)?.find('\'').map(|index| index + 4),
+    (_, '\'') => None,
+    (index, ch) => {
+      let end_index = index + ch.len_utf8();
+      text[end_index..].starts_with('\'').then_some(end_index + 1)
+    }
+  }
+}
+
+fn is_ident_char(ch: char) -> bool { ch.is_alphanumeric() || ch == '_' }
@@ -0,0 +1,185 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::ops::Range;
+
+use r3bl_rs_utils::*;
+
+use crate::common::*;
+
+/// What a [Span] of text is, which determines the style that it is painted with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+  Plain,
+  Keyword,
+  Type,
+  Macro,
+  Attribute,
+  Key,
+  String,
+  Number,
+  Comment,
+  Heading,
+  Code,
+  Emphasis,
+  Strong,
+  Link,
+  ListMarker,
+  Quote,
+  SearchMatch,
+  CurrentSearchMatch,
+  /// Selected text, which is painted w/ the selection style (instead of the colors of its kind).
+  Selection,
+}
+
+/// The bytes in `range` of a line are a token of `kind`. The spans of a line are contiguous & cover
+/// the whole line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+  pub kind: TokenKind,
+  pub range: Range<usize>,
+}
+
+impl TokenKind {
+  pub fn get_color(&self) -> Option<TWColor> {
+    let (r, g, b) = match self {
+      TokenKind::Plain | TokenKind::Selection => return None,
+      TokenKind::Keyword | TokenKind::ListMarker => (198, 120, 221),
+      TokenKind::Type => (229, 192, 123),
+      TokenKind::Macro | TokenKind::Key => (97, 175, 239),
+      TokenKind::Attribute => (86, 182, 194),
+      TokenKind::String | TokenKind::Code => (152, 195, 121),
+      TokenKind::Number => (209, 154, 102),
+      TokenKind::Comment | TokenKind::Quote => (150, 155, 165),
+      TokenKind::Heading => (224, 108, 117),
+      TokenKind::Emphasis => (230, 200, 160),
+      TokenKind::Strong => (255, 170, 90),
+      TokenKind::Link => (110, 200, 250),
+      TokenKind::SearchMatch | TokenKind::CurrentSearchMatch => (0, 0, 0),
+    };
+    Some(TWColor::Rgb { r, g, b })
+  }
+
+  pub fn get_bg_color(&self) -> Option<TWColor> {
+    match self {
+      TokenKind::SearchMatch => Some(TWColor::Rgb {
+        r: 229,
+        g: 192,
+        b: 123,
+      }),
+      TokenKind::CurrentSearchMatch => Some(TWColor::Rgb {
+        r: 255,
+        g: 140,
+        b: 0,
+      }),
+      _ => None,
+    }
+  }
+
+  /// The `base_style` (eg: the style of the box) w/ the colors for this kind of token.
+  pub fn get_style(&self, base_style: &Option<Style>) -> Style {
+    let mut style = base_style.clone().unwrap_or_default();
+    if let Some(color) = self.get_color() {
+      style.color_fg = Some(color);
+    }
+    if let Some(color) = self.get_bg_color() {
+      style.color_bg = Some(color);
+    }
+    style
+  }
+}
+
+/// Split the `spans` so that the bytes in `ranges` (which are sorted & don't overlap) are marked as
+/// `kind` (eg: search matches over syntax highlighting).
+pub fn overlay_spans(spans: &[Span], ranges: &[Range<usize>], kind: TokenKind) -> Vec<Span> {
+  let mut overlaid_spans = vec![];
+  for span in spans {
+    let mut pos = span.range.start;
+    for range in ranges {
+      let start = range.start.max(pos);
+      let end = range.end.min(span.range.end);
+      if start >= end {
+        continue;
+      }
+      if start > pos {
+        overlaid_spans.push(Span {
+          kind: span.kind,
+          range: pos..start,
+        });
+      }
+      overlaid_spans.push(Span {
+        kind,
+        range: start..end,
+      });
+      pos = end;
+    }
+    if pos < span.range.end {
+      overlaid_spans.push(Span {
+        kind: span.kind,
+        range: pos..span.range.end,
+      });
+    }
+  }
+  overlaid_spans
+}
+
+/// Turn the `spans` of a `line` into styled texts, for the part of the line that is in a
+/// [VisibleLineSlice] of a viewport that is `width` cols wide. [TokenKind::Selection] spans are
+/// painted w/ the `selection_style`.
+pub fn spans_to_styled_texts(
+  line: &str, spans: &[Span], visible_line_slice: &VisibleLineSlice, width: UnitType,
+  base_style: &Option<Style>, selection_style: &Style,
+) -> StyledTexts {
+  let byte_range = &visible_line_slice.byte_range;
+  let start_col = visible_line_slice.start_col;
+  let end_col = start_col + width;
+
+  let mut styled_texts = StyledTexts::default();
+  let mut col: UnitType = 0;
+
+  for span in spans {
+    let start_byte_index = span.range.start.max(byte_range.start);
+    let end_byte_index = span.range.end.min(byte_range.end);
+    if start_byte_index >= end_byte_index {
+      continue;
+    }
+
+    let text = &line[start_byte_index..end_byte_index];
+    let text_start_col = col;
+    let text_end_col = col + display_width(text);
+    col = text_end_col;
+
+    let visible_start_col = text_start_col.max(start_col);
+    let visible_end_col = text_end_col.min(end_col);
+    if visible_start_col >= visible_end_col {
+      continue;
+    }
+
+    let visible_text = clip_to_display_cols(
+      text,
+      visible_start_col - text_start_col,
+      visible_end_col - visible_start_col,
+    );
+    let style = match span.kind {
+      TokenKind::Selection => selection_style.clone(),
+      _ => span.kind.get_style(base_style),
+    };
+    styled_texts += styled_text! { visible_text, style };
+  }
+
+  styled_texts
+}
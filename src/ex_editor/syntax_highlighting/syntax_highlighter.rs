@@ -0,0 +1,102 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::ops::Range;
+
+use super::*;
+use crate::ex_editor::*;
+
+/// The spans of a line (w/ the `text` that was lexed), & the state that the next line starts in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct LexedLine {
+  text: String,
+  spans: Vec<Span>,
+  end_state: LexState,
+}
+
+/// Highlights the lines of an editor buffer, caching the spans of each line (by its index). The
+/// lines are lexed in order from the top (since each one starts in the state that the line above it
+/// ended in), but only as far down as the last line that is painted. An edit drops the lines from
+/// the first one that it changed (see [LinesRevision]) down, so the lines above it are never lexed
+/// again.
+#[derive(Clone, Debug, Default)]
+pub struct SyntaxHighlighter {
+  language: Language,
+  lexed_lines: Vec<LexedLine>,
+  /// The id of the [LinesRevision] that the `lexed_lines` are for.
+  lines_revision_id: u64,
+}
+
+impl SyntaxHighlighter {
+  /// The cache is cleared when the language changes.
+  pub fn set_language(&mut self, language: Language) {
+    if self.language != language {
+      self.language = language;
+      self.lexed_lines.clear();
+    }
+  }
+
+  /// Returns the spans for the lines in `line_range`. Only the lines (above the end of the range)
+  /// that aren't in the cache are lexed.
+  pub fn highlight_lines(
+    &mut self, lines: &[String], lines_revision: &LinesRevision, line_range: Range<usize>,
+  ) -> Vec<Vec<Span>> {
+    self.drop_edited_lines(lines, lines_revision);
+
+    let end_row = line_range.end.min(lines.len());
+    while self.lexed_lines.len() < end_row {
+      let start_state = match self.lexed_lines.last() {
+        Some(lexed_line) => lexed_line.end_state.clone(),
+        None => LexState::Normal,
+      };
+      let text = lines[self.lexed_lines.len()].clone();
+      let (spans, end_state) = lex_line(self.language, &text, &start_state);
+      self.lexed_lines.push(LexedLine {
+        text,
+        spans,
+        end_state,
+      });
+    }
+
+    self.lexed_lines[line_range.start.min(end_row)..end_row]
+      .iter()
+      .map(|lexed_line| lexed_line.spans.clone())
+      .collect()
+  }
+
+  /// Drop the lines from the first one that was edited down. If the lines aren't the next revision
+  /// after the cached one (eg: after an undo, or a few edits between paints), the lines are
+  /// compared to the cached text to find the first one that is different.
+  fn drop_edited_lines(&mut self, lines: &[String], lines_revision: &LinesRevision) {
+    if lines_revision.id == self.lines_revision_id {
+      return;
+    }
+
+    let first_edited_row = if lines_revision.prior_id == self.lines_revision_id {
+      lines_revision.first_edited_row
+    } else {
+      self
+        .lexed_lines
+        .iter()
+        .zip(lines)
+        .take_while(|(lexed_line, line)| lexed_line.text == **line)
+        .count()
+    };
+    self.lexed_lines.truncate(first_edited_row);
+    self.lines_revision_id = lines_revision.id;
+  }
+}
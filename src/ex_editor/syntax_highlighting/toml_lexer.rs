@@ -0,0 +1,126 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use super::*;
+
+/// Marks comments, table headers, keys, strings (incl. multi line strings), numbers (incl. dates)
+/// & booleans.
+pub fn lex_toml_line(scanner: &mut Scanner<'_>, start_state: LexState) -> LexState {
+  // A table header has to be the 1st thing in the line.
+  let mut is_header_allowed = true;
+  if let LexState::TomlMultilineString(quote) = start_state {
+    is_header_allowed = false;
+    let state = lex_multiline_string(scanner, 0, quote);
+    if state != LexState::Normal {
+      return state;
+    }
+  }
+
+  while !scanner.is_done() {
+    scanner.advance_while(char::is_whitespace);
+    let start = scanner.pos;
+    let rest = scanner.rest();
+    let ch = match scanner.peek() {
+      Some(ch) => ch,
+      None => break,
+    };
+
+    match ch {
+      '#' => {
+        scanner.advance_to_end();
+        scanner.push_span(TokenKind::Comment, start);
+      }
+      '[' if is_header_allowed => {
+        scanner.advance_while(|ch| ch != ']');
+        scanner.advance_while(|ch| ch == ']');
+        scanner.push_span(TokenKind::Heading, start);
+      }
+      '"' | '\'' if rest.starts_with("\"\"\"") || rest.starts_with("'''") => {
+        scanner.advance_by(3);
+        let state = lex_multiline_string(scanner, start, ch);
+        if state != LexState::Normal {
+          return state;
+        }
+      }
+      '"' | '\'' => {
+        scanner.advance();
+        while let Some(next_ch) = scanner.advance() {
+          if next_ch == '\\' && ch == '"' {
+            scanner.advance();
+          } else if next_ch == ch {
+            break;
+          }
+        }
+        let kind = if is_key_next(scanner) {
+          TokenKind::Key
+        } else {
+          TokenKind::String
+        };
+        scanner.push_span(kind, start);
+      }
+      _ if is_bare_word_char(ch) => {
+        scanner.advance_while(is_bare_word_char);
+        let word = &scanner.line[start..scanner.pos];
+        if is_key_next(scanner) {
+          scanner.push_span(TokenKind::Key, start);
+        } else if word == "true" || word == "false" {
+          scanner.push_span(TokenKind::Keyword, start);
+        } else if word.starts_with(|ch: char| ch.is_ascii_digit() || ch == '+' || ch == '-')
+          || word == "inf"
+          || word == "nan"
+        {
+          scanner.push_span(TokenKind::Number, start);
+        }
+      }
+      _ => {
+        scanner.advance();
+      }
+    }
+    is_header_allowed = false;
+  }
+
+  LexState::Normal
+}
+
+/// Lex up to (& including) the closing `"""` or `'''`.
+fn lex_multiline_string(scanner: &mut Scanner<'_>, start: usize, quote: char) -> LexState {
+  let closing_quote = quote.to_string().repeat(3);
+  while !scanner.is_done() {
+    if scanner.starts_with(&closing_quote) {
+      scanner.advance_by(3);
+      // Up to 2 more quotes are part of the string.
+      scanner.advance_while(|ch| ch == quote);
+      scanner.push_span(TokenKind::String, start);
+      return LexState::Normal;
+    }
+    if scanner.advance() == Some('\\') && quote == '"' {
+      scanner.advance();
+    }
+  }
+  scanner.push_span(TokenKind::String, start);
+  LexState::TomlMultilineString(quote)
+}
+
+/// A key (or a part of a dotted key) is followed by `=` or `.`.
+fn is_key_next(scanner: &Scanner<'_>) -> bool {
+  scanner.rest().trim_start().starts_with(['=', '.'])
+}
+
+/// Chars in bare keys, numbers, dates & booleans.
+fn is_bare_word_char(ch: char) -> bool {
+  ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.' | '+' | ':')
+}
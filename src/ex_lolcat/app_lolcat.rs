@@ -0,0 +1,121 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::common::*;
+
+/// Async trait object that implements the [TWApp] trait. Shows the lines in the state, painted w/
+/// the rainbow colors of the `lolcat`, & scrolls through them.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct AppLolcat {
+  pub lolcat: Lolcat,
+}
+
+#[async_trait]
+impl TWApp<LolcatState, LolcatAction> for AppLolcat {
+  async fn app_render(
+    &mut self, state: &LolcatState, _shared_store: &SharedStore<LolcatState, LolcatAction>,
+    window_size: Size,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      let mut queue = tw_command_queue!(TWCommand::ClearScreen, TWCommand::ResetColor);
+
+      // Leave the bottom row for the status bar.
+      let viewport_size: Size = (window_size.cols, window_size.rows.saturating_sub(1)).into();
+      for (row, visible_text) in state
+        .viewport
+        .visible_lines(&state.lines, viewport_size, false)
+      {
+        tw_command_queue! {
+          queue push
+          TWCommand::MoveCursorPositionAbs((0, row).into()),
+          TWCommand::PrintWithAttributes(
+            colorize_using_lolcat! { &mut self.lolcat, "{}", visible_text },
+            None
+          ),
+          TWCommand::ResetColor
+        };
+      }
+
+      status_bar_helpers::create_status_bar_message(&mut queue, window_size);
+
+      call_if_true!(is_logging_enabled(), {
+        log_no_err!(
+          INFO,
+          "🦜 AppLolcat::render -> size, state: {} {}",
+          window_size,
+          state
+        );
+      });
+      queue
+    });
+  }
+
+  async fn app_handle_event(
+    &mut self, input_event: &TWInputEvent, _state: &LolcatState,
+    shared_store: &SharedStore<LolcatState, LolcatAction>, _terminal_size: Size,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      let mut event_consumed = false;
+
+      if let TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: None,
+        non_modifier_key: NonModifierKey::Special(special_key),
+      }) = input_event
+      {
+        match special_key {
+          SpecialKey::Up => {
+            spawn_and_consume_event!(event_consumed, shared_store, LolcatAction::ScrollUp)
+          }
+          SpecialKey::Down => {
+            spawn_and_consume_event!(event_consumed, shared_store, LolcatAction::ScrollDown)
+          }
+          _ => {}
+        }
+      }
+
+      if event_consumed {
+        EventPropagation::Consumed
+      } else {
+        EventPropagation::Propagate
+      }
+    });
+  }
+}
+
+mod status_bar_helpers {
+  use r3bl_rs_utils::*;
+
+  /// Shows helpful messages at the bottom row of the screen.
+  pub fn create_status_bar_message(queue: &mut TWCommandQueue, size: Size) {
+    let st_vec = styled_texts! {
+      styled_text! { "Hints:",             style!(attrib: [dim])       },
+      styled_text! { " ↑ / ↓ : scroll ",   style!(attrib: [underline]) }
+    };
+
+    let display_width = st_vec.unicode_string().display_width;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    let center: Position = (col_center, row_bottom).into();
+
+    *queue += TWCommand::MoveCursorPositionAbs(center);
+    *queue += st_vec.render();
+  }
+}
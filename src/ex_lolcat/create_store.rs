@@ -0,0 +1,92 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use crate::common::*;
+
+// Create a new store and attach the reducer.
+pub async fn create_store() -> Store<LolcatState, LolcatAction> {
+  let mut store: Store<LolcatState, LolcatAction> = Store::default();
+  store.add_reducer(LolcatReducer::new()).await;
+  store
+}
+
+/// Action.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+#[allow(dead_code)]
+pub enum LolcatAction {
+  SetLines(Vec<String>),
+  ScrollUp,
+  ScrollDown,
+  #[default]
+  Noop,
+}
+
+impl Display for LolcatAction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{:?}", self) }
+}
+
+/// State. The lines that are painted w/ the rainbow colors, & the part of them that is on screen.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct LolcatState {
+  pub lines: Vec<String>,
+  pub viewport: Viewport,
+}
+
+impl Display for LolcatState {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "State {{ lines: {}, viewport: {:?} }}",
+      self.lines.len(),
+      self.viewport
+    )
+  }
+}
+
+/// Reducer. The last line can be scrolled up to the top of the screen, but no further.
+#[derive(Default)]
+pub struct LolcatReducer;
+
+#[async_trait]
+impl AsyncReducer<LolcatState, LolcatAction> for LolcatReducer {
+  async fn run(&self, action: &LolcatAction, state: &LolcatState) -> LolcatState {
+    let mut new_state = state.clone();
+
+    match action {
+      LolcatAction::SetLines(lines) => {
+        new_state.lines = lines.clone();
+        new_state.viewport = Viewport::default();
+      }
+      LolcatAction::ScrollUp => {
+        new_state.viewport.offset.row = state.viewport.offset.row.saturating_sub(1)
+      }
+      LolcatAction::ScrollDown => {
+        let last_row = state.lines.len().saturating_sub(1) as UnitType;
+        new_state.viewport.offset.row = (state.viewport.offset.row + 1).min(last_row)
+      }
+      _ => {}
+    }
+
+    new_state
+  }
+}
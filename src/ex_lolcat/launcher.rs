@@ -15,27 +15,160 @@
  *   limitations under the License.
  */
 
-use std::io::{BufRead, BufReader};
-
-use tokio::fs::File;
+use std::{fs::File,
+          io::{self, stdin, stdout, BufRead, BufReader, ErrorKind, IsTerminal, Write}};
 
 use crate::*;
 
-pub async fn run_app() -> CommonResult<()> {
-  let mut my_lolcat = Lolcat::new();
+/// The file path that stands for stdin.
+const STDIN_FILE_PATH: &str = "-";
+
+/// Print the given files (or stdin, if there are none) in rainbow colors, one line at a time, so
+/// that it can be used in a pipeline. The colors are left out if stdout isn't a terminal. Lines
+/// that aren't valid UTF-8 are printed lossily (w/ `�` in place of the invalid bytes).
+///
+/// The (blocking) task that prints the lines is awaited, so all of them are printed before this
+/// returns. If a file can't be read, then the error is printed & the rest of the files are still
+/// printed. But an error is returned at the end, so that the process exits w/ a non zero status
+/// code.
+pub async fn run_app(file_paths: Vec<String>) -> CommonResult<()> {
+  throws!({
+    let file_paths = get_file_paths(file_paths)?;
+    let is_colorized = stdout().is_terminal();
+    tokio::task::spawn_blocking(move || print_files(&file_paths, is_colorized)).await??;
+  });
+}
 
-  println!("{:?}", my_lolcat);
+/// Stdin is read if no file paths are passed in, unless it is a terminal (eg: when lolcat is run
+/// from the picker), since there wouldn't be anything to read from it.
+fn get_file_paths(file_paths: Vec<String>) -> CommonResult<Vec<String>> {
+  match (file_paths.is_empty(), stdin().is_terminal()) {
+    (false, _) => Ok(file_paths),
+    (true, false) => Ok(vec![STDIN_FILE_PATH.to_string()]),
+    (true, true) => Err(
+      "Nothing to print (pass the files to print, eg: `r3bl-cmdr lolcat Cargo.toml`, or pipe text \
+       into it)"
+        .into(),
+    ),
+  }
+}
 
-  let file = File::open("Cargo.lock").await?;
-  let file = file.into_std().await;
+/// Returns an error if any of the files couldn't be read. It stops early (w/out an error) if stdout
+/// is closed, eg: when it is piped into `head`.
+fn print_files(file_paths: &[String], is_colorized: bool) -> CommonResult<()> {
+  throws!({
+    let mut lolcat = Lolcat::new();
+    let mut stdout = stdout().lock();
+    let mut failed_file_count = 0;
 
-  tokio::task::spawn_blocking(move || {
-    let buffer_reader = BufReader::new(file);
-    for (index, line) in buffer_reader.lines().enumerate() {
-      let line = line.unwrap();
-      println!("{}. {}", index + 1, my_lolcat.format_str(&line));
+    for file_path in file_paths {
+      match print_file(file_path, &mut lolcat, is_colorized, &mut stdout) {
+        Ok(_) => {}
+        Err(error) if error.kind() == ErrorKind::BrokenPipe => break,
+        Err(error) => {
+          eprintln!("lolcat: {}: {}", file_path, error);
+          failed_file_count += 1;
+        }
+      }
+    }
+
+    if failed_file_count > 0 {
+      return Err(format!("{} file(s) couldn't be read", failed_file_count).into());
     }
   });
+}
+
+fn print_file(
+  file_path: &str, lolcat: &mut Lolcat, is_colorized: bool, writer: &mut impl Write,
+) -> io::Result<()> {
+  if file_path == STDIN_FILE_PATH {
+    print_lines(stdin().lock(), lolcat, is_colorized, writer)
+  } else {
+    let file = File::open(file_path)?;
+    print_lines(BufReader::new(file), lolcat, is_colorized, writer)
+  }
+}
+
+/// Reads (& writes) one line at a time, so the whole input is never held in memory.
+fn print_lines(
+  reader: impl BufRead, lolcat: &mut Lolcat, is_colorized: bool, writer: &mut impl Write,
+) -> io::Result<()> {
+  for_each_line(reader, |line| {
+    if is_colorized {
+      writeln!(writer, "{}", lolcat.format_str(line))
+    } else {
+      writeln!(writer, "{}", line)
+    }
+  })?;
+  writer.flush()
+}
+
+/// All the lines in the file (eg: to show them in an [AppLolcat]), read the same way that they are
+/// printed.
+pub fn read_lines(file_path: &str) -> io::Result<Vec<String>> {
+  let mut lines = vec![];
+  for_each_line(BufReader::new(File::open(file_path)?), |line| {
+    lines.push(line.to_string());
+    Ok(())
+  })?;
+  Ok(lines)
+}
 
+/// Calls `on_line` w/ each line (w/out its line ending). Lines that aren't valid UTF-8 are decoded
+/// lossily.
+fn for_each_line(
+  mut reader: impl BufRead, mut on_line: impl FnMut(&str) -> io::Result<()>,
+) -> io::Result<()> {
+  let mut buffer = vec![];
+  while reader.read_until(b'\n', &mut buffer)? > 0 {
+    let line = String::from_utf8_lossy(&buffer);
+    let line = line.strip_suffix('\n').unwrap_or(&line);
+    on_line(line.strip_suffix('\r').unwrap_or(line))?;
+    buffer.clear();
+  }
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn print_lines_to_string(input: &[u8], is_colorized: bool) -> String {
+    let mut output = vec![];
+    print_lines(input, &mut Lolcat::new(), is_colorized, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+  }
+
+  #[test]
+  fn test_every_line_is_printed() {
+    let input = b"first\r\nsecond\n\nlast w/out a newline";
+    assert_eq!(
+      print_lines_to_string(input, false),
+      "first\nsecond\n\nlast w/out a newline\n"
+    );
+    assert_eq!(print_lines_to_string(input, true).lines().count(), 4);
+  }
+
+  #[test]
+  fn test_invalid_utf8_is_printed_lossily() {
+    let input = b"valid\ninvalid \xF0\x28\x8C\xBC\nvalid again\n";
+    assert_eq!(
+      print_lines_to_string(input, false),
+      "valid\ninvalid \u{FFFD}(\u{FFFD}\u{FFFD}\nvalid again\n"
+    );
+  }
+
+  #[test]
+  fn test_read_lines_decodes_like_print_lines() {
+    let file_path = std::env::temp_dir().join("r3bl_cmdr_test_read_lines.txt");
+    std::fs::write(&file_path, b"valid\r\ninvalid \xFF\n").unwrap();
+    let lines = read_lines(file_path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&file_path).unwrap();
+    assert_eq!(lines, vec!["valid", "invalid \u{FFFD}"]);
+  }
+
+  #[tokio::test]
+  async fn test_a_file_that_cant_be_read_is_an_error() {
+    assert!(run_app(vec!["does/not/exist".to_string()]).await.is_err());
+  }
+}
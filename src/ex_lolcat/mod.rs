@@ -16,7 +16,11 @@
  */
 
 // Attach.
+pub mod app_lolcat;
+pub mod create_store;
 pub mod launcher;
 
 // Re-export.
+pub use app_lolcat::*;
+pub use create_store::*;
 pub use launcher::*;
@@ -0,0 +1,456 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::{sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+            task::JoinHandle};
+
+use super::*;
+use crate::{common::*, ex_router::*};
+
+/// Panes that are smaller than this (not counting their title row) show a placeholder instead of
+/// their app.
+const MIN_PANE_SIZE: Size = Size { cols: 10, rows: 3 };
+
+/// An app in a pane, & which app it is.
+pub struct Pane {
+  pub route_id: RouteId,
+  pub route: Box<dyn Route>,
+}
+
+/// Async trait object that implements the [TWApp] trait. Lays out its `panes` (each of which is a
+/// totally separate app, w/ its own store) "tmux style", & passes the input events to the focused
+/// one. The commands to split, close, resize & focus the panes are typed after the prefix key.
+pub struct AppMultiplexer {
+  pub panes: HashMap<PaneId, Pane>,
+  pub next_pane_id: PaneId,
+  /// The size that each pane's app was last rendered at.
+  pub pane_app_sizes: HashMap<PaneId, Size>,
+  /// The next key is a command (instead of an input event for the focused pane).
+  pub is_prefix_pressed: bool,
+  pub pane_changed_sender: UnboundedSender<PaneId>,
+  /// Handed off to a task that dispatches the changes to the panes, the 1st time the app renders
+  /// (which is when the app is given the store).
+  pub maybe_pane_changed_receiver: Option<UnboundedReceiver<PaneId>>,
+  pub maybe_forward_pane_changes_task: Option<JoinHandle<()>>,
+  /// The file that the lolcat app (in any pane) shows.
+  pub maybe_lolcat_file_path: Option<String>,
+}
+
+#[async_trait]
+impl TWApp<MultiplexerState, MultiplexerAction> for AppMultiplexer {
+  async fn app_render(
+    &mut self, state: &MultiplexerState,
+    shared_store: &SharedStore<MultiplexerState, MultiplexerAction>, window_size: Size,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      self.forward_pane_changes_to_store_once(shared_store);
+
+      let mut surface = surface_start! {
+        stylesheet: Stylesheet::default(),
+        pos: (0, 0).into(),
+        size: (window_size.cols, window_size.rows.saturating_sub(1)).into(), // Leave row at bottom for message.
+      };
+
+      let mut pane_boxes = vec![];
+      layout_helpers::lay_out_panes(&mut surface, &state.layout, (100, 100), &mut pane_boxes)?;
+      surface.surface_end()?;
+
+      let mut queue = tw_command_queue!(TWCommand::ClearScreen, TWCommand::ResetColor);
+      queue += surface.render_buffer;
+      for (pane_id, pane_box) in pane_boxes {
+        self
+          .render_pane(&mut queue, state, pane_id, &pane_box, window_size)
+          .await?;
+      }
+
+      status_bar_helpers::render(&mut queue, window_size, self.is_prefix_pressed);
+
+      call_if_true!(is_logging_enabled(), {
+        log_no_err!(
+          INFO,
+          "🪟 AppMultiplexer::render -> size, state: {} {}",
+          window_size,
+          state
+        );
+      });
+      queue
+    });
+  }
+
+  async fn app_handle_event(
+    &mut self, input_event: &TWInputEvent, state: &MultiplexerState,
+    shared_store: &SharedStore<MultiplexerState, MultiplexerAction>, terminal_size: Size,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      if self.is_prefix_pressed {
+        self.is_prefix_pressed = false;
+        return self
+          .handle_command_key(input_event, state, shared_store)
+          .await;
+      }
+
+      if let TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: Some(ModifierKeys::CTRL),
+        non_modifier_key: NonModifierKey::Character('b'),
+      }) = input_event
+      {
+        self.is_prefix_pressed = true;
+        return Ok(EventPropagation::ConsumedRerender);
+      }
+
+      // Only the focused pane gets the input events.
+      match self.panes.get(&state.focused_pane_id) {
+        Some(pane) => {
+          let pane_app_size = self
+            .pane_app_sizes
+            .get(&state.focused_pane_id)
+            .copied()
+            .unwrap_or(terminal_size);
+          pane
+            .route
+            .route_handle_event(input_event, pane_app_size)
+            .await?
+        }
+        None => EventPropagation::Propagate,
+      }
+    });
+  }
+}
+
+impl AppMultiplexer {
+  /// Starts out w/ 1 pane, that has the app for the `route_id` in it.
+  pub async fn new(
+    route_id: RouteId, maybe_lolcat_file_path: Option<String>,
+  ) -> CommonResult<Self> {
+    throws_with_return!({
+      let (pane_changed_sender, pane_changed_receiver) = mpsc::unbounded_channel();
+      let mut app = Self {
+        panes: HashMap::new(),
+        next_pane_id: 0,
+        pane_app_sizes: HashMap::new(),
+        is_prefix_pressed: false,
+        pane_changed_sender,
+        maybe_pane_changed_receiver: Some(pane_changed_receiver),
+        maybe_forward_pane_changes_task: None,
+        maybe_lolcat_file_path,
+      };
+      app.create_pane(route_id).await?;
+      app
+    });
+  }
+
+  /// Returns the id of the new pane (which isn't in the layout until it is dispatched).
+  async fn create_pane(&mut self, route_id: RouteId) -> CommonResult<PaneId> {
+    throws_with_return!({
+      let pane_id = self.next_pane_id;
+      self.next_pane_id += 1;
+      self.replace_pane(pane_id, route_id).await?;
+      pane_id
+    });
+  }
+
+  /// Put a new instance of the app for the `route_id` (w/ a new store) in the pane.
+  async fn replace_pane(&mut self, pane_id: PaneId, route_id: RouteId) -> CommonResult<()> {
+    throws!({
+      let route = create_route(
+        route_id,
+        pane_id,
+        self.pane_changed_sender.clone(),
+        self.maybe_lolcat_file_path.as_deref(),
+      )
+      .await?;
+      self.panes.insert(pane_id, Pane { route_id, route });
+    });
+  }
+
+  /// The key that was typed after the prefix key. The prefix is consumed even if the key isn't a
+  /// command. The commands that dispatch an action re-render once the state changes, & the rest
+  /// re-render right away (which also hides the commands in the status bar).
+  async fn handle_command_key(
+    &mut self, input_event: &TWInputEvent, state: &MultiplexerState,
+    shared_store: &SharedStore<MultiplexerState, MultiplexerAction>,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      let mut event_consumed = false;
+      let focused_pane_id = state.focused_pane_id;
+
+      if let TWInputEvent::DisplayableKeypress(typed_char) = input_event {
+        match typed_char {
+          '%' | '"' => {
+            let direction = if *typed_char == '%' {
+              SplitDirection::LeftRight
+            } else {
+              SplitDirection::TopBottom
+            };
+            let route_id = self
+              .panes
+              .get(&focused_pane_id)
+              .map(|pane| pane.route_id)
+              .unwrap_or_default();
+            let new_pane_id = self.create_pane(route_id).await?;
+            spawn_and_consume_event!(
+              event_consumed,
+              shared_store,
+              MultiplexerAction::SplitFocusedPane(direction, new_pane_id)
+            );
+          }
+          // The last pane can't be closed.
+          'x' if state.layout.get_pane_ids().len() > 1 => {
+            self.panes.remove(&focused_pane_id);
+            self.pane_app_sizes.remove(&focused_pane_id);
+            spawn_and_consume_event!(
+              event_consumed,
+              shared_store,
+              MultiplexerAction::CloseFocusedPane
+            );
+          }
+          'o' => {
+            spawn_and_consume_event!(
+              event_consumed,
+              shared_store,
+              MultiplexerAction::FocusNextPane
+            )
+          }
+          '>' | '<' | '+' | '-' => {
+            let direction = match typed_char {
+              '>' | '<' => SplitDirection::LeftRight,
+              _ => SplitDirection::TopBottom,
+            };
+            let delta_percent = match typed_char {
+              '>' | '+' => RESIZE_STEP_PERCENT,
+              _ => -RESIZE_STEP_PERCENT,
+            };
+            spawn_and_consume_event!(
+              event_consumed,
+              shared_store,
+              MultiplexerAction::ResizeFocusedPane(direction, delta_percent)
+            );
+          }
+          '1'..='9' => {
+            let index = typed_char.to_digit(10).unwrap_or_default() as usize - 1;
+            if let Some(route_id) = RouteId::ALL.get(index) {
+              self.replace_pane(focused_pane_id, *route_id).await?;
+            }
+          }
+          _ => {}
+        }
+      }
+
+      if event_consumed {
+        EventPropagation::Consumed
+      } else {
+        EventPropagation::ConsumedRerender
+      }
+    });
+  }
+
+  /// The pane's title is in its top row, & its app is rendered below that. The right col of a pane
+  /// that has another pane to its right is a border.
+  async fn render_pane(
+    &mut self, queue: &mut TWCommandQueue, state: &MultiplexerState, pane_id: PaneId,
+    pane_box: &TWBox, window_size: Size,
+  ) -> CommonResult<()> {
+    throws!({
+      let origin_pos = pane_box.origin_pos;
+      let size = pane_box.bounding_size;
+      let has_border = origin_pos.col + size.cols < window_size.cols;
+      let app_origin_pos = position!(col: origin_pos.col, row: origin_pos.row + 1);
+      let app_size = size!(
+        col: size.cols.saturating_sub(if has_border { 1 } else { 0 }),
+        row: size.rows.saturating_sub(1)
+      );
+
+      let maybe_pane = self.panes.get(&pane_id);
+      let is_focused = pane_id == state.focused_pane_id;
+      render_helpers::render_title(queue, pane_box, pane_id, maybe_pane, is_focused);
+      if has_border {
+        render_helpers::render_border(queue, pane_box);
+      }
+
+      if app_size.cols < MIN_PANE_SIZE.cols || app_size.rows < MIN_PANE_SIZE.rows {
+        render_helpers::render_placeholder(queue, app_origin_pos, app_size);
+        return Ok(());
+      }
+
+      if let Some(pane) = maybe_pane {
+        let app_queue = pane.route.route_render(app_size).await?;
+        *queue += translate_to_pane(app_queue, app_origin_pos, app_size);
+        *queue += TWCommand::ResetColor;
+      }
+      self.pane_app_sizes.insert(pane_id, app_size);
+    });
+  }
+
+  fn forward_pane_changes_to_store_once(
+    &mut self, shared_store: &SharedStore<MultiplexerState, MultiplexerAction>,
+  ) {
+    if let Some(mut pane_changed_receiver) = self.maybe_pane_changed_receiver.take() {
+      let shared_store = shared_store.clone();
+      self.maybe_forward_pane_changes_task = Some(tokio::spawn(async move {
+        while let Some(pane_id) = pane_changed_receiver.recv().await {
+          shared_store
+            .write()
+            .await
+            .dispatch_action(MultiplexerAction::PaneChanged(pane_id))
+            .await;
+        }
+      }));
+    }
+  }
+}
+
+impl Drop for AppMultiplexer {
+  fn drop(&mut self) {
+    if let Some(task) = self.maybe_forward_pane_changes_task.take() {
+      task.abort();
+    }
+  }
+}
+
+mod layout_helpers {
+  use super::*;
+
+  /// Start a box for each split & pane in the `layout` (which takes up `req_size_percent` of its
+  /// parent box), & collect the boxes of the panes (in the order of the panes).
+  pub fn lay_out_panes(
+    surface: &mut Surface, layout: &PaneLayout, req_size_percent: (i32, i32),
+    pane_boxes: &mut Vec<(PaneId, TWBox)>,
+  ) -> CommonResult<()> {
+    throws!({
+      match layout {
+        PaneLayout::Pane(pane_id) => {
+          surface.box_start(box_props! {
+            id:   format!("pane_{}", pane_id),
+            dir:  Direction::Vertical,
+            size: req_size_percent.try_into()?,
+          })?;
+          pane_boxes.push((*pane_id, surface.current_box()?.clone()));
+          surface.box_end()?;
+        }
+        PaneLayout::Split {
+          direction,
+          first_percent,
+          first,
+          second,
+        } => {
+          let pane_ids: Vec<String> = layout
+            .get_pane_ids()
+            .iter()
+            .map(PaneId::to_string)
+            .collect();
+          surface.box_start(box_props! {
+            id:   format!("split_{}", pane_ids.join("_")),
+            dir:  direction.get_box_direction(),
+            size: req_size_percent.try_into()?,
+          })?;
+          let first_percent = *first_percent as i32;
+          let (first_size_percent, second_size_percent) = match direction {
+            SplitDirection::LeftRight => ((first_percent, 100), (100 - first_percent, 100)),
+            SplitDirection::TopBottom => ((100, first_percent), (100, 100 - first_percent)),
+          };
+          lay_out_panes(surface, first, first_size_percent, pane_boxes)?;
+          lay_out_panes(surface, second, second_size_percent, pane_boxes)?;
+          surface.box_end()?;
+        }
+      }
+    });
+  }
+}
+
+mod render_helpers {
+  use super::*;
+
+  /// The focused pane's title is highlighted.
+  pub fn render_title(
+    queue: &mut TWCommandQueue, pane_box: &TWBox, pane_id: PaneId, maybe_pane: Option<&Pane>,
+    is_focused: bool,
+  ) {
+    let title = match maybe_pane {
+      Some(pane) => format!(" {}: {} ", pane_id, pane.route_id.get_title()),
+      None => format!(" {} ", pane_id),
+    };
+    let style = if is_focused {
+      style!(attrib: [bold, reverse])
+    } else {
+      style!(attrib: [dim])
+    };
+    let title = clip_to_display_cols(&title, 0, pane_box.bounding_size.cols);
+
+    *queue += TWCommand::MoveCursorPositionAbs(pane_box.origin_pos);
+    *queue += styled_texts! { styled_text! { title, style } }.render();
+    *queue += TWCommand::ResetColor;
+  }
+
+  pub fn render_border(queue: &mut TWCommandQueue, pane_box: &TWBox) {
+    let origin_pos = pane_box.origin_pos;
+    let size = pane_box.bounding_size;
+    for row in 0..size.rows {
+      *queue += TWCommand::MoveCursorPositionAbs(
+        position!(col: origin_pos.col + size.cols - 1, row: origin_pos.row + row),
+      );
+      *queue += styled_texts! { styled_text! { "│", style!(attrib: [dim]) } }.render();
+    }
+  }
+
+  pub fn render_placeholder(queue: &mut TWCommandQueue, origin_pos: Position, size: Size) {
+    if size.cols > 0 && size.rows > 0 {
+      *queue += TWCommand::MoveCursorPositionAbs(origin_pos);
+      *queue += TWCommand::PrintWithAttributes("…".into(), None);
+    }
+  }
+}
+
+mod status_bar_helpers {
+  use super::*;
+
+  /// Shows the commands (once the prefix key is pressed), or how to get to them, at the bottom row
+  /// of the screen.
+  pub fn render(queue: &mut TWCommandQueue, size: Size, is_prefix_pressed: bool) {
+    let st_vec = if is_prefix_pressed {
+      styled_texts! {
+        styled_text! { " % / \" : split │ / ─ ", style!(attrib: [underline]) },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " x : close ",             style!(attrib: [underline]) },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " o : next ",              style!(attrib: [underline]) },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " < > + - : resize ",      style!(attrib: [underline]) },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " 1 - 4 : app ",           style!(attrib: [underline]) }
+      }
+    } else {
+      styled_texts! {
+        styled_text! { "Hints:",                  style!(attrib: [dim])       },
+        styled_text! { " Ctrl+q : Exit ⛔ ",      style!(attrib: [bold])      },
+        styled_text! { " … ",                     style!(attrib: [dim])       },
+        styled_text! { " Ctrl+b : pane commands ", style!(attrib: [underline]) }
+      }
+    };
+
+    let display_width = st_vec.unicode_string().display_width;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    let center: Position = (col_center, row_bottom).into();
+
+    *queue += TWCommand::MoveCursorPositionAbs(center);
+    *queue += st_vec.render();
+  }
+}
@@ -0,0 +1,123 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+
+/// How much a pane grows or shrinks each time that it is resized.
+pub const RESIZE_STEP_PERCENT: i16 = 5;
+
+// Create a new store and attach the reducer.
+pub async fn create_store() -> Store<MultiplexerState, MultiplexerAction> {
+  let mut store: Store<MultiplexerState, MultiplexerAction> = Store::default();
+  store.add_reducer(MultiplexerReducer::new()).await;
+  store
+}
+
+/// Action.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+#[allow(dead_code)]
+pub enum MultiplexerAction {
+  /// Split the focused pane, & focus the new pane (w/ the given id) in the 2nd half.
+  SplitFocusedPane(SplitDirection, PaneId),
+  CloseFocusedPane,
+  /// Grow the focused pane by this many percent (or shrink it, if that is negative).
+  ResizeFocusedPane(SplitDirection, i16),
+  FocusNextPane,
+  /// The state in the store of the pane changed.
+  PaneChanged(PaneId),
+  #[default]
+  Noop,
+}
+
+impl Display for MultiplexerAction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{:?}", self) }
+}
+
+/// State. Where the panes are, which one of them gets the input events, & how many times the state
+/// of any pane has changed (which is what makes the window re-render when it does).
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct MultiplexerState {
+  pub layout: PaneLayout,
+  pub focused_pane_id: PaneId,
+  pub pane_change_count: usize,
+}
+
+impl Display for MultiplexerState {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "State {{ panes: {:?}, focused_pane_id: {}, pane_change_count: {} }}",
+      self.layout.get_pane_ids(),
+      self.focused_pane_id,
+      self.pane_change_count
+    )
+  }
+}
+
+/// Reducer. When the focused pane is closed, the pane that took its place in the order of the panes
+/// is focused (or the last one, if it was the last).
+#[derive(Default)]
+pub struct MultiplexerReducer;
+
+#[async_trait]
+impl AsyncReducer<MultiplexerState, MultiplexerAction> for MultiplexerReducer {
+  async fn run(&self, action: &MultiplexerAction, state: &MultiplexerState) -> MultiplexerState {
+    let mut new_state = state.clone();
+
+    match action {
+      MultiplexerAction::SplitFocusedPane(direction, new_pane_id) => {
+        let is_split = new_state
+          .layout
+          .split(state.focused_pane_id, *direction, *new_pane_id);
+        if is_split {
+          new_state.focused_pane_id = *new_pane_id;
+        }
+      }
+      MultiplexerAction::CloseFocusedPane => {
+        let pane_ids = state.layout.get_pane_ids();
+        if new_state.layout.close(state.focused_pane_id) {
+          let index = pane_ids
+            .iter()
+            .position(|pane_id| *pane_id == state.focused_pane_id)
+            .unwrap_or_default();
+          let new_pane_ids = new_state.layout.get_pane_ids();
+          new_state.focused_pane_id = new_pane_ids[index.min(new_pane_ids.len() - 1)];
+        }
+      }
+      MultiplexerAction::ResizeFocusedPane(direction, delta_percent) => {
+        new_state
+          .layout
+          .resize(state.focused_pane_id, *direction, *delta_percent);
+      }
+      MultiplexerAction::FocusNextPane => {
+        new_state.focused_pane_id = state.layout.get_next_pane_id(state.focused_pane_id)
+      }
+      MultiplexerAction::PaneChanged(pane_id) if state.layout.contains(*pane_id) => {
+        new_state.pane_change_count += 1
+      }
+      _ => {}
+    }
+
+    new_state
+  }
+}
@@ -0,0 +1,47 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::sync::Arc;
+
+use r3bl_rs_utils::*;
+use tokio::sync::RwLock;
+
+use super::*;
+use crate::ex_router::RouteId;
+
+/// Run the example apps in split panes, starting w/ the editor in a single pane. The lolcat app
+/// shows the file at `maybe_lolcat_file_path` (if there is one).
+pub async fn run_app(maybe_lolcat_file_path: Option<String>) -> CommonResult<()> {
+  throws!({
+    // Create store.
+    let store = create_store().await;
+
+    // Create an App (renders & responds to user input).
+    let shared_app: SharedTWApp<MultiplexerState, MultiplexerAction> = Arc::new(RwLock::new(
+      AppMultiplexer::new(RouteId::Editor, maybe_lolcat_file_path).await?,
+    ));
+
+    // Exit if these keys are pressed. Typed chars (like 'x') are passed on to the focused pane.
+    let exit_keys: Vec<TWInputEvent> = vec![TWInputEvent::NonDisplayableKeypress(Keypress {
+      maybe_modifier_keys: Some(ModifierKeys::CTRL),
+      non_modifier_key: NonModifierKey::Character('q'),
+    })];
+
+    // Create a window.
+    TerminalWindow::main_event_loop(store, shared_app, exit_keys).await?
+  });
+}
@@ -0,0 +1,33 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Multiplexing apps in split panes, "tmux style". Each pane is a totally separate app (w/ its own
+//! store), & the panes are laid out w/ the [r3bl_rs_utils::Surface] boxes.
+
+// Attach sources.
+pub mod app_multiplexer;
+pub mod create_store;
+pub mod launcher;
+pub mod pane_commands;
+pub mod pane_layout;
+
+// Re-export.
+pub use app_multiplexer::*;
+pub use create_store::*;
+pub use launcher::*;
+pub use pane_commands::*;
+pub use pane_layout::*;
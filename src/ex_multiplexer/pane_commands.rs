@@ -0,0 +1,116 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils::*;
+
+use crate::common::*;
+
+/// Move the commands that an app rendered (as if it had the whole window to itself) into a pane at
+/// `origin_pos` that is `size` big. Clearing the screen only clears the pane, & the text (&
+/// cursor moves) that would end up outside of the pane are clipped.
+pub fn translate_to_pane(
+  queue: TWCommandQueue, origin_pos: Position, size: Size,
+) -> TWCommandQueue {
+  let mut pane_queue = tw_command_queue!();
+  // Where the cursor is in the pane, or None if it was moved outside of it.
+  let mut maybe_cursor_pos: Option<Position> = None;
+
+  for command in queue.queue {
+    match command {
+      TWCommand::ClearScreen => clear_pane(&mut pane_queue, origin_pos, size),
+      TWCommand::MoveCursorPositionAbs(pos) => {
+        maybe_cursor_pos = move_cursor(&mut pane_queue, origin_pos, size, pos)
+      }
+      TWCommand::MoveCursorPositionRelTo(box_origin_pos, pos) => {
+        let pos = position!(col: box_origin_pos.col + pos.col, row: box_origin_pos.row + pos.row);
+        maybe_cursor_pos = move_cursor(&mut pane_queue, origin_pos, size, pos)
+      }
+      TWCommand::PrintWithAttributes(text, maybe_style) => {
+        if let Some(cursor_pos) = maybe_cursor_pos.as_mut() {
+          let (clipped_text, width) = clip_ansi_text(&text, size.cols - cursor_pos.col);
+          cursor_pos.col += width;
+          pane_queue += TWCommand::PrintWithAttributes(clipped_text, maybe_style);
+        }
+      }
+      _ => pane_queue += command,
+    }
+  }
+
+  pane_queue
+}
+
+fn clear_pane(pane_queue: &mut TWCommandQueue, origin_pos: Position, size: Size) {
+  let blank_line = " ".repeat(size.cols as usize);
+  *pane_queue += TWCommand::ResetColor;
+  for row in 0..size.rows {
+    *pane_queue +=
+      TWCommand::MoveCursorPositionAbs(position!(col: origin_pos.col, row: origin_pos.row + row));
+    *pane_queue += TWCommand::PrintWithAttributes(blank_line.clone(), None);
+  }
+}
+
+/// Returns where the cursor is in the pane, or None if `pos` is outside of it (in which case the
+/// cursor isn't moved).
+fn move_cursor(
+  pane_queue: &mut TWCommandQueue, origin_pos: Position, size: Size, pos: Position,
+) -> Option<Position> {
+  if pos.col >= size.cols || pos.row >= size.rows {
+    return None;
+  }
+  *pane_queue += TWCommand::MoveCursorPositionAbs(
+    position!(col: origin_pos.col + pos.col, row: origin_pos.row + pos.row),
+  );
+  Some(pos)
+}
+
+/// Clip the `text` to `max_cols` display cols. The text can have ANSI escape sequences in it (eg:
+/// the colors that lolcat adds), which are kept since they take up no room on screen. Returns the
+/// clipped text & how many cols it takes up.
+fn clip_ansi_text(text: &str, max_cols: UnitType) -> (String, UnitType) {
+  let mut clipped_text = String::new();
+  let mut col: UnitType = 0;
+  let mut rest = text;
+
+  while !rest.is_empty() {
+    if let Some(escape_sequence_len) = get_escape_sequence_len(rest) {
+      clipped_text.push_str(&rest[..escape_sequence_len]);
+      rest = &rest[escape_sequence_len..];
+      continue;
+    }
+
+    // The text up to the next escape sequence (w/ at least 1 char, in case this is a lone escape).
+    let plain_text_len = rest
+      .char_indices()
+      .skip(1)
+      .find(|(_, ch)| *ch == '\x1b')
+      .map_or(rest.len(), |(index, _)| index);
+    let visible_text = clip_to_display_cols(&rest[..plain_text_len], 0, max_cols - col);
+    col += display_width(&visible_text);
+    clipped_text.push_str(&visible_text);
+    rest = &rest[plain_text_len..];
+  }
+
+  (clipped_text, col)
+}
+
+/// The length of the CSI escape sequence (eg: a color) at the start of the `text`, if it starts w/
+/// one.
+fn get_escape_sequence_len(text: &str) -> Option<usize> {
+  let params = text.strip_prefix("\x1b[")?;
+  let final_byte_index = params.find(|ch: char| ('@'..='~').contains(&ch))?;
+  Some("\x1b[".len() + final_byte_index + 1)
+}
@@ -0,0 +1,275 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils::*;
+
+pub type PaneId = usize;
+
+/// The smallest share (in percent) of a split that a pane can be resized to.
+pub const MIN_SPLIT_PERCENT: u16 = 10;
+
+/// How a pane is split in 2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SplitDirection {
+  /// The new pane is to the right of the pane that is split.
+  LeftRight,
+  /// The new pane is below the pane that is split.
+  TopBottom,
+}
+
+impl SplitDirection {
+  /// The direction of the box that holds both sides of the split.
+  pub fn get_box_direction(&self) -> Direction {
+    match self {
+      SplitDirection::LeftRight => Direction::Horizontal,
+      SplitDirection::TopBottom => Direction::Vertical,
+    }
+  }
+}
+
+/// Where the panes are on screen. Panes are split in 2 (over & over again), so the layout is a
+/// binary tree w/ the panes at its leaves.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PaneLayout {
+  Pane(PaneId),
+  /// `first_percent` of the width (or height) goes to the `first` side, & the rest to the `second`.
+  Split {
+    direction: SplitDirection,
+    first_percent: u16,
+    first: Box<PaneLayout>,
+    second: Box<PaneLayout>,
+  },
+}
+
+impl Default for PaneLayout {
+  fn default() -> Self { PaneLayout::Pane(0) }
+}
+
+impl PaneLayout {
+  /// The panes from left to right, & top to bottom.
+  pub fn get_pane_ids(&self) -> Vec<PaneId> {
+    match self {
+      PaneLayout::Pane(pane_id) => vec![*pane_id],
+      PaneLayout::Split { first, second, .. } => {
+        let mut pane_ids = first.get_pane_ids();
+        pane_ids.extend(second.get_pane_ids());
+        pane_ids
+      }
+    }
+  }
+
+  pub fn contains(&self, pane_id: PaneId) -> bool {
+    match self {
+      PaneLayout::Pane(it) => *it == pane_id,
+      PaneLayout::Split { first, second, .. } => {
+        first.contains(pane_id) || second.contains(pane_id)
+      }
+    }
+  }
+
+  /// The pane after the given one (wrapping around to the 1st one).
+  pub fn get_next_pane_id(&self, pane_id: PaneId) -> PaneId {
+    let pane_ids = self.get_pane_ids();
+    match pane_ids.iter().position(|it| *it == pane_id) {
+      Some(index) => pane_ids[(index + 1) % pane_ids.len()],
+      None => pane_ids[0],
+    }
+  }
+
+  /// Split the pane in half, & put the `new_pane_id` in the 2nd half. Returns false if there is no
+  /// such pane.
+  pub fn split(&mut self, pane_id: PaneId, direction: SplitDirection, new_pane_id: PaneId) -> bool {
+    match self {
+      PaneLayout::Pane(it) if *it == pane_id => {
+        *self = PaneLayout::Split {
+          direction,
+          first_percent: 50,
+          first: Box::new(PaneLayout::Pane(pane_id)),
+          second: Box::new(PaneLayout::Pane(new_pane_id)),
+        };
+        true
+      }
+      PaneLayout::Pane(_) => false,
+      PaneLayout::Split { first, second, .. } => {
+        first.split(pane_id, direction, new_pane_id)
+          || second.split(pane_id, direction, new_pane_id)
+      }
+    }
+  }
+
+  /// Remove the pane, & give its space to the other side of the split that it is in. Returns false
+  /// if there is no such pane, or if it is the only one.
+  pub fn close(&mut self, pane_id: PaneId) -> bool {
+    match self {
+      PaneLayout::Pane(_) => false,
+      PaneLayout::Split { first, second, .. } => {
+        if **first == PaneLayout::Pane(pane_id) {
+          *self = (**second).clone();
+          true
+        } else if **second == PaneLayout::Pane(pane_id) {
+          *self = (**first).clone();
+          true
+        } else {
+          first.close(pane_id) || second.close(pane_id)
+        }
+      }
+    }
+  }
+
+  /// Grow the pane by `delta_percent` (or shrink it, if that is negative) in the closest split that
+  /// it is in, that is in the given `direction`. Returns false if there is no such split.
+  pub fn resize(&mut self, pane_id: PaneId, direction: SplitDirection, delta_percent: i16) -> bool {
+    match self {
+      PaneLayout::Pane(_) => false,
+      PaneLayout::Split {
+        direction: split_direction,
+        first_percent,
+        first,
+        second,
+      } => {
+        if first.resize(pane_id, direction, delta_percent)
+          || second.resize(pane_id, direction, delta_percent)
+        {
+          return true;
+        }
+
+        let signed_delta_percent = if *split_direction != direction {
+          return false;
+        } else if first.contains(pane_id) {
+          delta_percent
+        } else if second.contains(pane_id) {
+          -delta_percent
+        } else {
+          return false;
+        };
+
+        let max_percent = 100 - MIN_SPLIT_PERCENT;
+        *first_percent = (*first_percent as i16 + signed_delta_percent)
+          .clamp(MIN_SPLIT_PERCENT as i16, max_percent as i16) as u16;
+        true
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// 0 on the left, & 1 above 2 on the right.
+  fn create_layout() -> PaneLayout {
+    let mut layout = PaneLayout::default();
+    assert!(layout.split(0, SplitDirection::LeftRight, 1));
+    assert!(layout.split(1, SplitDirection::TopBottom, 2));
+    layout
+  }
+
+  #[test]
+  fn test_split_puts_the_new_pane_after_the_split_one() {
+    let layout = create_layout();
+    assert_eq!(layout.get_pane_ids(), vec![0, 1, 2]);
+    assert_eq!(
+      layout,
+      PaneLayout::Split {
+        direction: SplitDirection::LeftRight,
+        first_percent: 50,
+        first: Box::new(PaneLayout::Pane(0)),
+        second: Box::new(PaneLayout::Split {
+          direction: SplitDirection::TopBottom,
+          first_percent: 50,
+          first: Box::new(PaneLayout::Pane(1)),
+          second: Box::new(PaneLayout::Pane(2)),
+        }),
+      }
+    );
+
+    let mut layout = layout;
+    assert!(!layout.split(3, SplitDirection::LeftRight, 4));
+    assert_eq!(layout.get_pane_ids(), vec![0, 1, 2]);
+  }
+
+  #[test]
+  fn test_next_pane_wraps_around() {
+    let layout = create_layout();
+    assert_eq!(layout.get_next_pane_id(0), 1);
+    assert_eq!(layout.get_next_pane_id(2), 0);
+    assert_eq!(layout.get_next_pane_id(3), 0);
+  }
+
+  #[test]
+  fn test_close_gives_the_space_to_the_other_side() {
+    let mut layout = create_layout();
+    assert!(layout.close(0));
+    assert_eq!(
+      layout,
+      PaneLayout::Split {
+        direction: SplitDirection::TopBottom,
+        first_percent: 50,
+        first: Box::new(PaneLayout::Pane(1)),
+        second: Box::new(PaneLayout::Pane(2)),
+      }
+    );
+
+    assert!(!layout.close(0));
+    assert!(layout.close(2));
+    assert_eq!(layout, PaneLayout::Pane(1));
+
+    // The last pane can't be closed.
+    assert!(!layout.close(1));
+  }
+
+  #[test]
+  fn test_resize_uses_the_closest_split_in_the_direction() {
+    let mut layout = create_layout();
+
+    // Pane 2 is in the 2nd half of both splits, so growing it shrinks the 1st halves.
+    assert!(layout.resize(2, SplitDirection::TopBottom, 10));
+    assert!(layout.resize(2, SplitDirection::LeftRight, 20));
+    let PaneLayout::Split {
+      first_percent,
+      second,
+      ..
+    } = &layout
+    else {
+      unreachable!()
+    };
+    assert_eq!(*first_percent, 30);
+    let PaneLayout::Split { first_percent, .. } = second.as_ref() else {
+      unreachable!()
+    };
+    assert_eq!(*first_percent, 40);
+
+    // Pane 0 isn't in a top / bottom split.
+    assert!(!layout.resize(0, SplitDirection::TopBottom, 10));
+  }
+
+  #[test]
+  fn test_resize_is_clamped() {
+    let mut layout = create_layout();
+    assert!(layout.resize(0, SplitDirection::LeftRight, 100));
+    let PaneLayout::Split { first_percent, .. } = &layout else {
+      unreachable!()
+    };
+    assert_eq!(*first_percent, 100 - MIN_SPLIT_PERCENT);
+
+    assert!(layout.resize(0, SplitDirection::LeftRight, -100));
+    let PaneLayout::Split { first_percent, .. } = &layout else {
+      unreachable!()
+    };
+    assert_eq!(*first_percent, MIN_SPLIT_PERCENT);
+  }
+}
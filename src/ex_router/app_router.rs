@@ -0,0 +1,185 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::{sync::mpsc::UnboundedReceiver, task::JoinHandle};
+
+use super::*;
+use crate::common::*;
+
+/// Async trait object that implements the [TWApp] trait. Shows one of its `routes` at a time (w/ a
+/// tab bar at the bottom row of the screen), & passes the input events that it doesn't handle on to
+/// that route.
+pub struct AppRouter {
+  pub routes: HashMap<RouteId, Box<dyn Route>>,
+  /// Handed off to a task that dispatches the changes to the routes, the 1st time the app renders
+  /// (which is when the app is given the store).
+  pub maybe_route_changed_receiver: Option<UnboundedReceiver<RouteId>>,
+  pub maybe_forward_route_changes_task: Option<JoinHandle<()>>,
+}
+
+#[async_trait]
+impl TWApp<RouterState, RouterAction> for AppRouter {
+  async fn app_render(
+    &mut self, state: &RouterState, shared_store: &SharedStore<RouterState, RouterAction>,
+    window_size: Size,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      self.forward_route_changes_to_store_once(shared_store);
+
+      let mut queue = match self.routes.get(&state.active_route_id) {
+        Some(route) => route.route_render(get_route_size(window_size)).await?,
+        None => tw_command_queue!(TWCommand::ClearScreen, TWCommand::ResetColor),
+      };
+      queue += TWCommand::ResetColor;
+      tab_bar_helpers::render(&mut queue, window_size, state);
+
+      call_if_true!(is_logging_enabled(), {
+        log_no_err!(
+          INFO,
+          "🔀 AppRouter::render -> size, state: {} {}",
+          window_size,
+          state
+        );
+      });
+      queue
+    });
+  }
+
+  async fn app_handle_event(
+    &mut self, input_event: &TWInputEvent, state: &RouterState,
+    shared_store: &SharedStore<RouterState, RouterAction>, terminal_size: Size,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      let mut event_consumed = false;
+
+      if let TWInputEvent::NonDisplayableKeypress(keypress) = input_event {
+        match keypress {
+          Keypress {
+            maybe_modifier_keys: None,
+            non_modifier_key: NonModifierKey::Function(function_key),
+          } => {
+            if let Some(route_id) = RouteId::from_function_key(*function_key) {
+              spawn_and_consume_event!(
+                event_consumed,
+                shared_store,
+                RouterAction::SwitchTo(route_id)
+              )
+            }
+          }
+          Keypress {
+            maybe_modifier_keys: Some(ModifierKeys::CTRL),
+            non_modifier_key: NonModifierKey::Character('n'),
+          } => spawn_and_consume_event!(event_consumed, shared_store, RouterAction::SwitchToNext),
+          Keypress {
+            maybe_modifier_keys: Some(ModifierKeys::CTRL),
+            non_modifier_key: NonModifierKey::Character('p'),
+          } => spawn_and_consume_event!(event_consumed, shared_store, RouterAction::SwitchToPrev),
+          _ => {}
+        }
+      }
+
+      if event_consumed {
+        return Ok(EventPropagation::Consumed);
+      }
+
+      match self.routes.get(&state.active_route_id) {
+        Some(route) => {
+          route
+            .route_handle_event(input_event, get_route_size(terminal_size))
+            .await?
+        }
+        None => EventPropagation::Propagate,
+      }
+    });
+  }
+}
+
+impl AppRouter {
+  pub fn new(
+    routes: HashMap<RouteId, Box<dyn Route>>, route_changed_receiver: UnboundedReceiver<RouteId>,
+  ) -> Self {
+    Self {
+      routes,
+      maybe_route_changed_receiver: Some(route_changed_receiver),
+      maybe_forward_route_changes_task: None,
+    }
+  }
+
+  fn forward_route_changes_to_store_once(
+    &mut self, shared_store: &SharedStore<RouterState, RouterAction>,
+  ) {
+    if let Some(mut route_changed_receiver) = self.maybe_route_changed_receiver.take() {
+      let shared_store = shared_store.clone();
+      self.maybe_forward_route_changes_task = Some(tokio::spawn(async move {
+        while let Some(route_id) = route_changed_receiver.recv().await {
+          shared_store
+            .write()
+            .await
+            .dispatch_action(RouterAction::RouteChanged(route_id))
+            .await;
+        }
+      }));
+    }
+  }
+}
+
+impl Drop for AppRouter {
+  fn drop(&mut self) {
+    if let Some(task) = self.maybe_forward_route_changes_task.take() {
+      task.abort();
+    }
+  }
+}
+
+/// The routes are rendered above the tab bar.
+fn get_route_size(window_size: Size) -> Size {
+  size!(col: window_size.cols, row: window_size.rows.saturating_sub(1))
+}
+
+mod tab_bar_helpers {
+  use super::*;
+
+  /// One tab per route at the bottom row of the screen (the one that is shown is highlighted), &
+  /// the keys to switch between them.
+  pub fn render(queue: &mut TWCommandQueue, size: Size, state: &RouterState) {
+    let mut st_vec = StyledTexts::default();
+    for route_id in RouteId::ALL {
+      let tab = format!(
+        " {:?} {} ",
+        route_id.get_function_key(),
+        route_id.get_title()
+      );
+      st_vec += if route_id == state.active_route_id {
+        styled_text! { tab, style!(attrib: [bold, reverse]) }
+      } else {
+        styled_text! { tab, style!(attrib: [dim]) }
+      };
+    }
+    st_vec += styled_text! { " … ",                           style!(attrib: [dim])       };
+    st_vec += styled_text! { " Ctrl+n / Ctrl+p : next / prev ", style!(attrib: [underline]) };
+    st_vec += styled_text! { " … ",                           style!(attrib: [dim])       };
+    st_vec += styled_text! { " Ctrl+q : Exit ⛔ ",             style!(attrib: [bold])      };
+
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    *queue += TWCommand::MoveCursorPositionAbs((0, row_bottom).into());
+    *queue += st_vec.render();
+  }
+}
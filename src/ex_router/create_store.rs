@@ -0,0 +1,92 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+
+// Create a new store and attach the reducer.
+pub async fn create_store() -> Store<RouterState, RouterAction> {
+  let mut store: Store<RouterState, RouterAction> = Store::default();
+  store.add_reducer(RouterReducer::new()).await;
+  store
+}
+
+/// Action.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+#[allow(dead_code)]
+pub enum RouterAction {
+  SwitchTo(RouteId),
+  SwitchToNext,
+  SwitchToPrev,
+  /// The state in the store of the route changed.
+  RouteChanged(RouteId),
+  #[default]
+  Noop,
+}
+
+impl Display for RouterAction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{:?}", self) }
+}
+
+/// State. Which route is shown, & how many times the state of that route has changed since it was
+/// switched to (which is what makes the window re-render when it does).
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct RouterState {
+  pub active_route_id: RouteId,
+  pub active_route_change_count: usize,
+}
+
+impl Display for RouterState {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "State {{ active_route_id: {}, active_route_change_count: {} }}",
+      self.active_route_id, self.active_route_change_count
+    )
+  }
+}
+
+/// Reducer. Changes to routes that aren't shown are ignored, they are rendered when they are
+/// switched to.
+#[derive(Default)]
+pub struct RouterReducer;
+
+#[async_trait]
+impl AsyncReducer<RouterState, RouterAction> for RouterReducer {
+  async fn run(&self, action: &RouterAction, state: &RouterState) -> RouterState {
+    let switch_to = |route_id: RouteId| RouterState {
+      active_route_id: route_id,
+      active_route_change_count: 0,
+    };
+
+    match action {
+      RouterAction::SwitchTo(route_id) => switch_to(*route_id),
+      RouterAction::SwitchToNext => switch_to(state.active_route_id.get_next()),
+      RouterAction::SwitchToPrev => switch_to(state.active_route_id.get_prev()),
+      RouterAction::RouteChanged(route_id) if *route_id == state.active_route_id => RouterState {
+        active_route_id: state.active_route_id,
+        active_route_change_count: state.active_route_change_count + 1,
+      },
+      _ => state.clone(),
+    }
+  }
+}
@@ -0,0 +1,145 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{collections::HashMap, io, sync::Arc};
+
+use r3bl_rs_utils::*;
+use tokio::sync::{mpsc::{self, UnboundedSender},
+                  RwLock};
+
+use super::*;
+use crate::{ex_app_no_layout::{self, AppNoLayout},
+            ex_app_with_layout::{self, AppWithLayout, AppWithLayoutAction, AppWithLayoutState},
+            ex_editor::{self, AppEditor},
+            ex_lolcat::{self, AppLolcat, LolcatAction}};
+
+/// Run all the example apps in one window. Each one has its own store, which is created up front
+/// & kept for as long as the router runs, so switching away from an app & back again doesn't lose
+/// its state. The lolcat route shows the file at `maybe_lolcat_file_path` (if there is one).
+pub async fn run_app(maybe_lolcat_file_path: Option<String>) -> CommonResult<()> {
+  throws!({
+    // Create store.
+    let store = create_store().await;
+
+    // Create the routes.
+    let (route_changed_sender, route_changed_receiver) = mpsc::unbounded_channel();
+    let routes = create_routes(route_changed_sender, maybe_lolcat_file_path).await?;
+
+    // Create an App (renders & responds to user input).
+    let shared_app: SharedTWApp<RouterState, RouterAction> =
+      Arc::new(RwLock::new(AppRouter::new(routes, route_changed_receiver)));
+
+    // Exit if these keys are pressed. Typed chars (like 'x') are passed on to the routes.
+    let exit_keys: Vec<TWInputEvent> = vec![TWInputEvent::NonDisplayableKeypress(Keypress {
+      maybe_modifier_keys: Some(ModifierKeys::CTRL),
+      non_modifier_key: NonModifierKey::Character('q'),
+    })];
+
+    // Create a window.
+    TerminalWindow::main_event_loop(store, shared_app, exit_keys).await?
+  });
+}
+
+async fn create_routes(
+  route_changed_sender: UnboundedSender<RouteId>, maybe_lolcat_file_path: Option<String>,
+) -> CommonResult<HashMap<RouteId, Box<dyn Route>>> {
+  throws_with_return!({
+    let mut routes: HashMap<RouteId, Box<dyn Route>> = HashMap::new();
+    for route_id in RouteId::ALL {
+      let route = create_route(
+        route_id,
+        route_id,
+        route_changed_sender.clone(),
+        maybe_lolcat_file_path.as_deref(),
+      )
+      .await?;
+      routes.insert(route_id, route);
+    }
+    routes
+  });
+}
+
+/// Create a new instance of the app for the `route_id`, w/ a new store. The `route_key` is sent to
+/// the `route_changed_sender` when the state in that store changes. The lolcat app shows the file
+/// at `maybe_lolcat_file_path` (if there is one).
+pub async fn create_route<K>(
+  route_id: RouteId, route_key: K, route_changed_sender: UnboundedSender<K>,
+  maybe_lolcat_file_path: Option<&str>,
+) -> CommonResult<Box<dyn Route>>
+where
+  K: Copy + Sync + Send + 'static,
+{
+  throws_with_return!({
+    let route: Box<dyn Route> = match route_id {
+      RouteId::NoLayout => {
+        let store = ex_app_no_layout::create_store().await;
+        let shared_app = AppNoLayout::new_shared();
+        Box::new(AppRoute::new(route_key, store, shared_app, route_changed_sender).await)
+      }
+      RouteId::WithLayout => {
+        let mut store: Store<AppWithLayoutState, AppWithLayoutAction> = Store::default();
+        store
+          .add_reducer(ex_app_with_layout::AppReducer::new())
+          .await;
+        let shared_app = AppWithLayout::new_shared();
+        Box::new(AppRoute::new(route_key, store, shared_app, route_changed_sender).await)
+      }
+      RouteId::Lolcat => {
+        let mut store = ex_lolcat::create_store().await;
+        store
+          .dispatch_action(LolcatAction::SetLines(
+            read_lolcat_lines(maybe_lolcat_file_path).await,
+          ))
+          .await;
+        let shared_app = AppLolcat::new_shared();
+        Box::new(AppRoute::new(route_key, store, shared_app, route_changed_sender).await)
+      }
+      RouteId::Editor => {
+        let store = ex_editor::create_store().await;
+        let shared_app = AppEditor::new_shared();
+        Box::new(AppRoute::new(route_key, store, shared_app, route_changed_sender).await)
+      }
+    };
+    route
+  });
+}
+
+/// If there is no file (or it can't be read), then the lolcat route says so, instead of keeping
+/// the app that it is in from starting.
+async fn read_lolcat_lines(maybe_file_path: Option<&str>) -> Vec<String> {
+  let file_path = match maybe_file_path {
+    Some(file_path) => file_path.to_string(),
+    None => {
+      return vec![format!(
+        "No file to show (pass one, eg: `{} router Cargo.toml`)",
+        crate::cli::BIN_NAME
+      )]
+    }
+  };
+
+  let read_lines_task = tokio::task::spawn_blocking({
+    let file_path = file_path.clone();
+    move || ex_lolcat::read_lines(&file_path)
+  });
+  match read_lines_task
+    .await
+    .unwrap_or_else(|error| Err(io::Error::from(error)))
+  {
+    Ok(lines) => lines,
+    Err(error) => vec![format!("Could not read {}: {}", file_path, error)],
+  }
+}
@@ -0,0 +1,33 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Routing between apps inside of a single [r3bl_rs_utils::TerminalWindow]. The function keys
+//! (F1 - F4) switch the route that is shown. The store of each route is created when the router
+//! starts, & kept alive in the background while another route is shown, so a route comes back the
+//! way it was left.
+
+// Attach sources.
+pub mod app_router;
+pub mod create_store;
+pub mod launcher;
+pub mod route;
+
+// Re-export.
+pub use app_router::*;
+pub use create_store::*;
+pub use launcher::*;
+pub use route::*;
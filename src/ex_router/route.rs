@@ -0,0 +1,182 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{fmt::{Display, Formatter},
+          sync::Arc};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::sync::{mpsc::UnboundedSender, RwLock};
+
+/// The apps that the router switches between. Each one is shown when its function key is pressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum RouteId {
+  #[default]
+  NoLayout,
+  WithLayout,
+  Lolcat,
+  Editor,
+}
+
+impl RouteId {
+  pub const ALL: [RouteId; 4] = [
+    RouteId::NoLayout,
+    RouteId::WithLayout,
+    RouteId::Lolcat,
+    RouteId::Editor,
+  ];
+
+  pub fn get_title(&self) -> &'static str {
+    match self {
+      RouteId::NoLayout => "No layout",
+      RouteId::WithLayout => "Layout",
+      RouteId::Lolcat => "lolcat",
+      RouteId::Editor => "Editor",
+    }
+  }
+
+  pub fn get_function_key(&self) -> FunctionKey {
+    match self {
+      RouteId::NoLayout => FunctionKey::F1,
+      RouteId::WithLayout => FunctionKey::F2,
+      RouteId::Lolcat => FunctionKey::F3,
+      RouteId::Editor => FunctionKey::F4,
+    }
+  }
+
+  pub fn from_function_key(function_key: FunctionKey) -> Option<RouteId> {
+    RouteId::ALL
+      .into_iter()
+      .find(|route_id| route_id.get_function_key() == function_key)
+  }
+
+  /// The route after this one, wrapping around to the 1st one.
+  pub fn get_next(&self) -> RouteId {
+    let index = RouteId::ALL
+      .iter()
+      .position(|it| it == self)
+      .unwrap_or_default();
+    RouteId::ALL[(index + 1) % RouteId::ALL.len()]
+  }
+
+  /// The route before this one, wrapping around to the last one.
+  pub fn get_prev(&self) -> RouteId {
+    let index = RouteId::ALL
+      .iter()
+      .position(|it| it == self)
+      .unwrap_or_default();
+    RouteId::ALL[(index + RouteId::ALL.len() - 1) % RouteId::ALL.len()]
+  }
+}
+
+impl Display for RouteId {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{:?}", self) }
+}
+
+/// An app that the router can show, type erased so that apps w/ different state & action types can
+/// be kept side by side. The route owns the store of its app, so the state of the app lives on
+/// while other routes are shown.
+#[async_trait]
+pub trait Route: Send + Sync {
+  async fn route_render(&self, window_size: Size) -> CommonResult<TWCommandQueue>;
+
+  async fn route_handle_event(
+    &self, input_event: &TWInputEvent, window_size: Size,
+  ) -> CommonResult<EventPropagation>;
+}
+
+/// A [Route] to a [TWApp], & the store that it renders.
+pub struct AppRoute<S, A> {
+  pub shared_store: SharedStore<S, A>,
+  pub shared_app: SharedTWApp<S, A>,
+}
+
+impl<S, A> AppRoute<S, A>
+where
+  S: Default + Display + Clone + PartialEq + std::fmt::Debug + Sync + Send + 'static,
+  A: Default + Display + Clone + Sync + Send + 'static,
+{
+  /// Every time that the state in the `store` changes, the `route_key` (eg: the [RouteId]) is sent
+  /// to the `route_changed_sender` (so that the app that shows this route can re-render).
+  pub async fn new<K>(
+    route_key: K, mut store: Store<S, A>, shared_app: SharedTWApp<S, A>,
+    route_changed_sender: UnboundedSender<K>,
+  ) -> Self
+  where
+    K: Copy + Sync + Send + 'static,
+  {
+    let subscriber = RouteChangedSubscriber {
+      route_key,
+      route_changed_sender,
+    };
+    store
+      .add_subscriber(Arc::new(RwLock::new(subscriber)))
+      .await;
+
+    Self {
+      shared_store: Arc::new(RwLock::new(store)),
+      shared_app,
+    }
+  }
+}
+
+#[async_trait]
+impl<S, A> Route for AppRoute<S, A>
+where
+  S: Default + Display + Clone + PartialEq + std::fmt::Debug + Sync + Send + 'static,
+  A: Default + Display + Clone + Sync + Send + 'static,
+{
+  async fn route_render(&self, window_size: Size) -> CommonResult<TWCommandQueue> {
+    let state = self.shared_store.read().await.get_state();
+    self
+      .shared_app
+      .write()
+      .await
+      .app_render(&state, &self.shared_store, window_size)
+      .await
+  }
+
+  async fn route_handle_event(
+    &self, input_event: &TWInputEvent, window_size: Size,
+  ) -> CommonResult<EventPropagation> {
+    let state = self.shared_store.read().await.get_state();
+    self
+      .shared_app
+      .write()
+      .await
+      .app_handle_event(input_event, &state, &self.shared_store, window_size)
+      .await
+  }
+}
+
+/// Subscribes to the store of a route, to let the app that shows it know when its state changes.
+pub struct RouteChangedSubscriber<K> {
+  pub route_key: K,
+  pub route_changed_sender: UnboundedSender<K>,
+}
+
+#[async_trait]
+impl<S, K> AsyncSubscriber<S> for RouteChangedSubscriber<K>
+where
+  S: Sync + Send + 'static,
+  K: Copy + Sync + Send + 'static,
+{
+  async fn run(&self, _state: S) {
+    // The app is gone if this fails, & there is no one left to tell.
+    self.route_changed_sender.send(self.route_key).ok();
+  }
+}
@@ -0,0 +1,39 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils::*;
+
+use crate::common::*;
+
+/// Print the `text` at the start of the `row` of the box (clipped to the width of the box), if the
+/// box is tall enough to have that row.
+pub fn render_row(
+  queue: &mut TWCommandQueue, current_box: &TWBox, row: UnitType, text: &str, style: Style,
+) {
+  if row >= current_box.bounding_size.rows {
+    return;
+  }
+  let visible_text = clip_to_display_cols(text, 0, current_box.bounding_size.cols);
+
+  tw_command_queue! {
+    queue push
+    TWCommand::MoveCursorPositionRelTo(current_box.origin_pos, position!(col: 0, row: row)),
+    TWCommand::ApplyColors(Some(style.clone())),
+    TWCommand::PrintWithAttributes(visible_text, Some(style)),
+    TWCommand::ResetColor
+  };
+}
@@ -0,0 +1,234 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Debug, Display};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+use crate::common::*;
+
+/// A list of items that can be filtered (by typing), & that one or more of them can be chosen from.
+/// When the choice is confirmed, the action made by `on_confirm` is dispatched, so that any app can
+/// embed this component (in a box of its own).
+///
+/// The [ChooserState] is kept in the component, since the state of the app that embeds it isn't
+/// known. The items are taken from the app's state (using `get_items`) when it changes.
+pub struct ChooserComponent<S, A> {
+  pub chooser_state: ChooserState,
+  pub get_items: fn(&S) -> Vec<ChooserItem>,
+  pub on_confirm: fn(Vec<ChosenItem>) -> A,
+}
+
+impl<S, A> ChooserComponent<S, A> {
+  pub fn new(
+    mode: ChooserMode, get_items: fn(&S) -> Vec<ChooserItem>, on_confirm: fn(Vec<ChosenItem>) -> A,
+  ) -> Self {
+    Self {
+      chooser_state: ChooserState::new(mode),
+      get_items,
+      on_confirm,
+    }
+  }
+}
+
+#[async_trait]
+impl<S, A> Component<S, A> for ChooserComponent<S, A>
+where
+  S: Default + Display + Clone + PartialEq + Debug + Sync + Send + 'static,
+  A: Default + Display + Clone + Sync + Send + 'static,
+{
+  /// Handle following input events (and consume them):
+  /// - Up, Down  : move the cursor
+  /// - Tab       : check / uncheck the item under the cursor (in [ChooserMode::MultiSelect])
+  /// - Enter     : confirm the chosen items
+  /// - Backspace : delete the last char of the filter
+  /// - Esc       : clear the filter (if there is one)
+  /// - Any typed char (including a space) is added to the filter.
+  async fn handle_event(
+    &mut self, input_event: &TWInputEvent, state: &S, shared_store: &SharedStore<S, A>,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      self.chooser_state.set_items((self.get_items)(state));
+
+      let maybe_chooser_event = match input_event {
+        TWInputEvent::DisplayableKeypress(typed_char) => {
+          Some(ChooserEvent::InsertFilterChar(*typed_char))
+        }
+        TWInputEvent::NonDisplayableKeypress(Keypress {
+          maybe_modifier_keys: None,
+          non_modifier_key: NonModifierKey::Special(special_key),
+        }) => match special_key {
+          SpecialKey::Up => Some(ChooserEvent::MoveUp),
+          SpecialKey::Down => Some(ChooserEvent::MoveDown),
+          SpecialKey::Tab if self.chooser_state.mode == ChooserMode::MultiSelect => {
+            Some(ChooserEvent::Toggle)
+          }
+          SpecialKey::Backspace => Some(ChooserEvent::DeleteFilterChar),
+          SpecialKey::Esc if !self.chooser_state.filter.is_empty() => {
+            Some(ChooserEvent::ClearFilter)
+          }
+          SpecialKey::Enter => return Ok(self.confirm(shared_store)),
+          _ => None,
+        },
+        _ => None,
+      };
+
+      match maybe_chooser_event {
+        Some(chooser_event) => {
+          if self.chooser_state.apply_event(&chooser_event) {
+            EventPropagation::ConsumedRerender
+          } else {
+            EventPropagation::Consumed
+          }
+        }
+        None => EventPropagation::Propagate,
+      }
+    });
+  }
+
+  async fn render(
+    &mut self, has_focus: &HasFocus, current_box: &TWBox, state: &S,
+    _shared_store: &SharedStore<S, A>,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      self.chooser_state.set_items((self.get_items)(state));
+
+      let is_focused = has_focus.does_current_box_have_focus(current_box);
+      let mut queue = tw_command_queue!();
+      render_helpers::render_filter(&mut queue, current_box, &self.chooser_state, is_focused);
+
+      // The rows after the filter.
+      let visible_row_count = (current_box.bounding_size.rows as usize).saturating_sub(1);
+      self.chooser_state.scroll_to_cursor(visible_row_count);
+      render_helpers::render_items(
+        &mut queue,
+        current_box,
+        &self.chooser_state,
+        visible_row_count,
+        is_focused,
+      );
+
+      call_if_true!(is_logging_enabled(), {
+        log_no_err!(
+          INFO,
+          "☑ ChooserComponent::render -> box_origin_pos: {:?}, box_bounding_size: {:?}",
+          current_box.origin_pos,
+          current_box.bounding_size
+        );
+      });
+      queue
+    });
+  }
+}
+
+impl<S, A> ChooserComponent<S, A>
+where
+  S: Default + Display + Clone + PartialEq + Debug + Sync + Send + 'static,
+  A: Default + Display + Clone + Sync + Send + 'static,
+{
+  /// Dispatch the action for the chosen items (if there are any).
+  fn confirm(&self, shared_store: &SharedStore<S, A>) -> EventPropagation {
+    let mut event_consumed = false;
+
+    let chosen_items = self.chooser_state.get_chosen_items();
+    if !chosen_items.is_empty() {
+      spawn_and_consume_event!(
+        event_consumed,
+        shared_store,
+        (self.on_confirm)(chosen_items)
+      );
+    }
+
+    if event_consumed {
+      EventPropagation::Consumed
+    } else {
+      EventPropagation::Propagate
+    }
+  }
+}
+
+mod render_helpers {
+  use super::*;
+
+  /// The filter (or a hint to type one), & how many of the items contain it.
+  pub fn render_filter(
+    queue: &mut TWCommandQueue, current_box: &TWBox, chooser_state: &ChooserState, is_focused: bool,
+  ) {
+    let caret = if is_focused { "▏" } else { "" };
+    let filtered_item_count = chooser_state.get_filtered_indices().len();
+    let text = if chooser_state.filter.is_empty() {
+      format!("❯ {}type to filter", caret)
+    } else {
+      format!(
+        "❯ {}{} ({}/{})",
+        chooser_state.filter,
+        caret,
+        filtered_item_count,
+        chooser_state.items.len()
+      )
+    };
+    render_row(queue, current_box, 0, &text, style!(attrib: [dim]));
+  }
+
+  /// The filtered items, starting from the scroll offset. In [ChooserMode::MultiSelect], each one
+  /// has a check box.
+  pub fn render_items(
+    queue: &mut TWCommandQueue, current_box: &TWBox, chooser_state: &ChooserState,
+    visible_row_count: usize, is_focused: bool,
+  ) {
+    let filtered_indices = chooser_state.get_filtered_indices();
+    if filtered_indices.is_empty() {
+      let hint = if chooser_state.items.is_empty() {
+        "Nothing to choose from"
+      } else {
+        "No matches"
+      };
+      render_row(queue, current_box, 1, hint, style!(attrib: [dim]));
+      return;
+    }
+
+    for (row_index, (filtered_index, index)) in filtered_indices
+      .iter()
+      .enumerate()
+      .skip(chooser_state.scroll_offset)
+      .take(visible_row_count)
+      .enumerate()
+    {
+      let is_under_cursor = filtered_index == chooser_state.cursor_index;
+      let check_box = match chooser_state.mode {
+        ChooserMode::MultiSelect if chooser_state.is_checked(*index) => "◉ ",
+        ChooserMode::MultiSelect => "○ ",
+        ChooserMode::SingleSelect => "",
+      };
+      let text = format!(
+        "{} {}{}",
+        if is_under_cursor { "❯" } else { " " },
+        check_box,
+        chooser_state.items[*index].text
+      );
+      let style = match (is_under_cursor, is_focused) {
+        (true, true) => style!(attrib: [bold, reverse]),
+        (true, false) => style!(attrib: [bold]),
+        _ => Style::default(),
+      };
+      let row = 1 + row_index as UnitType;
+      render_row(queue, current_box, row, &text, style);
+    }
+  }
+}
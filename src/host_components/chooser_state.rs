@@ -0,0 +1,195 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::collections::BTreeSet;
+
+/// Whether any number of items can be chosen, or exactly one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ChooserMode {
+  #[default]
+  MultiSelect,
+  SingleSelect,
+}
+
+/// An item in a chooser. Its `id` stays the same when the items are replaced (eg: when they are
+/// taken from the app's state again), even if its index (or its text) changes, & no two items have
+/// the same one.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ChooserItem {
+  pub id: String,
+  pub text: String,
+}
+
+/// An item that was chosen, & its index in the items that were given to the chooser.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ChosenItem {
+  pub index: usize,
+  pub id: String,
+  pub text: String,
+}
+
+/// What the user did in a chooser.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChooserEvent {
+  MoveUp,
+  MoveDown,
+  /// Check or uncheck the item under the cursor (only in [ChooserMode::MultiSelect]).
+  Toggle,
+  InsertFilterChar(char),
+  DeleteFilterChar,
+  ClearFilter,
+}
+
+/// The items in a chooser, which of them are checked, & the filter that was typed. Only the items
+/// that contain the filter (ignoring case) are shown, & the cursor moves over those.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ChooserState {
+  pub mode: ChooserMode,
+  pub items: Vec<ChooserItem>,
+  /// Ids of the checked items.
+  pub checked_ids: BTreeSet<String>,
+  pub filter: String,
+  /// Index into the filtered items.
+  pub cursor_index: usize,
+  /// Index (into the filtered items) of the 1st item that is shown.
+  pub scroll_offset: usize,
+}
+
+impl ChooserState {
+  pub fn new(mode: ChooserMode) -> Self {
+    Self {
+      mode,
+      ..Default::default()
+    }
+  }
+
+  /// Replace the items. The checked items, & the one under the cursor, stay that way if they are
+  /// still there (the items are matched by their id).
+  pub fn set_items(&mut self, items: Vec<ChooserItem>) {
+    if self.items == items {
+      return;
+    }
+
+    let maybe_cursor_id = self.get_cursor_item().map(|it| it.id);
+    self
+      .checked_ids
+      .retain(|checked_id| items.iter().any(|item| item.id == *checked_id));
+
+    self.items = items;
+    let filtered_indices = self.get_filtered_indices();
+    self.cursor_index = filtered_indices
+      .iter()
+      .position(|index| Some(&self.items[*index].id) == maybe_cursor_id.as_ref())
+      .unwrap_or_else(|| {
+        self
+          .cursor_index
+          .min(filtered_indices.len().saturating_sub(1))
+      });
+  }
+
+  /// Indices (into `items`) of the items that contain the filter.
+  pub fn get_filtered_indices(&self) -> Vec<usize> {
+    let filter = self.filter.to_lowercase();
+    self
+      .items
+      .iter()
+      .enumerate()
+      .filter(|(_, item)| item.text.to_lowercase().contains(&filter))
+      .map(|(index, _)| index)
+      .collect()
+  }
+
+  pub fn get_cursor_item(&self) -> Option<ChosenItem> {
+    let index = *self.get_filtered_indices().get(self.cursor_index)?;
+    Some(self.get_chosen_item(index))
+  }
+
+  pub fn is_checked(&self, index: usize) -> bool {
+    self.checked_ids.contains(&self.items[index].id)
+  }
+
+  fn get_chosen_item(&self, index: usize) -> ChosenItem {
+    ChosenItem {
+      index,
+      id: self.items[index].id.clone(),
+      text: self.items[index].text.clone(),
+    }
+  }
+
+  /// The items that are confirmed when Enter is pressed. These are the checked ones, or the one
+  /// under the cursor if none are checked (or in [ChooserMode::SingleSelect]).
+  pub fn get_chosen_items(&self) -> Vec<ChosenItem> {
+    if self.mode == ChooserMode::MultiSelect && !self.checked_ids.is_empty() {
+      return (0..self.items.len())
+        .filter(|index| self.is_checked(*index))
+        .map(|index| self.get_chosen_item(index))
+        .collect();
+    }
+    self.get_cursor_item().into_iter().collect()
+  }
+
+  /// Returns true if the state changed.
+  pub fn apply_event(&mut self, event: &ChooserEvent) -> bool {
+    let old_state = self.clone();
+
+    match event {
+      ChooserEvent::MoveUp => self.cursor_index = self.cursor_index.saturating_sub(1),
+      ChooserEvent::MoveDown => {
+        let filtered_item_count = self.get_filtered_indices().len();
+        self.cursor_index = (self.cursor_index + 1).min(filtered_item_count.saturating_sub(1));
+      }
+      ChooserEvent::Toggle => {
+        if self.mode == ChooserMode::MultiSelect {
+          if let Some(item) = self.get_cursor_item() {
+            if !self.checked_ids.remove(&item.id) {
+              self.checked_ids.insert(item.id);
+            }
+          }
+        }
+      }
+      ChooserEvent::InsertFilterChar(ch) => self.set_filter(format!("{}{}", self.filter, ch)),
+      ChooserEvent::DeleteFilterChar => {
+        let mut filter = self.filter.clone();
+        filter.pop();
+        self.set_filter(filter);
+      }
+      ChooserEvent::ClearFilter => self.set_filter(String::new()),
+    }
+
+    *self != old_state
+  }
+
+  /// The cursor goes back to the 1st item that is shown, since the one that it was on might be
+  /// filtered out.
+  fn set_filter(&mut self, filter: String) {
+    if self.filter != filter {
+      self.filter = filter;
+      self.cursor_index = 0;
+      self.scroll_offset = 0;
+    }
+  }
+
+  /// Scroll so that the cursor is on one of the `visible_row_count` rows that are shown.
+  pub fn scroll_to_cursor(&mut self, visible_row_count: usize) {
+    let visible_row_count = visible_row_count.max(1);
+    if self.cursor_index < self.scroll_offset {
+      self.scroll_offset = self.cursor_index;
+    } else if self.cursor_index >= self.scroll_offset + visible_row_count {
+      self.scroll_offset = self.cursor_index + 1 - visible_row_count;
+    }
+  }
+}
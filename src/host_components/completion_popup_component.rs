@@ -0,0 +1,484 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{fmt::{Debug, Display, Formatter},
+          sync::{Arc, Mutex}};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::task::JoinHandle;
+
+use super::*;
+use crate::common::*;
+
+/// The popup only opens once this many chars of a word have been typed.
+pub const MIN_COMPLETION_PREFIX_LEN: usize = 2;
+/// The popup scrolls if there are more candidates than this.
+pub const MAX_COMPLETION_POPUP_ROWS: usize = 6;
+
+/// What is being completed in the text input that a [CompletionPopupComponent] is attached to.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct CompletionContext {
+  /// The part of the word that has been typed before the caret.
+  pub prefix: String,
+  /// All of the text in the input, which a [CompletionSource] may take candidates from. It is only
+  /// filled in (see [GetCompletionTextFn]) when the source is queried.
+  pub text: String,
+  /// Position of the caret, relative to the origin of the box that the input is rendered in.
+  pub caret_pos: Position,
+}
+
+/// A candidate that was accepted, for the prefix that it completes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Completion {
+  pub prefix: String,
+  pub candidate: String,
+}
+
+impl Completion {
+  /// What has to be inserted at the caret (after the prefix) to complete the word.
+  pub fn get_text_to_insert(&self) -> &str {
+    self
+      .candidate
+      .strip_prefix(&self.prefix)
+      .unwrap_or_default()
+  }
+}
+
+/// Gets the [CompletionContext] (w/out the text) from the state, for the input w/ the given id that
+/// is rendered in a box w/ the given [Size]. It returns [None] if nothing can be completed (eg: the
+/// caret isn't at the end of a word). This is called every time that the popup is rendered.
+pub type GetCompletionContextFn<S> = fn(&str, &S, Size) -> Option<CompletionContext>;
+
+/// Gets the text of the input w/ the given id from the state. This is only called when the source
+/// is queried (when the prefix changes), since the text can be big (eg: a whole buffer).
+pub type GetCompletionTextFn<S> = fn(&str, &S) -> String;
+
+/// Makes the action that inserts the [Completion] into the input w/ the given id, which is rendered
+/// in a box w/ the given [Size].
+pub type AcceptCompletionFn<A> = fn(&str, Size, Completion) -> A;
+
+/// The candidates that a query (which runs in its own task) got for a prefix.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct CompletionQueryResult {
+  pub prefix: String,
+  pub candidates: Vec<String>,
+}
+
+/// The candidates that are shown in the popup, & which one of them is selected.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct CompletionPopupState {
+  /// Set when a char is typed (or deleted) in the input, & cleared by any other input event. The
+  /// popup is only shown while typing, so that moving the caret around doesn't open it.
+  pub is_armed: bool,
+  /// The prefix that the candidates are for.
+  pub maybe_queried_prefix: Option<String>,
+  pub candidates: Vec<String>,
+  pub selected_index: usize,
+}
+
+impl CompletionPopupState {
+  pub fn is_open(&self) -> bool { self.is_armed && !self.candidates.is_empty() }
+
+  pub fn close(&mut self) {
+    self.is_armed = false;
+    self.maybe_queried_prefix = None;
+    self.candidates.clear();
+    self.selected_index = 0;
+  }
+
+  pub fn select_prev(&mut self) { self.selected_index = self.selected_index.saturating_sub(1); }
+
+  pub fn select_next(&mut self) {
+    self.selected_index = (self.selected_index + 1).min(self.candidates.len().saturating_sub(1));
+  }
+
+  pub fn get_selected_candidate(&self) -> Option<&String> {
+    self.candidates.get(self.selected_index)
+  }
+
+  /// Index of the 1st candidate that is shown, so that the selected one is visible.
+  pub fn get_scroll_offset(&self) -> usize {
+    (self.selected_index + 1).saturating_sub(MAX_COMPLETION_POPUP_ROWS)
+  }
+}
+
+/// A popup that lists the candidates (from a [CompletionSource]) that complete the word that is
+/// being typed in a text input, anchored at the caret. It is attached to an input by the component
+/// that renders that input: that component passes each input event to the popup 1st (which
+/// consumes the ones that it uses while it is open), & renders the popup in its own box after the
+/// input. The accepted candidate is inserted by dispatching the action made by `on_accept`.
+///
+/// The source is queried in a separate task, so that a slow source doesn't hold up rendering. When
+/// the query is done, the `repaint_action` (which must not change the state) is dispatched, so that
+/// the popup is rendered again w/ the candidates.
+pub struct CompletionPopupComponent<S, A> {
+  /// Id of the input (eg: of the box that an editor component is rendered in).
+  pub host_id: String,
+  pub source: SharedCompletionSource,
+  pub get_context: GetCompletionContextFn<S>,
+  pub get_text: GetCompletionTextFn<S>,
+  pub on_accept: AcceptCompletionFn<A>,
+  pub repaint_action: A,
+  pub popup_state: CompletionPopupState,
+  /// Bounding size of the box that the input was last rendered in.
+  pub box_size: Size,
+  /// The query that is running (if any). It is aborted when a new one is started.
+  pub maybe_query_task: Option<JoinHandle<()>>,
+  /// Where the running query puts its result, for the next render to pick up.
+  pub maybe_query_result: Arc<Mutex<Option<CompletionQueryResult>>>,
+}
+
+impl<S, A> CompletionPopupComponent<S, A> {
+  pub fn new(
+    host_id: &str, source: SharedCompletionSource, get_context: GetCompletionContextFn<S>,
+    get_text: GetCompletionTextFn<S>, on_accept: AcceptCompletionFn<A>, repaint_action: A,
+  ) -> Self {
+    Self {
+      host_id: host_id.to_string(),
+      source,
+      get_context,
+      get_text,
+      on_accept,
+      repaint_action,
+      popup_state: CompletionPopupState::default(),
+      box_size: Size::default(),
+      maybe_query_task: None,
+      maybe_query_result: Arc::default(),
+    }
+  }
+}
+
+impl<S, A: Clone> Clone for CompletionPopupComponent<S, A> {
+  fn clone(&self) -> Self {
+    Self {
+      host_id: self.host_id.clone(),
+      source: self.source.clone(),
+      get_context: self.get_context,
+      get_text: self.get_text,
+      on_accept: self.on_accept,
+      repaint_action: self.repaint_action.clone(),
+      popup_state: self.popup_state.clone(),
+      box_size: self.box_size,
+      maybe_query_task: None,
+      maybe_query_result: Arc::default(),
+    }
+  }
+}
+
+impl<S, A> Debug for CompletionPopupComponent<S, A> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("CompletionPopupComponent")
+      .field("host_id", &self.host_id)
+      .field("popup_state", &self.popup_state)
+      .field("box_size", &self.box_size)
+      .field("is_querying", &self.maybe_query_task.is_some())
+      .finish()
+  }
+}
+
+#[async_trait]
+impl<S, A> Component<S, A> for CompletionPopupComponent<S, A>
+where
+  S: Default + Display + Clone + PartialEq + Debug + Sync + Send + 'static,
+  A: Default + Display + Clone + Sync + Send + 'static,
+{
+  /// Handle following input events (and consume them) while the popup is open:
+  /// - Up, Down   : select the previous / next candidate
+  /// - Enter, Tab : insert the selected candidate
+  /// - Esc        : close the popup
+  ///
+  /// All other input events are propagated (to the input that the popup is attached to).
+  async fn handle_event(
+    &mut self, input_event: &TWInputEvent, state: &S, shared_store: &SharedStore<S, A>,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      if self.popup_state.is_open() {
+        if let TWInputEvent::NonDisplayableKeypress(Keypress {
+          maybe_modifier_keys: None,
+          non_modifier_key: NonModifierKey::Special(special_key),
+        }) = input_event
+        {
+          match special_key {
+            SpecialKey::Up => {
+              self.popup_state.select_prev();
+              return Ok(EventPropagation::ConsumedRerender);
+            }
+            SpecialKey::Down => {
+              self.popup_state.select_next();
+              return Ok(EventPropagation::ConsumedRerender);
+            }
+            SpecialKey::Enter | SpecialKey::Tab => return Ok(self.accept(state, shared_store)),
+            SpecialKey::Esc => {
+              self.close();
+              return Ok(EventPropagation::ConsumedRerender);
+            }
+            _ => {}
+          }
+        }
+      }
+
+      self.popup_state.is_armed = matches!(
+        input_event,
+        TWInputEvent::DisplayableKeypress(_)
+          | TWInputEvent::NonDisplayableKeypress(Keypress {
+            maybe_modifier_keys: None,
+            non_modifier_key: NonModifierKey::Special(SpecialKey::Backspace),
+          })
+      );
+      if !self.popup_state.is_armed {
+        self.close();
+      }
+
+      EventPropagation::Propagate
+    });
+  }
+
+  /// The candidates are fetched (from the source) when the prefix changes while the popup is armed.
+  /// Until they arrive, the candidates for the previous prefix that still match are shown. Nothing
+  /// is painted if the box doesn't have focus.
+  async fn render(
+    &mut self, has_focus: &HasFocus, current_box: &TWBox, state: &S,
+    shared_store: &SharedStore<S, A>,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      self.box_size = current_box.bounding_size;
+      let mut queue = tw_command_queue!();
+
+      if !self.popup_state.is_armed || !has_focus.does_current_box_have_focus(current_box) {
+        return Ok(queue);
+      }
+
+      let context = match (self.get_context)(&self.host_id, state, self.box_size) {
+        Some(context) if context.prefix.chars().count() >= MIN_COMPLETION_PREFIX_LEN => context,
+        _ => {
+          self.close();
+          return Ok(queue);
+        }
+      };
+
+      if self.popup_state.maybe_queried_prefix.as_ref() != Some(&context.prefix) {
+        self.start_query(&context, state, shared_store);
+      }
+      self.take_query_result();
+
+      if self.popup_state.is_open() {
+        render_helpers::render_popup(&mut queue, current_box, &context, &self.popup_state);
+      }
+      queue
+    });
+  }
+}
+
+impl<S, A> CompletionPopupComponent<S, A>
+where
+  S: Default + Display + Clone + PartialEq + Debug + Sync + Send + 'static,
+  A: Default + Display + Clone + Sync + Send + 'static,
+{
+  /// Query the source for the candidates in a new task (aborting the one for the previous prefix,
+  /// if it is still running). If the source fails, then there is nothing to complete (rather than
+  /// stopping the app).
+  fn start_query(
+    &mut self, context: &CompletionContext, state: &S, shared_store: &SharedStore<S, A>,
+  ) {
+    if let Some(query_task) = self.maybe_query_task.take() {
+      query_task.abort();
+    }
+    self.popup_state.maybe_queried_prefix = Some(context.prefix.clone());
+    self
+      .popup_state
+      .candidates
+      .retain(|candidate| is_completion_for(candidate, &context.prefix));
+    self.popup_state.selected_index = 0;
+
+    let source = self.source.clone();
+    let context = CompletionContext {
+      text: (self.get_text)(&self.host_id, state),
+      ..context.clone()
+    };
+    let maybe_query_result = self.maybe_query_result.clone();
+    let shared_store = shared_store.clone();
+    let repaint_action = self.repaint_action.clone();
+    self.maybe_query_task = Some(tokio::spawn(async move {
+      let candidates = match source.get_candidates(&context).await {
+        Ok(candidates) => candidates,
+        Err(error) => {
+          call_if_true!(
+            is_logging_enabled(),
+            log_no_err!(ERROR, "💬 Could not get the completions: {}", error)
+          );
+          vec![]
+        }
+      };
+      let query_result = CompletionQueryResult {
+        candidates: candidates
+          .into_iter()
+          .filter(|candidate| is_completion_for(candidate, &context.prefix))
+          .collect(),
+        prefix: context.prefix,
+      };
+      if let Ok(mut maybe_query_result) = maybe_query_result.lock() {
+        *maybe_query_result = Some(query_result);
+      }
+      shared_store
+        .write()
+        .await
+        .dispatch_action(repaint_action)
+        .await;
+    }));
+  }
+
+  /// Show the candidates from the query, unless they are for a prefix that has changed since.
+  fn take_query_result(&mut self) {
+    let maybe_query_result = match self.maybe_query_result.lock() {
+      Ok(mut maybe_query_result) => maybe_query_result.take(),
+      Err(_) => None,
+    };
+    if let Some(query_result) = maybe_query_result {
+      if self.popup_state.maybe_queried_prefix.as_ref() == Some(&query_result.prefix) {
+        self.popup_state.candidates = query_result.candidates;
+        self.popup_state.selected_index = 0;
+        self.maybe_query_task = None;
+      }
+    }
+  }
+
+  fn close(&mut self) {
+    if let Some(query_task) = self.maybe_query_task.take() {
+      query_task.abort();
+    }
+    self.popup_state.close();
+  }
+
+  /// Dispatch the action that inserts the selected candidate, & close the popup.
+  fn accept(&mut self, state: &S, shared_store: &SharedStore<S, A>) -> EventPropagation {
+    let mut event_consumed = false;
+
+    let maybe_context = (self.get_context)(&self.host_id, state, self.box_size);
+    if let (Some(context), Some(candidate)) =
+      (maybe_context, self.popup_state.get_selected_candidate())
+    {
+      let completion = Completion {
+        prefix: context.prefix,
+        candidate: candidate.clone(),
+      };
+      spawn_and_consume_event!(
+        event_consumed,
+        shared_store,
+        (self.on_accept)(&self.host_id, self.box_size, completion)
+      );
+    }
+    self.close();
+
+    if event_consumed {
+      EventPropagation::Consumed
+    } else {
+      EventPropagation::ConsumedRerender
+    }
+  }
+}
+
+/// A candidate is only shown if it is longer than the prefix that it starts w/.
+fn is_completion_for(candidate: &str, prefix: &str) -> bool {
+  candidate.starts_with(prefix) && candidate != prefix
+}
+
+mod render_helpers {
+  use super::*;
+
+  /// The popup is painted below the caret (or above it, if there isn't room below), lined up w/
+  /// the start of the word that is being completed, & moved left if it would stick out of the box.
+  pub fn render_popup(
+    queue: &mut TWCommandQueue, current_box: &TWBox, context: &CompletionContext,
+    popup_state: &CompletionPopupState,
+  ) {
+    let box_size = current_box.bounding_size;
+    let row_count = popup_state.candidates.len().min(MAX_COMPLETION_POPUP_ROWS) as UnitType;
+    let width = popup_state
+      .candidates
+      .iter()
+      .map(|candidate| display_width(candidate) + 2)
+      .max()
+      .unwrap_or_default()
+      .min(box_size.cols);
+
+    let caret_pos = context.caret_pos;
+    let first_row = if caret_pos.row + 1 + row_count <= box_size.rows {
+      caret_pos.row + 1
+    } else {
+      caret_pos.row.saturating_sub(row_count)
+    };
+    let first_col = caret_pos
+      .col
+      .saturating_sub(display_width(&context.prefix))
+      .min(box_size.cols.saturating_sub(width));
+
+    let style = Style {
+      color_fg: Some(TWColor::Rgb {
+        r: 220,
+        g: 220,
+        b: 220,
+      }),
+      color_bg: Some(TWColor::Rgb {
+        r: 60,
+        g: 63,
+        b: 65,
+      }),
+      ..Default::default()
+    };
+    let selected_style = Style {
+      color_fg: Some(TWColor::Rgb { r: 0, g: 0, b: 0 }),
+      color_bg: Some(TWColor::Rgb {
+        r: 97,
+        g: 175,
+        b: 239,
+      }),
+      ..Default::default()
+    };
+
+    for (row_index, (index, candidate)) in popup_state
+      .candidates
+      .iter()
+      .enumerate()
+      .skip(popup_state.get_scroll_offset())
+      .take(row_count as usize)
+      .enumerate()
+    {
+      let row = first_row + row_index as UnitType;
+      if row >= box_size.rows {
+        break;
+      }
+
+      // Pad each candidate, so that the popup is a rectangle.
+      let text = format!(" {} ", candidate);
+      let padding = " ".repeat(width.saturating_sub(display_width(&text)) as usize);
+      let text = clip_to_display_cols(&format!("{}{}", text, padding), 0, width);
+      let style = if index == popup_state.selected_index {
+        selected_style.clone()
+      } else {
+        style.clone()
+      };
+
+      tw_command_queue! {
+        queue push
+        TWCommand::MoveCursorPositionRelTo(current_box.origin_pos, position!(col: first_col, row: row)),
+        TWCommand::PrintWithAttributes(text, Some(style)),
+        TWCommand::ResetColor
+      };
+    }
+  }
+}
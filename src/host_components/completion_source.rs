@@ -0,0 +1,53 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use super::*;
+
+/// Where the candidates for a [CompletionPopupComponent] come from. Sources can be slow (eg: they
+/// might read files, or ask a language server), so they are async.
+#[async_trait]
+pub trait CompletionSource: Send + Sync {
+  /// The candidates that complete the prefix in the `context`. Each one starts w/ the prefix (the
+  /// ones that don't are left out).
+  async fn get_candidates(&self, context: &CompletionContext) -> CommonResult<Vec<String>>;
+}
+
+pub type SharedCompletionSource = Arc<dyn CompletionSource>;
+
+/// Completes the prefix w/ the other words (runs of alphanumeric or `_` chars) in the text of the
+/// input, eg: the identifiers in a buffer.
+#[derive(Debug, Clone, Default)]
+pub struct TextWordsSource;
+
+#[async_trait]
+impl CompletionSource for TextWordsSource {
+  async fn get_candidates(&self, context: &CompletionContext) -> CommonResult<Vec<String>> {
+    throws_with_return!({
+      let words: BTreeSet<&str> = context
+        .text
+        .split(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+        .filter(|word| word.starts_with(&context.prefix) && *word != context.prefix)
+        .collect();
+      words.into_iter().map(String::from).collect::<Vec<_>>()
+    });
+  }
+}
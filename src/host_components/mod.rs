@@ -0,0 +1,33 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Components that any of the apps can embed in a box of their own, w/ the state that they need
+//! kept in the component (rather than in the app's store).
+
+// Attach sources.
+pub mod box_rows;
+pub mod chooser_component;
+pub mod chooser_state;
+pub mod completion_popup_component;
+pub mod completion_source;
+
+// Re-export.
+pub use box_rows::*;
+pub use chooser_component::*;
+pub use chooser_state::*;
+pub use completion_popup_component::*;
+pub use completion_source::*;
@@ -23,66 +23,270 @@
 use r3bl_rs_utils::*;
 
 // Attach sources.
+mod cli;
+mod common;
+mod discovery;
+mod ex_address_book;
 mod ex_app_no_layout;
 mod ex_app_with_layout;
+mod ex_discovery;
 mod ex_editor;
 mod ex_lolcat;
+mod ex_multiplexer;
+mod ex_router;
+mod host_components;
+mod pairing;
+mod picker;
 
 // Use things from sources.
 use ex_app_no_layout::*;
 use ex_app_with_layout::*;
+use picker::AppInfo;
 use reedline::*;
 
-const HELP_MSG: &str = "\
-Type a number to run corresponding example:
-  1. App with no layout ❌
-  2. App with layout ✅
-  3. lolcat 🦜
-  4. Text editor 📜
-or type Ctrl+C / Ctrl+D / 'x' to exit";
+/// The apps that can be picked (& run) from the picker.
+const APPS: &[AppInfo] = &[
+  AppInfo {
+    name: "no_layout",
+    title: "App with no layout ❌",
+    description: "Paints the state (a stack of numbers, which the Up & Down keys change) w/ \
+                  lolcat colors, w/out any layout.",
+    args_usage: "",
+    maybe_args_prompt: None,
+    is_full_screen: true,
+    run: |_| Box::pin(ex_app_no_layout::run_app()),
+  },
+  AppInfo {
+    name: "layout",
+    title: "App with layout ✅",
+    description: "Two columns (laid out like Flexbox & styled like CSS) that show the state. The \
+                  Left & Right keys move the focus between them.",
+    args_usage: "",
+    maybe_args_prompt: None,
+    is_full_screen: true,
+    run: |_| Box::pin(ex_app_with_layout::run_app()),
+  },
+  AppInfo {
+    name: "lolcat",
+    title: "lolcat 🦜",
+    description: "Prints the files that are passed in (or stdin) in rainbow colors, eg: `ls | \
+                  r3bl-cmdr lolcat`. A '-' stands for stdin.",
+    args_usage: "[FILE]...",
+    maybe_args_prompt: Some("Files to print (eg: Cargo.toml)"),
+    is_full_screen: false,
+    run: |args| Box::pin(ex_lolcat::run_app(args)),
+  },
+  AppInfo {
+    name: "editor",
+    title: "Text editor 📜",
+    description: "Two editors side by side, w/ syntax highlighting, search & replace, multiple \
+                  carets, undo & word completion.\n\n\
+                  The file that is passed on the command line (if any) is loaded into the left \
+                  one (eg: `r3bl-cmdr editor README.md`).",
+    args_usage: "[FILE]",
+    maybe_args_prompt: None,
+    is_full_screen: true,
+    run: |args| Box::pin(ex_editor::run_app(args.first().cloned(), None)),
+  },
+  AppInfo {
+    name: "shared_editor",
+    title: "Shared text editor 👥",
+    description: "Share the left editor w/ another r3bl-cmdr, over TCP or a Unix socket. The \
+                  peers pair w/ a short code that the host shows.\n\n\
+                  Pass 'host [addr] [file]' or 'join <addr>' on the command line, or type them \
+                  in when asked. Host on 0.0.0.0 to be found by peers on the local network.",
+    args_usage: "host [ADDR] [FILE] | join ADDR",
+    maybe_args_prompt: None,
+    is_full_screen: true,
+    run: |args| Box::pin(run_shared_editor_from_args(args)),
+  },
+  AppInfo {
+    name: "peers",
+    title: "Peers on the local network 📡",
+    description: "Lists the other r3bl-cmdr instances on the local network. Choose one to join \
+                  its shared editor.",
+    args_usage: "",
+    maybe_args_prompt: None,
+    is_full_screen: true,
+    run: |_| Box::pin(join_chosen_peer()),
+  },
+  AppInfo {
+    name: "router",
+    title: "All of the above in one window 🔀",
+    description: "The apps w/ & w/out layout, lolcat & the editor in one window, each w/ its own \
+                  store. F1 - F4 switch between them.\n\n\
+                  lolcat shows the file that is passed on the command line (if any).",
+    args_usage: "[FILE]",
+    maybe_args_prompt: None,
+    is_full_screen: true,
+    run: |args| Box::pin(ex_router::run_app(args.first().cloned())),
+  },
+  AppInfo {
+    name: "multiplexer",
+    title: "All of the above in split panes 🪟",
+    description: "Split the window into panes (tmux style, w/ Ctrl+b as the prefix key), each \
+                  running one of the apps.\n\n\
+                  lolcat shows the file that is passed on the command line (if any).",
+    args_usage: "[FILE]",
+    maybe_args_prompt: None,
+    is_full_screen: true,
+    run: |args| Box::pin(ex_multiplexer::run_app(args.first().cloned())),
+  },
+  AppInfo {
+    name: "address_book",
+    title: "Address book 📇",
+    description: "A list of contacts that can be added, edited & deleted. They are saved in the \
+                  app's data dir.",
+    args_usage: "",
+    maybe_args_prompt: None,
+    is_full_screen: true,
+    run: |_| Box::pin(ex_address_book::run_app()),
+  },
+];
 
 #[tokio::main]
 async fn main() -> CommonResult<()> {
   throws!({
-    println!("{}", HELP_MSG);
-    let maybe_user_selection_string = get_user_selection_from_terminal();
-    if let Some(user_selection) = maybe_user_selection_string {
-      run_user_selected_example(user_selection).await?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli_args = match cli::parse_cli_args(&args, APPS) {
+      Ok(cli_args) => cli_args,
+      Err(error) => {
+        eprintln!(
+          "{}\nRun `{} --help` to see the usage.",
+          error,
+          cli::BIN_NAME
+        );
+        std::process::exit(2);
+      }
+    };
+
+    common::init_logging(
+      cli_args.get_log_level(),
+      cli_args
+        .maybe_log_file_path
+        .as_deref()
+        .unwrap_or(common::DEFAULT_LOG_FILE_PATH),
+    )?;
+
+    match cli_args.command {
+      cli::CliCommand::Help => print!("{}", cli::get_help_msg(APPS)),
+      cli::CliCommand::List => print!("{}", cli::get_app_list(APPS)),
+      cli::CliCommand::RunPicker => picker::run_app(APPS).await?,
+      cli::CliCommand::RunApp { app, args } => {
+        if let Err(error) = (app.run)(args).await {
+          eprintln!("{}: {}: {}", cli::BIN_NAME, app.name, error);
+          std::process::exit(1);
+        }
+      }
     }
   })
 }
 
-/// This is a single threaded blocking function. The R3BL examples are all async and non-blocking.
-fn get_user_selection_from_terminal() -> Option<String> {
-  let mut line_editor = Reedline::create();
-  let prompt = DefaultPrompt::default();
+/// Choose a peer on the local network, & join its shared editor.
+async fn join_chosen_peer() -> CommonResult<()> {
+  throws!({
+    if let Some(peer) = ex_discovery::run_app().await? {
+      join_shared_editor(&peer).await?
+    }
+  });
+}
 
-  loop {
-    let maybe_signal = &line_editor.read_line(&prompt);
-    if let Ok(Signal::Success(user_input_str)) = maybe_signal {
-      match user_input_str.as_str() {
-        code @ ("1" | "2" | "3" | "4") => return Some(code.into()),
-        "x" => break,
-        _ => println!("Unknown command: {}", user_input_str),
+/// Host or join a shared editor, w/ the args that are passed on the command line. If there are
+/// none (eg: when it is run from the picker), then they are typed in.
+async fn run_shared_editor_from_args(args: Vec<String>) -> CommonResult<()> {
+  throws!({
+    if args.is_empty() {
+      return run_shared_editor_from_terminal().await;
+    }
+    match parse_collab_args(&args.join(" ")) {
+      Some((collab_mode, maybe_file_path)) => {
+        run_shared_editor(maybe_file_path, collab_mode).await?
       }
-    } else if let Ok(Signal::CtrlC) | Ok(Signal::CtrlD) = maybe_signal {
-      break;
+      None => return Err("Usage: host [addr] [file] | join <addr>".into()),
     }
-  }
+  });
+}
 
-  None
+/// Host or join a shared editor, w/ the args that are typed in.
+async fn run_shared_editor_from_terminal() -> CommonResult<()> {
+  throws!({
+    println!("Type 'host [addr] [file]' or 'join <addr>':");
+    let mut line_editor = Reedline::create();
+    let prompt = DefaultPrompt::default();
+
+    while let Ok(Signal::Success(user_input_str)) = line_editor.read_line(&prompt) {
+      match parse_collab_args(&user_input_str) {
+        Some((collab_mode, maybe_file_path)) => {
+          return run_shared_editor(maybe_file_path, collab_mode).await;
+        }
+        None => println!("Usage: host [addr] [file] | join <addr>"),
+      }
+    }
+  });
 }
 
-async fn run_user_selected_example(selection: String) -> CommonResult<()> {
+async fn join_shared_editor(peer: &discovery::PeerInfo) -> CommonResult<()> {
   throws!({
-    if !selection.is_empty() {
-      match selection.as_ref() {
-        "1" => throws!(ex_app_no_layout::run_app().await?),
-        "2" => throws!(ex_app_with_layout::run_app().await?),
-        "3" => throws!(ex_lolcat::run_app().await?),
-        "4" => todo!("TODO: implement editor ex!"),
-        _ => unimplemented!(),
+    match peer.get_shared_app(ex_editor::COLLAB_SHARED_APP_NAME) {
+      Some(shared_app) => {
+        let collab_mode =
+          ex_editor::CollabMode::Join(ex_editor::CollabAddr::parse(&shared_app.addr));
+        run_shared_editor(None, collab_mode).await?
       }
+      None => println!("{} isn't sharing an editor", peer),
     }
-  })
+  });
+}
+
+/// The host makes up a pairing code (which it shows while it waits for a peer), & the peer that
+/// joins has to type it in.
+async fn run_shared_editor(
+  maybe_file_path: Option<String>, collab_mode: ex_editor::CollabMode,
+) -> CommonResult<()> {
+  throws!({
+    let maybe_pairing_code = match collab_mode {
+      ex_editor::CollabMode::Host(_) => Some(pairing::PairingCode::generate()?),
+      ex_editor::CollabMode::Join(_) => read_pairing_code_from_terminal(),
+    };
+    if let Some(pairing_code) = maybe_pairing_code {
+      ex_editor::run_app(maybe_file_path, Some((collab_mode, pairing_code))).await?
+    }
+  });
+}
+
+/// This is a single threaded blocking function (like [run_shared_editor_from_terminal]).
+fn read_pairing_code_from_terminal() -> Option<pairing::PairingCode> {
+  println!("Type the pairing code that is shown on the host's screen:");
+  let mut line_editor = Reedline::create();
+  let prompt = DefaultPrompt::default();
+
+  loop {
+    match line_editor.read_line(&prompt) {
+      Ok(Signal::Success(user_input_str)) => match pairing::PairingCode::parse(&user_input_str) {
+        Ok(pairing_code) => return Some(pairing_code),
+        Err(error) => println!("{}", error),
+      },
+      _ => return None,
+    }
+  }
+}
+
+/// Parse `host [addr] [file]` or `join <addr>`. The host listens on localhost by default (& isn't
+/// announced on the local network, unless it listens on another address, eg: `0.0.0.0`).
+fn parse_collab_args(args: &str) -> Option<(ex_editor::CollabMode, Option<String>)> {
+  let mut words = args.split_whitespace();
+  match (words.next(), words.next()) {
+    (Some("host"), maybe_addr) => {
+      let addr = ex_editor::CollabAddr::parse(maybe_addr.unwrap_or("127.0.0.1"));
+      Some((
+        ex_editor::CollabMode::Host(addr),
+        words.next().map(String::from),
+      ))
+    }
+    (Some("join"), Some(addr)) => Some((
+      ex_editor::CollabMode::Join(ex_editor::CollabAddr::parse(addr)),
+      None,
+    )),
+    _ => None,
+  }
 }
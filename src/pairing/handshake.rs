@@ -0,0 +1,303 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT,
+                       ristretto::{CompressedRistretto, RistrettoPoint},
+                       scalar::Scalar,
+                       traits::IsIdentity};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use r3bl_rs_utils::*;
+use sha2::{Digest, Sha256, Sha512};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::*;
+
+/// Sent at the start of the handshake, so that something that isn't a `r3bl-cmdr` peer (or that
+/// speaks a different version of the handshake) is rejected right away.
+const HANDSHAKE_MAGIC: &[u8; 8] = b"r3blpr01";
+const SPAKE2_M_SEED: &[u8] = b"r3bl-cmdr pairing SPAKE2 M";
+const SPAKE2_N_SEED: &[u8] = b"r3bl-cmdr pairing SPAKE2 N";
+const KEY_INFO: &[u8] = b"r3bl-cmdr pairing keys";
+
+/// Each side of the handshake blinds its share w/ a different point, so the 2 sides must have
+/// different roles. The host is the one that shows the code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PairingRole {
+  Host,
+  Guest,
+}
+
+/// Run the pairing handshake over the `stream`. If the peer has the same `pairing_code`, returns a
+/// [SecureChannel] to it. Otherwise (or if the peer isn't running the handshake at all) returns an
+/// error, & the `stream` should be closed.
+pub async fn pair<S: AsyncRead + AsyncWrite + Unpin>(
+  mut stream: S, role: PairingRole, pairing_code: &PairingCode,
+) -> CommonResult<SecureChannel<S>> {
+  throws_with_return!({
+    // SPAKE2: each side sends x*G + w*M (or N), where w is derived from the code.
+    let password_scalar = Scalar::hash_from_bytes::<Sha512>(pairing_code.as_bytes());
+    let secret_scalar = generate_random_scalar()?;
+    let (own_blind, peer_blind) = match role {
+      PairingRole::Host => (
+        get_blinding_point(SPAKE2_M_SEED),
+        get_blinding_point(SPAKE2_N_SEED),
+      ),
+      PairingRole::Guest => (
+        get_blinding_point(SPAKE2_N_SEED),
+        get_blinding_point(SPAKE2_M_SEED),
+      ),
+    };
+    let own_share =
+      (secret_scalar * RISTRETTO_BASEPOINT_POINT + password_scalar * own_blind).compress();
+
+    stream.write_all(HANDSHAKE_MAGIC).await?;
+    stream.write_all(own_share.as_bytes()).await?;
+    stream.flush().await?;
+
+    let mut magic = [0; 8];
+    stream.read_exact(&mut magic).await?;
+    if &magic != HANDSHAKE_MAGIC {
+      return Err("The peer isn't running the pairing handshake".into());
+    }
+    let mut peer_share_bytes = [0; 32];
+    stream.read_exact(&mut peer_share_bytes).await?;
+    let peer_share = CompressedRistretto(peer_share_bytes);
+    let peer_point = peer_share
+      .decompress()
+      .filter(|point| !point.is_identity())
+      .ok_or("The peer sent an invalid share")?;
+    let shared_point = secret_scalar * (peer_point - password_scalar * peer_blind);
+
+    // Both sides hash the same transcript (in host, guest order), which only matches if they
+    // used the same code.
+    let (host_share, guest_share) = match role {
+      PairingRole::Host => (own_share, peer_share),
+      PairingRole::Guest => (peer_share, own_share),
+    };
+    let transcript_hash = Sha256::new()
+      .chain_update(HANDSHAKE_MAGIC)
+      .chain_update(host_share.as_bytes())
+      .chain_update(guest_share.as_bytes())
+      .chain_update(shared_point.compress().as_bytes())
+      .chain_update(password_scalar.as_bytes())
+      .finalize();
+    let keys = SessionKeys::derive(&transcript_hash)?;
+
+    // Key confirmation: each side proves that it derived the same keys.
+    let (own_confirm_key, peer_confirm_key, send_key, recv_key) = match role {
+      PairingRole::Host => (
+        &keys.host_confirm_key,
+        &keys.guest_confirm_key,
+        &keys.host_to_guest_key,
+        &keys.guest_to_host_key,
+      ),
+      PairingRole::Guest => (
+        &keys.guest_confirm_key,
+        &keys.host_confirm_key,
+        &keys.guest_to_host_key,
+        &keys.host_to_guest_key,
+      ),
+    };
+    let own_confirmation = create_mac(own_confirm_key, &transcript_hash)?
+      .finalize()
+      .into_bytes();
+    stream.write_all(&own_confirmation).await?;
+    stream.flush().await?;
+
+    let mut peer_confirmation = [0; 32];
+    stream.read_exact(&mut peer_confirmation).await?;
+    create_mac(peer_confirm_key, &transcript_hash)?
+      .verify_slice(&peer_confirmation)
+      .map_err(|_| "Pairing failed, the pairing codes don't match")?;
+
+    SecureChannel::new(stream, send_key, recv_key)
+  });
+}
+
+struct SessionKeys {
+  host_to_guest_key: [u8; 32],
+  guest_to_host_key: [u8; 32],
+  host_confirm_key: [u8; 32],
+  guest_confirm_key: [u8; 32],
+}
+
+impl SessionKeys {
+  fn derive(transcript_hash: &[u8]) -> CommonResult<Self> {
+    throws_with_return!({
+      let mut okm = [0; 128];
+      Hkdf::<Sha256>::new(None, transcript_hash)
+        .expand(KEY_INFO, &mut okm)
+        .map_err(|_| "Could not derive the session keys")?;
+      let get_key = |index: usize| -> [u8; 32] {
+        let mut key = [0; 32];
+        key.copy_from_slice(&okm[index * 32..(index + 1) * 32]);
+        key
+      };
+      Self {
+        host_to_guest_key: get_key(0),
+        guest_to_host_key: get_key(1),
+        host_confirm_key: get_key(2),
+        guest_confirm_key: get_key(3),
+      }
+    });
+  }
+}
+
+fn create_mac(key: &[u8; 32], transcript_hash: &[u8]) -> CommonResult<Hmac<Sha256>> {
+  throws_with_return!({
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).map_err(|_| "Invalid MAC key")?;
+    mac.update(transcript_hash);
+    mac
+  });
+}
+
+/// A point that nobody knows the discrete log of (since it is hashed from a fixed seed).
+fn get_blinding_point(seed: &[u8]) -> RistrettoPoint {
+  RistrettoPoint::hash_from_bytes::<Sha512>(seed)
+}
+
+fn generate_random_scalar() -> CommonResult<Scalar> {
+  throws_with_return!({
+    let mut bytes = [0; 64];
+    getrandom::getrandom(&mut bytes)?;
+    Scalar::from_bytes_mod_order_wide(&bytes)
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::io::{duplex, DuplexStream};
+
+  use super::*;
+
+  async fn pair_over_duplex(
+    host_code: &str, guest_code: &str,
+  ) -> (
+    CommonResult<SecureChannel<DuplexStream>>,
+    CommonResult<SecureChannel<DuplexStream>>,
+  ) {
+    let (host_stream, guest_stream) = duplex(1024);
+    let host_code = PairingCode::parse(host_code).unwrap();
+    let guest_code = PairingCode::parse(guest_code).unwrap();
+    tokio::join!(
+      pair(host_stream, PairingRole::Host, &host_code),
+      pair(guest_stream, PairingRole::Guest, &guest_code)
+    )
+  }
+
+  #[tokio::test]
+  async fn test_peers_w_the_same_code_are_paired() {
+    let (host, guest) = pair_over_duplex("123-456", "123 456").await;
+    let (mut host, mut guest) = (host.unwrap(), guest.unwrap());
+
+    host.writer.write_frame(b"hello guest").await.unwrap();
+    guest.writer.write_frame(b"hello host").await.unwrap();
+    host.writer.write_frame(b"bye").await.unwrap();
+    assert_eq!(
+      guest.reader.read_frame().await.unwrap().unwrap(),
+      b"hello guest"
+    );
+    assert_eq!(guest.reader.read_frame().await.unwrap().unwrap(), b"bye");
+    assert_eq!(
+      host.reader.read_frame().await.unwrap().unwrap(),
+      b"hello host"
+    );
+
+    drop(host);
+    assert_eq!(guest.reader.read_frame().await.unwrap(), None);
+  }
+
+  #[tokio::test]
+  async fn test_peers_w_different_codes_are_rejected() {
+    let (host, guest) = pair_over_duplex("123456", "123457").await;
+    assert!(host.is_err());
+    assert!(guest.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_peer_that_skips_the_handshake_is_rejected() {
+    let (host_stream, mut intruder_stream) = duplex(1024);
+    let pairing_code = PairingCode::generate().unwrap();
+    let (result, _) = tokio::join!(pair(host_stream, PairingRole::Host, &pairing_code), async {
+      intruder_stream
+        .write_all(b"{\"Hello\":{\"site_id\":1,\"user_name\":\"mallory\"}}\n")
+        .await
+        .unwrap();
+      intruder_stream.shutdown().await.unwrap();
+    });
+    assert!(result.is_err());
+  }
+
+  async fn relay_handshake<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: &mut R, writer: &mut W,
+  ) {
+    for len in [HANDSHAKE_MAGIC.len() + 32, 32] {
+      let mut bytes = vec![0; len];
+      reader.read_exact(&mut bytes).await.unwrap();
+      writer.write_all(&bytes).await.unwrap();
+    }
+  }
+
+  #[tokio::test]
+  async fn test_traffic_is_encrypted_and_tampering_is_detected() {
+    let (host_stream, mut wire) = duplex(4096);
+    let (relay_stream, guest_stream) = duplex(4096);
+    let pairing_code = PairingCode::generate().unwrap();
+
+    // Relay the handshake (the shares, then the confirmations) between the host & the guest,
+    // w/out touching it.
+    let (mut relay_reader, mut relay_writer) = tokio::io::split(relay_stream);
+    let (mut wire_reader, mut wire_writer) = tokio::io::split(&mut wire);
+    let ((host, guest), _, _) = tokio::join!(
+      async {
+        tokio::join!(
+          pair(host_stream, PairingRole::Host, &pairing_code),
+          pair(guest_stream, PairingRole::Guest, &pairing_code)
+        )
+      },
+      relay_handshake(&mut wire_reader, &mut relay_writer),
+      relay_handshake(&mut relay_reader, &mut wire_writer)
+    );
+    let (mut host, mut guest) = (host.unwrap(), guest.unwrap());
+
+    // The frame on the wire doesn't contain the plaintext.
+    host.writer.write_frame(b"secret text").await.unwrap();
+    let mut frame = vec![0; 4 + b"secret text".len() + 16];
+    wire_reader.read_exact(&mut frame).await.unwrap();
+    assert!(!frame.windows(6).any(|window| window == b"secret"));
+
+    // Flip a bit of the ciphertext, & pass it on to the guest.
+    frame[4] ^= 1;
+    relay_writer.write_all(&frame).await.unwrap();
+    assert!(guest.reader.read_frame().await.is_err());
+  }
+
+  #[test]
+  fn test_pairing_code_is_parsed_and_displayed() {
+    assert_eq!(
+      PairingCode::parse("042 917").unwrap().to_string(),
+      "042-917"
+    );
+    assert!(PairingCode::parse("12345").is_err());
+    assert!(PairingCode::parse("12345x").is_err());
+    assert_eq!(
+      PairingCode::generate().unwrap().to_string().len(),
+      PAIRING_CODE_DIGIT_COUNT + 1
+    );
+  }
+}
@@ -0,0 +1,33 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Pairing of 2 peers (eg: in a collab session) w/ a short [PairingCode], which is shown on one
+//! screen & typed on the other. The code is never sent over the network. Instead, the peers run a
+//! password authenticated key exchange (SPAKE2 over Ristretto255) w/ it, which derives a session
+//! key that only peers w/ the same code end up sharing, & then they prove to each other that they
+//! have it. All the traffic after that goes over a [SecureChannel] (ChaCha20-Poly1305). A peer
+//! that doesn't know the code learns nothing that lets it guess the code offline, & is rejected.
+
+// Attach sources.
+pub mod handshake;
+pub mod pairing_code;
+pub mod secure_channel;
+
+// Re-export.
+pub use handshake::*;
+pub use pairing_code::*;
+pub use secure_channel::*;
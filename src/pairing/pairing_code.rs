@@ -0,0 +1,77 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Debug, Display, Formatter};
+
+use r3bl_rs_utils::*;
+
+pub const PAIRING_CODE_DIGIT_COUNT: usize = 6;
+
+/// A short code of [PAIRING_CODE_DIGIT_COUNT] digits, which is displayed as `123-456`. It is
+/// hidden in debug output (eg: in logs).
+#[derive(Clone, PartialEq, Eq)]
+pub struct PairingCode {
+  digits: String,
+}
+
+impl PairingCode {
+  /// A new random code.
+  pub fn generate() -> CommonResult<Self> {
+    throws_with_return!({
+      let modulus = 10_u32.pow(PAIRING_CODE_DIGIT_COUNT as u32);
+      // Numbers in the incomplete range at the top of u32 are rejected, so that each code is
+      // equally likely.
+      let limit = u32::MAX - u32::MAX % modulus;
+      let number = loop {
+        let mut bytes = [0; 4];
+        getrandom::getrandom(&mut bytes)?;
+        let number = u32::from_be_bytes(bytes);
+        if number < limit {
+          break number % modulus;
+        }
+      };
+      Self {
+        digits: format!("{:0width$}", number, width = PAIRING_CODE_DIGIT_COUNT),
+      }
+    });
+  }
+
+  /// Spaces & dashes (eg: in `123-456` or `123 456`) are ignored.
+  pub fn parse(text: &str) -> CommonResult<Self> {
+    let digits: String = text
+      .chars()
+      .filter(|ch| !ch.is_whitespace() && *ch != '-')
+      .collect();
+    if digits.len() != PAIRING_CODE_DIGIT_COUNT || !digits.chars().all(|ch| ch.is_ascii_digit()) {
+      return Err(format!("A pairing code has {} digits", PAIRING_CODE_DIGIT_COUNT).into());
+    }
+    Ok(Self { digits })
+  }
+
+  pub fn as_bytes(&self) -> &[u8] { self.digits.as_bytes() }
+}
+
+impl Display for PairingCode {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let (first_half, second_half) = self.digits.split_at(PAIRING_CODE_DIGIT_COUNT / 2);
+    write!(f, "{}-{}", first_half, second_half)
+  }
+}
+
+impl Debug for PairingCode {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "PairingCode(***)") }
+}
@@ -0,0 +1,121 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::io::ErrorKind;
+
+use chacha20poly1305::{aead::{Aead, KeyInit},
+                       ChaCha20Poly1305,
+                       Key,
+                       Nonce};
+use r3bl_rs_utils::*;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
+
+/// Frames that are bigger than this are rejected (instead of allocating a buffer for them).
+pub const MAX_FRAME_SIZE: usize = 1024 * 1024;
+
+/// A stream (that a [crate::pairing::pair] handshake has been run over) w/ a key for each
+/// direction. Each frame is sent as its length (4 bytes, big endian) followed by its ciphertext.
+/// The nonce of a frame is the number of frames that were sent before it, so frames that are
+/// tampered w/, replayed, reordered or dropped fail to decrypt.
+pub struct SecureChannel<S> {
+  pub reader: SecureReader<ReadHalf<S>>,
+  pub writer: SecureWriter<WriteHalf<S>>,
+}
+
+impl<S: AsyncRead + AsyncWrite> SecureChannel<S> {
+  pub fn new(stream: S, send_key: &[u8; 32], recv_key: &[u8; 32]) -> Self {
+    let (reader, writer) = tokio::io::split(stream);
+    Self {
+      reader: SecureReader {
+        reader,
+        cipher: ChaCha20Poly1305::new(Key::from_slice(recv_key)),
+        frame_count: 0,
+      },
+      writer: SecureWriter {
+        writer,
+        cipher: ChaCha20Poly1305::new(Key::from_slice(send_key)),
+        frame_count: 0,
+      },
+    }
+  }
+}
+
+pub struct SecureWriter<W> {
+  writer: W,
+  cipher: ChaCha20Poly1305,
+  frame_count: u64,
+}
+
+impl<W: AsyncWrite + Unpin> SecureWriter<W> {
+  pub async fn write_frame(&mut self, plaintext: &[u8]) -> CommonResult<()> {
+    throws!({
+      let ciphertext = self
+        .cipher
+        .encrypt(&get_nonce(self.frame_count), plaintext)
+        .map_err(|_| "Could not encrypt the frame")?;
+      if ciphertext.len() > MAX_FRAME_SIZE {
+        return Err("The frame is too big".into());
+      }
+      self.frame_count += 1;
+      self
+        .writer
+        .write_all(&(ciphertext.len() as u32).to_be_bytes())
+        .await?;
+      self.writer.write_all(&ciphertext).await?;
+      self.writer.flush().await?;
+    });
+  }
+}
+
+pub struct SecureReader<R> {
+  reader: R,
+  cipher: ChaCha20Poly1305,
+  frame_count: u64,
+}
+
+impl<R: AsyncRead + Unpin> SecureReader<R> {
+  /// Returns [None] when the other peer has closed the connection (between frames).
+  pub async fn read_frame(&mut self) -> CommonResult<Option<Vec<u8>>> {
+    throws_with_return!({
+      let mut len_bytes = [0; 4];
+      match self.reader.read_exact(&mut len_bytes).await {
+        Ok(_) => {}
+        Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error.into()),
+      }
+      let len = u32::from_be_bytes(len_bytes) as usize;
+      if len > MAX_FRAME_SIZE {
+        return Err("The frame is too big".into());
+      }
+
+      let mut ciphertext = vec![0; len];
+      self.reader.read_exact(&mut ciphertext).await?;
+      let plaintext = self
+        .cipher
+        .decrypt(&get_nonce(self.frame_count), ciphertext.as_slice())
+        .map_err(|_| "The frame could not be decrypted (it was not sent by the paired peer)")?;
+      self.frame_count += 1;
+      Some(plaintext)
+    });
+  }
+}
+
+fn get_nonce(frame_count: u64) -> Nonce {
+  let mut nonce = [0; 12];
+  nonce[4..].copy_from_slice(&frame_count.to_be_bytes());
+  *Nonce::from_slice(&nonce)
+}
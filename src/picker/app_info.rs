@@ -0,0 +1,60 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{fmt::{Debug, Formatter},
+          future::Future,
+          pin::Pin};
+
+use r3bl_rs_utils::*;
+
+pub type RunAppFuture = Pin<Box<dyn Future<Output = CommonResult<()>>>>;
+
+/// Runs an app (until the user exits it), w/ the args that are passed to it on the command line.
+/// The picker only passes the ones that it asks for (see [AppInfo::maybe_args_prompt]).
+pub type RunAppFn = fn(Vec<String>) -> RunAppFuture;
+
+/// An app that can be picked (& run) from the [AppPicker], or run directly from the command line
+/// (see [crate::cli]).
+#[derive(Clone, Copy)]
+pub struct AppInfo {
+  /// Short name w/out spaces (eg: `editor`).
+  pub name: &'static str,
+  pub title: &'static str,
+  pub description: &'static str,
+  /// The args that the app takes on the command line (eg: `[FILE]`), shown in the help message.
+  /// Empty if it doesn't take any.
+  pub args_usage: &'static str,
+  /// Apps that need args (eg: lolcat, which has nothing to print w/out a file) are asked for them
+  /// w/ this prompt when they are picked. The answer is split on whitespace.
+  pub maybe_args_prompt: Option<&'static str>,
+  /// Apps that aren't full screen print to the terminal, so the picker waits for Enter to be
+  /// pressed (before it clears the screen) when they exit.
+  pub is_full_screen: bool,
+  pub run: RunAppFn,
+}
+
+impl Debug for AppInfo {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("AppInfo")
+      .field("name", &self.name)
+      .field("title", &self.title)
+      .field("args_usage", &self.args_usage)
+      .field("maybe_args_prompt", &self.maybe_args_prompt)
+      .field("is_full_screen", &self.is_full_screen)
+      .finish()
+  }
+}
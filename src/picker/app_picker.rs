@@ -0,0 +1,290 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+use tokio::sync::RwLock;
+
+use super::*;
+use crate::{common::*, host_components::*};
+
+// Constants for the ids.
+const APP_CHOOSER_ID: &str = "app_chooser";
+
+/// Async trait object that implements the [TWApp] trait. Lists the `apps` in a [ChooserComponent]
+/// (so they can be filtered by typing), w/ the description of the one under the cursor next to
+/// them, & lets one of them be picked.
+///
+/// Enter is one of the exit keys of the event loop. When it is pressed, the app under the cursor is
+/// noted as the `maybe_picked_app_index` & the event is propagated (instead of being passed to the
+/// chooser), which exits the event loop (so that the picked app can be run).
+pub struct AppPicker {
+  pub apps: &'static [AppInfo],
+  pub component_registry: ComponentRegistry<PickerState, PickerAction>,
+  pub has_focus: HasFocus,
+  /// The chooser in the registry, which the app under the cursor is taken from.
+  pub app_chooser: Arc<RwLock<ChooserComponent<PickerState, PickerAction>>>,
+  pub maybe_picked_app_index: Option<usize>,
+}
+
+impl AppPicker {
+  /// The cursor starts on the app at `selected_index`.
+  pub fn new(apps: &'static [AppInfo], selected_index: usize) -> Self {
+    let mut app_chooser = ChooserComponent::new(
+      ChooserMode::SingleSelect,
+      chooser_helpers::get_app_items_from_state,
+      chooser_helpers::confirm_app,
+    );
+    app_chooser.chooser_state.set_items(get_app_items(apps));
+    app_chooser.chooser_state.cursor_index = selected_index.min(apps.len().saturating_sub(1));
+
+    let app_chooser = Arc::new(RwLock::new(app_chooser));
+    let mut component_registry = ComponentRegistry::default();
+    component_registry.put(APP_CHOOSER_ID, app_chooser.clone());
+    let mut has_focus = HasFocus::default();
+    has_focus.set_id(APP_CHOOSER_ID);
+
+    Self {
+      apps,
+      component_registry,
+      has_focus,
+      app_chooser,
+      maybe_picked_app_index: None,
+    }
+  }
+}
+
+#[async_trait]
+impl TWApp<PickerState, PickerAction> for AppPicker {
+  async fn app_render(
+    &mut self, state: &PickerState, shared_store: &SharedStore<PickerState, PickerAction>,
+    window_size: Size,
+  ) -> CommonResult<TWCommandQueue> {
+    throws_with_return!({
+      // Leave the row at the top for the title, & the one at the bottom for the status bar.
+      let mut surface = surface_start! {
+        stylesheet: Stylesheet::default(),
+        pos: (0, 1).into(),
+        size: (window_size.cols, window_size.rows.saturating_sub(2)).into(),
+      };
+
+      make_box! {
+        in:     surface,
+        id:     APP_CHOOSER_ID,
+        dir:    Direction::Vertical,
+        size:   (render_helpers::LIST_WIDTH_PERCENT, 100).try_into()?,
+        styles: [],
+        render: {
+          from:         self.component_registry,
+          has_focus:    self.has_focus,
+          state:        state,
+          shared_store: shared_store
+        }
+      }
+
+      surface.surface_end()?;
+
+      let mut queue = tw_command_queue!(TWCommand::ClearScreen, TWCommand::ResetColor);
+      render_helpers::render_title(&mut queue);
+      queue += surface.render_buffer;
+      if let Some(app) = self.get_cursor_app().await {
+        render_helpers::render_app_description(&mut queue, window_size, app);
+      }
+      status_bar_helpers::render(&mut queue, window_size, state);
+
+      call_if_true!(is_logging_enabled(), {
+        log_no_err!(
+          INFO,
+          "🚀 AppPicker::render -> size, state: {} {}",
+          window_size,
+          state
+        );
+      });
+      queue
+    });
+  }
+
+  /// The status message (if any) is cleared by the 1st key that is pressed.
+  async fn app_handle_event(
+    &mut self, input_event: &TWInputEvent, state: &PickerState,
+    shared_store: &SharedStore<PickerState, PickerAction>, _terminal_size: Size,
+  ) -> CommonResult<EventPropagation> {
+    throws_with_return!({
+      if state.maybe_status_message.is_some() {
+        let shared_store = shared_store.clone();
+        tokio::spawn(async move {
+          shared_store
+            .write()
+            .await
+            .dispatch_action(PickerAction::ClearStatusMessage)
+            .await;
+        });
+      }
+
+      if let TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: None,
+        non_modifier_key: NonModifierKey::Special(SpecialKey::Enter),
+      }) = input_event
+      {
+        // Nothing is picked if the filter doesn't match any of the apps.
+        self.maybe_picked_app_index = self
+          .app_chooser
+          .read()
+          .await
+          .chooser_state
+          .get_cursor_item()
+          .map(|chosen_item| chosen_item.index);
+        return Ok(match self.maybe_picked_app_index {
+          Some(_) => EventPropagation::Propagate,
+          None => EventPropagation::Consumed,
+        });
+      }
+
+      // If component has focus, then route input_event to it. Return its propagation enum.
+      if let Some(shared_component_has_focus) =
+        self.component_registry.get_has_focus(&self.has_focus)
+      {
+        let result_event_propagation = shared_component_has_focus
+          .write()
+          .await
+          .handle_event(input_event, state, shared_store)
+          .await?;
+        return Ok(result_event_propagation);
+      };
+
+      // input_event not handled, propagate it.
+      EventPropagation::Propagate
+    });
+  }
+}
+
+impl AppPicker {
+  async fn get_cursor_app(&self) -> Option<&'static AppInfo> {
+    let chosen_item = self
+      .app_chooser
+      .read()
+      .await
+      .chooser_state
+      .get_cursor_item()?;
+    self.apps.get(chosen_item.index)
+  }
+}
+
+/// One (numbered) item per app, in the same order, so the index of an item is that of its app.
+pub fn get_app_items(apps: &[AppInfo]) -> Vec<ChooserItem> {
+  apps
+    .iter()
+    .enumerate()
+    .map(|(index, app)| ChooserItem {
+      id: app.name.to_string(),
+      text: format!("{:>2}. {}", index + 1, app.title),
+    })
+    .collect()
+}
+
+mod chooser_helpers {
+  use super::*;
+
+  pub fn get_app_items_from_state(state: &PickerState) -> Vec<ChooserItem> {
+    state.app_items.clone()
+  }
+
+  /// Enter never gets to the chooser, since the app handles it (see [AppPicker]).
+  pub fn confirm_app(_chosen_items: Vec<ChosenItem>) -> PickerAction { PickerAction::Noop }
+}
+
+mod render_helpers {
+  use super::*;
+
+  /// How much of the width of the window the list of apps takes up.
+  pub const LIST_WIDTH_PERCENT: i32 = 50;
+  /// The row (of the window) that the 1st app is on, below the title & the filter.
+  const FIRST_APP_ROW: UnitType = 2;
+  const GAP_WIDTH: UnitType = 3;
+
+  pub fn render_title(queue: &mut TWCommandQueue) {
+    *queue += TWCommand::MoveCursorPositionAbs((0, 0).into());
+    *queue += styled_texts! {
+      styled_text! { " Pick an app to run ", style!(attrib: [bold]) }
+    }
+    .render();
+  }
+
+  /// The description of the `app`, wrapped to fit in the cols to the right of the list.
+  pub fn render_app_description(queue: &mut TWCommandQueue, size: Size, app: &AppInfo) {
+    let list_width = size.cols * LIST_WIDTH_PERCENT as UnitType / 100;
+    let first_col = list_width + GAP_WIDTH;
+    let width = size.cols.saturating_sub(first_col);
+    if width == 0 {
+      return;
+    }
+
+    let last_row = size.rows.saturating_sub(1);
+    let mut row = FIRST_APP_ROW;
+    for line in app.description.lines() {
+      for grapheme_range in wrap_line(line, width) {
+        if row >= last_row {
+          return;
+        }
+        let text = line[grapheme_byte_index(line, grapheme_range.start)
+          ..grapheme_byte_index(line, grapheme_range.end)]
+          .to_string();
+        tw_command_queue! {
+          queue push
+          TWCommand::MoveCursorPositionAbs((first_col, row).into()),
+          TWCommand::PrintWithAttributes(text, Some(style!(attrib: [dim]))),
+          TWCommand::ResetColor
+        };
+        row += 1;
+      }
+    }
+  }
+}
+
+mod status_bar_helpers {
+  use super::*;
+
+  /// Shows the status message (if there is one), or helpful messages at the bottom row of the
+  /// screen.
+  pub fn render(queue: &mut TWCommandQueue, size: Size, state: &PickerState) {
+    let st_vec = match &state.maybe_status_message {
+      Some(message) => styled_texts! {
+        styled_text! { message, style!(attrib: [bold]) }
+      },
+      None => styled_texts! {
+        styled_text! { "Hints:",                style!(attrib: [dim])       },
+        styled_text! { " Ctrl+q : Exit ⛔ ",    style!(attrib: [bold])      },
+        styled_text! { " … ",                   style!(attrib: [dim])       },
+        styled_text! { " ↑ / ↓ : select ",      style!(attrib: [underline]) },
+        styled_text! { " … ",                   style!(attrib: [dim])       },
+        styled_text! { " type : filter ",       style!(attrib: [underline]) },
+        styled_text! { " … ",                   style!(attrib: [dim])       },
+        styled_text! { " Enter : run ",         style!(attrib: [underline]) }
+      },
+    };
+
+    let display_width = st_vec.unicode_string().display_width;
+    let col_center: UnitType = (size.cols / 2).saturating_sub(display_width / 2);
+    let row_bottom: UnitType = size.rows.saturating_sub(1);
+    let center: Position = (col_center, row_bottom).into();
+
+    *queue += TWCommand::MoveCursorPositionAbs(center);
+    *queue += st_vec.render();
+  }
+}
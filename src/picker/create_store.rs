@@ -0,0 +1,94 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use r3bl_rs_utils::*;
+
+use crate::host_components::*;
+
+// Create a new store and attach the reducer.
+pub async fn create_store() -> Store<PickerState, PickerAction> {
+  let mut store: Store<PickerState, PickerAction> = Store::default();
+  store.add_reducer(PickerReducer::new()).await;
+  store
+}
+
+/// Action.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+#[allow(dead_code)]
+pub enum PickerAction {
+  /// Start w/ the given apps (one item per app), & the given message in the status bar (eg: why
+  /// the app that was run last failed).
+  Init {
+    app_items: Vec<ChooserItem>,
+    maybe_status_message: Option<String>,
+  },
+  ClearStatusMessage,
+  #[default]
+  Noop,
+}
+
+impl Display for PickerAction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{:?}", self) }
+}
+
+/// State. The apps (in the registry that the picker was given) that are listed. Which of them is
+/// under the cursor is up to the chooser that lists them (see [crate::picker::AppPicker]).
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct PickerState {
+  pub app_items: Vec<ChooserItem>,
+  pub maybe_status_message: Option<String>,
+}
+
+impl Display for PickerState {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "State {{ app_items: {}, maybe_status_message: {:?} }}",
+      self.app_items.len(),
+      self.maybe_status_message
+    )
+  }
+}
+
+/// Reducer.
+#[derive(Default)]
+pub struct PickerReducer;
+
+#[async_trait]
+impl AsyncReducer<PickerState, PickerAction> for PickerReducer {
+  async fn run(&self, action: &PickerAction, state: &PickerState) -> PickerState {
+    let mut new_state = state.clone();
+
+    match action {
+      PickerAction::Init {
+        app_items,
+        maybe_status_message,
+      } => {
+        new_state.app_items = app_items.clone();
+        new_state.maybe_status_message = maybe_status_message.clone();
+      }
+      PickerAction::ClearStatusMessage => new_state.maybe_status_message = None,
+      _ => {}
+    }
+
+    new_state
+  }
+}
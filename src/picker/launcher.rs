@@ -0,0 +1,112 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{io::{stdin, stdout, Write},
+          sync::Arc};
+
+use r3bl_rs_utils::*;
+use tokio::sync::RwLock;
+
+use super::*;
+
+/// Show the picker, & run the app that is picked (after asking for its args, if it needs any).
+/// When that app exits, the picker is shown again (w/ the same app selected), until the picker
+/// itself is exited. If an app fails, then the error is shown in the picker's status bar.
+pub async fn run_app(apps: &'static [AppInfo]) -> CommonResult<()> {
+  throws!({
+    let mut selected_index = 0;
+    let mut maybe_status_message = None;
+
+    while let Some(picked_index) = pick_app(apps, selected_index, maybe_status_message).await? {
+      let app = &apps[picked_index];
+      selected_index = picked_index;
+      let args = match app.maybe_args_prompt {
+        Some(args_prompt) => {
+          print!("{}: ", args_prompt);
+          stdout().flush()?;
+          read_stdin_line()
+            .await?
+            .split_whitespace()
+            .map(String::from)
+            .collect()
+        }
+        None => vec![],
+      };
+      maybe_status_message = match (app.run)(args).await {
+        Ok(_) => None,
+        Err(error) => Some(format!("{} failed: {}", app.title, error)),
+      };
+
+      if !app.is_full_screen {
+        println!("Press Enter to go back to the picker");
+        read_stdin_line().await?;
+      }
+    }
+  });
+}
+
+/// Stdin is read on a blocking thread, so that the runtime isn't blocked while waiting for the user.
+async fn read_stdin_line() -> CommonResult<String> {
+  throws_with_return!({
+    let line = tokio::task::spawn_blocking(|| {
+      let mut line = String::new();
+      stdin().read_line(&mut line).map(|_| line)
+    })
+    .await??;
+    line
+  });
+}
+
+/// Returns the index of the app that was picked, or [None] if the picker was exited.
+async fn pick_app(
+  apps: &'static [AppInfo], selected_index: usize, maybe_status_message: Option<String>,
+) -> CommonResult<Option<usize>> {
+  throws_with_return!({
+    // Create store.
+    let mut store = create_store().await;
+    store
+      .dispatch_action(PickerAction::Init {
+        app_items: get_app_items(apps),
+        maybe_status_message,
+      })
+      .await;
+
+    // Create an App (renders & responds to user input). A handle to it is kept, to get the picked
+    // app from it when it exits.
+    let app = Arc::new(RwLock::new(AppPicker::new(apps, selected_index)));
+    let shared_app: SharedTWApp<PickerState, PickerAction> = app.clone();
+
+    // Exit if these keys are pressed. Enter exits too, once the app has noted which one was picked.
+    // Typed chars (like 'x') go into the filter.
+    let exit_keys: Vec<TWInputEvent> = vec![
+      TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: Some(ModifierKeys::CTRL),
+        non_modifier_key: NonModifierKey::Character('q'),
+      }),
+      TWInputEvent::NonDisplayableKeypress(Keypress {
+        maybe_modifier_keys: None,
+        non_modifier_key: NonModifierKey::Special(SpecialKey::Enter),
+      }),
+    ];
+
+    // Create a window.
+    TerminalWindow::main_event_loop(store, shared_app, exit_keys).await?;
+
+    let maybe_picked_app_index = app.write().await.maybe_picked_app_index.take();
+    maybe_picked_app_index
+  });
+}
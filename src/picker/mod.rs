@@ -0,0 +1,31 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! The picker that the apps are run from. It is given a registry of the apps (see [AppInfo]), & it
+//! comes back when the app that was picked exits.
+
+// Attach sources.
+pub mod app_info;
+pub mod app_picker;
+pub mod create_store;
+pub mod launcher;
+
+// Re-export.
+pub use app_info::*;
+pub use app_picker::*;
+pub use create_store::*;
+pub use launcher::*;